@@ -2,6 +2,7 @@
 
 use tauri::AppHandle;
 use tauri::Emitter;
+use tauri::Runtime;
 use crate::models;
 use crate::modules;
 use crate::error::{AppError, AppResult};
@@ -108,12 +109,20 @@ pub async fn refresh_current_quota(app: tauri::AppHandle) -> Result<(), String>
 /// 切换账号（完整流程：Token刷新 + 关闭程序 + 注入 + 指纹同步 + 重启）
 #[tauri::command]
 pub async fn switch_account(app: AppHandle, account_id: String) -> Result<models::Account, String> {
+    switch_account_with_app(&app, &account_id).await
+}
+
+/// 切换账号的完整流程，供命令与托盘快速切换共用
+pub async fn switch_account_with_app<R: Runtime>(
+    app: &AppHandle<R>,
+    account_id: &str,
+) -> Result<models::Account, String> {
     use std::fs;
     
     modules::logger::log_info(&format!("开始切换账号: {}", account_id));
     
     // 1. 加载并验证账号存在
-    let mut account = modules::load_account(&account_id)?;
+    let mut account = modules::load_account(account_id)?;
     modules::logger::log_info(&format!("正在切换到账号: {} (ID: {})", account.email, account.id));
     
     // 2. 确保 Token 有效（自动刷新过期的 Token）
@@ -174,13 +183,13 @@ pub async fn switch_account(app: AppHandle, account_id: String) -> Result<models
     })?;
     
     // 7. 更新工具内部状态
-    modules::set_current_account_id(&account_id)?;
+    modules::set_current_account_id(account_id)?;
     account.update_last_used();
     modules::save_account(&account)?;
     
     // 8. 同步更新 Antigravity 默认实例的绑定账号（不同步到 Codex，因为账号体系不同）
     if let Err(e) = modules::instance::update_default_settings(
-        Some(Some(account_id.clone())),
+        Some(Some(account_id.to_string())),
         None,
         Some(false),
     ) {
@@ -303,3 +312,21 @@ pub async fn sync_current_from_client() -> Result<Option<String>, String> {
     modules::logger::log_info("[SyncClient] 本地客户端账号未在 Tools 中找到");
     Ok(None)
 }
+
+/// 立即刷新所有平台账号的 Token（失效账号会被禁用）
+#[tauri::command]
+pub async fn refresh_all_tokens(app: AppHandle) -> Result<modules::token_refresher::TokenRefreshSummary, String> {
+    Ok(modules::token_refresher::run_once(Some(&app), true).await)
+}
+
+/// 检查所有平台账号的健康状态（Token 刷新、身份、配额）
+#[tauri::command]
+pub async fn run_account_health_check(app: AppHandle) -> Result<modules::health_check::HealthReport, String> {
+    modules::health_check::run_health_check(Some(&app)).await
+}
+
+/// 获取最近一次账号健康检查结果
+#[tauri::command]
+pub fn get_last_health_report() -> Result<Option<modules::health_check::HealthReport>, String> {
+    Ok(modules::health_check::get_last_report())
+}
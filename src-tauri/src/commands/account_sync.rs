@@ -0,0 +1,40 @@
+use crate::modules::account_sync::{self, AccountSyncReport, AccountSyncStatus};
+use crate::modules::config::{self, UserConfig};
+
+/// 获取账号池同步状态
+#[tauri::command]
+pub fn get_account_sync_status() -> Result<AccountSyncStatus, String> {
+    account_sync::get_status()
+}
+
+/// 设置账号池同步文件夹（传空字符串表示关闭同步）
+#[tauri::command]
+pub fn set_account_sync_folder(folder: String) -> Result<(), String> {
+    let folder = folder.trim().to_string();
+    if !folder.is_empty() && !std::path::Path::new(&folder).is_dir() {
+        return Err(format!("文件夹不存在: {}", folder));
+    }
+    let current = config::get_user_config();
+    config::save_user_config(&UserConfig {
+        account_sync_folder: folder,
+        ..current
+    })
+}
+
+/// 设置同步口令（用于加密同步文件中的 refresh_token，传空字符串表示不同步 Token）
+#[tauri::command]
+pub fn set_account_sync_passphrase(passphrase: String) -> Result<(), String> {
+    let current = config::get_user_config();
+    config::save_user_config(&UserConfig {
+        account_sync_passphrase: passphrase,
+        ..current
+    })
+}
+
+/// 立即与同步文件夹同步
+#[tauri::command]
+pub async fn sync_accounts_now() -> Result<AccountSyncReport, String> {
+    tokio::task::spawn_blocking(account_sync::sync_now)
+        .await
+        .map_err(|e| format!("同步任务失败: {}", e))?
+}
@@ -0,0 +1,85 @@
+use tauri::AppHandle;
+
+use crate::models::api_key::{ApiKeyAccount, ApiKeyUsage};
+use crate::modules::api_key_account::{self, ApiKeySwitchResult};
+use crate::modules::logger;
+
+/// 列出所有 API Key 账号
+#[tauri::command]
+pub fn list_api_key_accounts() -> Result<Vec<ApiKeyAccount>, String> {
+    Ok(api_key_account::list_accounts())
+}
+
+/// 获取当前使用的 API Key 账号
+#[tauri::command]
+pub fn get_current_api_key_account() -> Result<Option<ApiKeyAccount>, String> {
+    Ok(api_key_account::get_current_account())
+}
+
+/// 添加 API Key 账号（添加后尝试查询用量）
+#[tauri::command]
+pub async fn add_api_key_account(
+    label: String,
+    base_url: String,
+    api_key: String,
+    usage_url: Option<String>,
+) -> Result<ApiKeyAccount, String> {
+    let account = api_key_account::add_account(label, base_url, api_key, usage_url)?;
+    if let Err(e) = api_key_account::refresh_usage(&account.id).await {
+        logger::log_warn(&format!("[ApiKey] {} 查询用量失败: {}", account.label, e));
+    }
+    api_key_account::load_account(&account.id).ok_or_else(|| "账号保存后无法读取".to_string())
+}
+
+/// 更新 API Key 账号
+#[tauri::command]
+pub fn update_api_key_account(
+    account_id: String,
+    label: Option<String>,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    usage_url: Option<Option<String>>,
+) -> Result<ApiKeyAccount, String> {
+    api_key_account::update_account(&account_id, label, base_url, api_key, usage_url)
+}
+
+#[tauri::command]
+pub fn update_api_key_account_tags(account_id: String, tags: Vec<String>) -> Result<ApiKeyAccount, String> {
+    api_key_account::update_account_tags(&account_id, tags)
+}
+
+/// 删除 API Key 账号
+#[tauri::command]
+pub fn delete_api_key_accounts(account_ids: Vec<String>) -> Result<(), String> {
+    api_key_account::remove_accounts(&account_ids)
+}
+
+/// 切换到 API Key 账号（写入 Codex 与 OpenCode 凭据，OpenCode 重启在阻塞线程池中执行）
+#[tauri::command]
+pub async fn switch_api_key_account(app: AppHandle, account_id: String) -> Result<ApiKeySwitchResult, String> {
+    let result = tokio::task::spawn_blocking(move || api_key_account::switch_account(&account_id))
+        .await
+        .map_err(|e| format!("切换任务失败: {}", e))??;
+    // Codex auth.json 已被覆盖，托盘中的 Codex 当前账号随之变化
+    let _ = crate::modules::tray::update_tray_menu(&app);
+    Ok(result)
+}
+
+/// 刷新单个 API Key 的用量
+#[tauri::command]
+pub async fn refresh_api_key_usage(account_id: String) -> Result<ApiKeyUsage, String> {
+    api_key_account::refresh_usage(&account_id).await
+}
+
+/// 刷新所有 API Key 的用量，返回成功数量
+#[tauri::command]
+pub async fn refresh_all_api_key_usages() -> Result<i32, String> {
+    let mut success_count = 0;
+    for account in api_key_account::list_accounts() {
+        match api_key_account::refresh_usage(&account.id).await {
+            Ok(_) => success_count += 1,
+            Err(e) => logger::log_warn(&format!("[ApiKey] {} 查询用量失败: {}", account.label, e)),
+        }
+    }
+    Ok(success_count)
+}
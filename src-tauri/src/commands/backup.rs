@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use crate::modules::backup::{self, BackupRestorePreview, BackupRestoreResult, BackupSummary, RestoreMode};
+
+/// 备份整个数据目录（可选密码加密）
+#[tauri::command]
+pub async fn create_backup(path: String, password: Option<String>) -> Result<BackupSummary, String> {
+    tokio::task::spawn_blocking(move || backup::create_backup(&PathBuf::from(path), password.as_deref()))
+        .await
+        .map_err(|e| format!("备份任务失败: {}", e))?
+}
+
+/// 预览恢复：校验版本并列出冲突
+#[tauri::command]
+pub async fn preview_backup_restore(path: String, password: Option<String>) -> Result<BackupRestorePreview, String> {
+    tokio::task::spawn_blocking(move || backup::preview_restore(&PathBuf::from(path), password.as_deref()))
+        .await
+        .map_err(|e| format!("预览任务失败: {}", e))?
+}
+
+/// 从备份恢复（merge 合并 / replace 替换）
+#[tauri::command]
+pub async fn restore_backup(
+    path: String,
+    password: Option<String>,
+    mode: RestoreMode,
+) -> Result<BackupRestoreResult, String> {
+    tokio::task::spawn_blocking(move || backup::restore_backup(&PathBuf::from(path), password.as_deref(), mode))
+        .await
+        .map_err(|e| format!("恢复任务失败: {}", e))?
+}
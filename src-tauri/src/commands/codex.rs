@@ -2,7 +2,8 @@ use tauri::AppHandle;
 #[cfg(target_os = "macos")]
 use tauri::Emitter;
 use crate::models::codex::{CodexAccount, CodexQuota, CodexTokens};
-use crate::modules::{codex_account, codex_quota, codex_oauth, config, logger, opencode_auth, process};
+use crate::modules::credential_sink::{self, CredentialSink, CredentialSinkConfig};
+use crate::modules::{codex_account, codex_quota, codex_oauth, logger, process};
 
 /// 列出所有 Codex 账号
 #[tauri::command]
@@ -19,10 +20,13 @@ pub fn get_current_codex_account() -> Result<Option<CodexAccount>, String> {
 /// 切换 Codex 账号（包含 token 刷新检查）
 #[tauri::command]
 pub async fn switch_codex_account(app: AppHandle, account_id: String) -> Result<CodexAccount, String> {
+    // 先回收当前 auth.json 中被 Codex 轮换过的 Token，避免用旧令牌刷新
+    codex_account::sync_tokens_from_dir(&codex_account::get_codex_home());
     let _ = codex_account::prepare_account_for_injection(&account_id).await?;
-    
-    // 切换账号（写入 auth.json）
+
+    // 切换账号（写入 auth.json），并移除 API Key 账号写入的自定义接口地址
     let account = codex_account::switch_account(&account_id)?;
+    crate::modules::api_key_account::clear_custom_base_urls();
 
     // 同步更新 Codex 默认实例的绑定账号（不同步到 Antigravity，因为账号体系不同）
     if let Err(e) = crate::modules::codex_instance::update_default_settings(
@@ -35,34 +39,11 @@ pub async fn switch_codex_account(app: AppHandle, account_id: String) -> Result<
         logger::log_info(&format!("已同步更新 Codex 默认实例绑定账号: {}", account_id));
     }
 
-    let mut opencode_updated = false;
-    match opencode_auth::replace_openai_entry_from_codex(&account) {
-        Ok(()) => {
-            opencode_updated = true;
-        }
-        Err(e) => {
-            logger::log_warn(&format!("OpenCode auth.json 更新跳过: {}", e));
-        }
-    }
-
-    let user_config = config::get_user_config();
-    if user_config.opencode_sync_on_switch {
-        if opencode_updated {
-            if process::is_opencode_running() {
-                if let Err(e) = process::close_opencode(20) {
-                    logger::log_warn(&format!("OpenCode 关闭失败: {}", e));
-                }
-            } else {
-                logger::log_info("OpenCode 未在运行，准备启动");
-            }
-            if let Err(e) = process::start_opencode_with_path(Some(&user_config.opencode_app_path)) {
-                logger::log_warn(&format!("OpenCode 启动失败: {}", e));
-            }
-        } else {
-            logger::log_info("OpenCode 未更新 auth.json，跳过启动/重启");
+    // 同步到 OpenCode 等凭据目标（各目标写入后执行自己的重启钩子）
+    for result in credential_sink::sync_codex_account(&account) {
+        if let Some(error) = result.error {
+            logger::log_warn(&format!("{} 凭据更新跳过: {}", result.name, error));
         }
-    } else {
-        logger::log_info("已关闭 OpenCode 自动重启");
     }
 
     #[cfg(target_os = "macos")]
@@ -93,6 +74,11 @@ pub async fn switch_codex_account(app: AppHandle, account_id: String) -> Result<
     }
 
     let _ = crate::modules::tray::update_tray_menu(&app);
+    crate::modules::websocket::broadcast_provider_account_switched(
+        crate::modules::websocket::AccountProvider::Codex,
+        &account.id,
+        &account.email,
+    );
     Ok(account)
 }
 
@@ -229,3 +215,16 @@ pub fn close_codex_oauth_port() -> Result<u32, String> {
     let killed = process::kill_port_processes(port)?;
     Ok(killed as u32)
 }
+
+/// 列出 Codex 凭据同步目标（含内置 OpenCode）
+#[tauri::command]
+pub fn list_credential_sinks() -> Result<Vec<CredentialSink>, String> {
+    credential_sink::list_sinks()
+}
+
+/// 保存 Codex 凭据同步目标配置
+#[tauri::command]
+pub fn save_credential_sinks(config: CredentialSinkConfig) -> Result<Vec<CredentialSink>, String> {
+    credential_sink::save_config(&config)?;
+    credential_sink::list_sinks()
+}
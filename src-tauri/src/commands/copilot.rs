@@ -1,7 +1,8 @@
 use tauri::AppHandle;
 
 use crate::models::copilot::{CopilotAccount, CopilotQuota};
-use crate::modules::{copilot_account, copilot_quota, copilot_oauth};
+use crate::modules::config::{self, UserConfig};
+use crate::modules::{copilot_account, copilot_auth, copilot_quota, copilot_oauth, websocket};
 use serde::{Deserialize, Serialize};
 
 const COPILOT_DEVICE_CLIENT_ID: &str = "Iv1.b507a08c87ecfe98";
@@ -17,6 +18,15 @@ pub struct CopilotDeviceCode {
     pub interval: i64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct CopilotCredentialSettings {
+    /// 用户配置的凭据目录（为空表示默认）
+    pub config_dir: String,
+    /// 实际使用的凭据目录
+    pub resolved_dir: String,
+    pub backups: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CopilotDevicePollResult {
     pub status: String,
@@ -117,9 +127,33 @@ pub async fn poll_copilot_device_code(
     })
 }
 
+/// 切换 Copilot 账号（写入编辑器凭据文件，在阻塞线程池中执行）
 #[tauri::command]
-pub fn switch_copilot_account(account_id: String) -> Result<CopilotAccount, String> {
-    copilot_account::switch_account(&account_id)
+pub async fn switch_copilot_account(account_id: String) -> Result<CopilotAccount, String> {
+    tokio::task::spawn_blocking(move || switch_copilot_account_blocking(&account_id))
+        .await
+        .map_err(|e| format!("切换任务失败: {}", e))?
+}
+
+fn switch_copilot_account_blocking(account_id: &str) -> Result<CopilotAccount, String> {
+    let target = copilot_account::load_account(account_id)
+        .ok_or_else(|| format!("账号不存在: {}", account_id))?;
+    // 先写入编辑器凭据，失败时不切换当前账号；切换失败时撤销凭据写入
+    let written = copilot_auth::write_credentials(&target)?;
+    let account = match copilot_account::switch_account(account_id) {
+        Ok(account) => account,
+        Err(e) => {
+            copilot_auth::rollback_credentials(written);
+            return Err(e);
+        }
+    };
+
+    websocket::broadcast_provider_account_switched(
+        websocket::AccountProvider::Copilot,
+        &account.id,
+        account.email.as_deref().unwrap_or(&account.username),
+    );
+    Ok(account)
 }
 
 #[tauri::command]
@@ -133,14 +167,19 @@ pub fn delete_copilot_accounts(account_ids: Vec<String>) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn refresh_copilot_quota(_app: AppHandle, account_id: String) -> Result<CopilotQuota, String> {
-    copilot_quota::refresh_account_quota(&account_id).await
+pub async fn refresh_copilot_quota(app: AppHandle, account_id: String) -> Result<CopilotQuota, String> {
+    let result = copilot_quota::refresh_account_quota(&account_id).await;
+    if result.is_ok() {
+        let _ = crate::modules::tray::update_tray_menu(&app);
+    }
+    result
 }
 
 #[tauri::command]
-pub async fn refresh_all_copilot_quotas(_app: AppHandle) -> Result<i32, String> {
+pub async fn refresh_all_copilot_quotas(app: AppHandle) -> Result<i32, String> {
     let results = copilot_quota::refresh_all_quotas().await?;
     let success_count = results.iter().filter(|(_, r)| r.is_ok()).count();
+    let _ = crate::modules::tray::update_tray_menu(&app);
     Ok(success_count as i32)
 }
 
@@ -157,3 +196,25 @@ pub async fn refresh_current_copilot_quota(app: AppHandle) -> Result<(), String>
 pub fn update_copilot_account_tags(account_id: String, tags: Vec<String>) -> Result<CopilotAccount, String> {
     copilot_account::update_account_tags(&account_id, tags)
 }
+
+/// 获取 Copilot 凭据写入设置
+#[tauri::command]
+pub fn get_copilot_credential_settings() -> Result<CopilotCredentialSettings, String> {
+    let user_config = config::get_user_config();
+    Ok(CopilotCredentialSettings {
+        config_dir: user_config.copilot_config_dir,
+        resolved_dir: copilot_auth::get_copilot_config_dir()?.to_string_lossy().to_string(),
+        backups: copilot_auth::list_backups()?,
+    })
+}
+
+/// 保存 Copilot 凭据写入设置
+#[tauri::command]
+pub fn save_copilot_credential_settings(config_dir: String) -> Result<CopilotCredentialSettings, String> {
+    let current = config::get_user_config();
+    config::save_user_config(&UserConfig {
+        copilot_config_dir: config_dir.trim().to_string(),
+        ..current
+    })?;
+    get_copilot_credential_settings()
+}
@@ -0,0 +1,248 @@
+use std::path::Path;
+
+use crate::models::{DefaultInstanceSettings, InstanceProfileView};
+use crate::modules;
+
+const DEFAULT_INSTANCE_ID: &str = "__default__";
+
+fn is_profile_initialized(user_data_dir: &str) -> bool {
+    modules::instance::is_profile_initialized(Path::new(user_data_dir))
+}
+
+/// 默认实例视图（Copilot 实例不绑定账号）
+fn default_instance_view(
+    default_dir: &Path,
+    settings: DefaultInstanceSettings,
+    last_pid: Option<u32>,
+    running: bool,
+) -> InstanceProfileView {
+    InstanceProfileView {
+        id: DEFAULT_INSTANCE_ID.to_string(),
+        name: String::new(),
+        user_data_dir: default_dir.to_string_lossy().to_string(),
+        extra_args: settings.extra_args,
+        bind_account_id: None,
+        created_at: 0,
+        last_launched_at: None,
+        last_pid,
+        running,
+        initialized: modules::instance::is_profile_initialized(default_dir),
+        is_default: true,
+        follow_local_account: false,
+    }
+}
+
+#[tauri::command]
+pub async fn copilot_get_instance_defaults() -> Result<modules::instance::InstanceDefaults, String> {
+    modules::copilot_instance::get_instance_defaults()
+}
+
+#[tauri::command]
+pub async fn copilot_list_instances() -> Result<Vec<InstanceProfileView>, String> {
+    let store = modules::copilot_instance::load_instance_store()?;
+    let default_dir = modules::copilot_instance::get_default_vscode_user_data_dir()?;
+
+    let default_settings = store.default_settings.clone();
+    let mut result: Vec<InstanceProfileView> = store
+        .instances
+        .into_iter()
+        .map(|instance| {
+            let running = instance
+                .last_pid
+                .map(modules::process::is_pid_running)
+                .unwrap_or(false);
+            let initialized = is_profile_initialized(&instance.user_data_dir);
+            InstanceProfileView::from_profile(instance, running, initialized)
+        })
+        .collect();
+
+    let default_running = default_settings
+        .last_pid
+        .map(modules::process::is_pid_running)
+        .unwrap_or(false);
+    let last_pid = default_settings.last_pid;
+    result.push(default_instance_view(&default_dir, default_settings, last_pid, default_running));
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn copilot_create_instance(
+    name: String,
+    user_data_dir: String,
+    extra_args: Option<String>,
+    bind_account_id: Option<String>,
+    copy_source_instance_id: Option<String>,
+    init_mode: Option<String>,
+) -> Result<InstanceProfileView, String> {
+    let instance = modules::copilot_instance::create_instance(modules::copilot_instance::CreateInstanceParams {
+        name,
+        user_data_dir,
+        extra_args: extra_args.unwrap_or_default(),
+        bind_account_id,
+        copy_source_instance_id,
+        init_mode,
+    })?;
+
+    let initialized = is_profile_initialized(&instance.user_data_dir);
+    Ok(InstanceProfileView::from_profile(instance, false, initialized))
+}
+
+#[tauri::command]
+pub async fn copilot_update_instance(
+    instance_id: String,
+    name: Option<String>,
+    extra_args: Option<String>,
+    bind_account_id: Option<Option<String>>,
+    follow_local_account: Option<bool>,
+) -> Result<InstanceProfileView, String> {
+    if instance_id == DEFAULT_INSTANCE_ID {
+        let default_dir = modules::copilot_instance::get_default_vscode_user_data_dir()?;
+        let updated = modules::copilot_instance::update_default_settings(
+            bind_account_id,
+            extra_args,
+            follow_local_account,
+        )?;
+        let running = updated
+            .last_pid
+            .map(modules::process::is_pid_running)
+            .unwrap_or(false);
+        let last_pid = updated.last_pid;
+        return Ok(default_instance_view(&default_dir, updated, last_pid, running));
+    }
+
+    let instance = modules::copilot_instance::update_instance(modules::copilot_instance::UpdateInstanceParams {
+        instance_id,
+        name,
+        extra_args,
+        bind_account_id,
+    })?;
+
+    let running = instance
+        .last_pid
+        .map(modules::process::is_pid_running)
+        .unwrap_or(false);
+    let initialized = is_profile_initialized(&instance.user_data_dir);
+    Ok(InstanceProfileView::from_profile(instance, running, initialized))
+}
+
+#[tauri::command]
+pub async fn copilot_delete_instance(instance_id: String) -> Result<(), String> {
+    if instance_id == DEFAULT_INSTANCE_ID {
+        return Err("默认实例不可删除".to_string());
+    }
+    modules::copilot_instance::delete_instance(&instance_id)
+}
+
+#[tauri::command]
+pub async fn copilot_start_instance(instance_id: String) -> Result<InstanceProfileView, String> {
+    if instance_id == DEFAULT_INSTANCE_ID {
+        let default_dir = modules::copilot_instance::get_default_vscode_user_data_dir()?;
+        let default_settings = modules::copilot_instance::load_default_settings()?;
+        let extra_args = modules::process::parse_extra_args(&default_settings.extra_args);
+        let pid = modules::process::start_vscode_with_args("", &extra_args)?;
+        let _ = modules::copilot_instance::update_default_pid(Some(pid))?;
+        let running = modules::process::is_pid_running(pid);
+        return Ok(default_instance_view(&default_dir, default_settings, Some(pid), running));
+    }
+
+    let store = modules::copilot_instance::load_instance_store()?;
+    let instance = store
+        .instances
+        .into_iter()
+        .find(|item| item.id == instance_id)
+        .ok_or("实例不存在")?;
+
+    let extra_args = modules::process::parse_extra_args(&instance.extra_args);
+    let pid = modules::process::start_vscode_with_args(&instance.user_data_dir, &extra_args)?;
+    let updated = modules::copilot_instance::update_instance_after_start(&instance.id, pid)?;
+    let running = modules::process::is_pid_running(pid);
+    let initialized = is_profile_initialized(&updated.user_data_dir);
+    Ok(InstanceProfileView::from_profile(updated, running, initialized))
+}
+
+#[tauri::command]
+pub async fn copilot_stop_instance(instance_id: String) -> Result<InstanceProfileView, String> {
+    if instance_id == DEFAULT_INSTANCE_ID {
+        let default_dir = modules::copilot_instance::get_default_vscode_user_data_dir()?;
+        let default_settings = modules::copilot_instance::load_default_settings()?;
+        if let Some(pid) = default_settings.last_pid {
+            modules::process::close_pid(pid, 20)?;
+            let _ = modules::copilot_instance::update_default_pid(None)?;
+        }
+        return Ok(default_instance_view(&default_dir, default_settings, None, false));
+    }
+
+    let store = modules::copilot_instance::load_instance_store()?;
+    let instance = store
+        .instances
+        .into_iter()
+        .find(|item| item.id == instance_id)
+        .ok_or("实例不存在")?;
+
+    if let Some(pid) = instance.last_pid {
+        modules::process::close_pid(pid, 20)?;
+    }
+    // 同一目录可能由其他方式打开，按 --user-data-dir 再关闭一次
+    modules::process::close_vscode_instance(&instance.user_data_dir, 20)?;
+    let updated = modules::copilot_instance::update_instance_pid(&instance.id, None)?;
+    let initialized = is_profile_initialized(&updated.user_data_dir);
+    Ok(InstanceProfileView::from_profile(updated, false, initialized))
+}
+
+#[tauri::command]
+pub async fn copilot_force_stop_instance(instance_id: String) -> Result<InstanceProfileView, String> {
+    if instance_id == DEFAULT_INSTANCE_ID {
+        let default_dir = modules::copilot_instance::get_default_vscode_user_data_dir()?;
+        let default_settings = modules::copilot_instance::load_default_settings()?;
+        if let Some(pid) = default_settings.last_pid {
+            modules::process::force_kill_pid(pid)?;
+            let _ = modules::copilot_instance::update_default_pid(None)?;
+        }
+        return Ok(default_instance_view(&default_dir, default_settings, None, false));
+    }
+
+    let store = modules::copilot_instance::load_instance_store()?;
+    let instance = store
+        .instances
+        .into_iter()
+        .find(|item| item.id == instance_id)
+        .ok_or("实例不存在")?;
+
+    if let Some(pid) = instance.last_pid {
+        modules::process::force_kill_pid(pid)?;
+    }
+    let updated = modules::copilot_instance::update_instance_pid(&instance.id, None)?;
+    let initialized = is_profile_initialized(&updated.user_data_dir);
+    Ok(InstanceProfileView::from_profile(updated, false, initialized))
+}
+
+#[tauri::command]
+pub async fn copilot_close_all_instances() -> Result<(), String> {
+    modules::process::close_vscode(20)?;
+    let _ = modules::copilot_instance::clear_all_pids();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn copilot_open_instance_window(instance_id: String) -> Result<(), String> {
+    if instance_id == DEFAULT_INSTANCE_ID {
+        let default_settings = modules::copilot_instance::load_default_settings()?;
+        let extra_args = modules::process::parse_extra_args(&default_settings.extra_args);
+        let pid = modules::process::start_vscode_with_args("", &extra_args)?;
+        let _ = modules::copilot_instance::update_default_pid(Some(pid))?;
+        return Ok(());
+    }
+
+    let store = modules::copilot_instance::load_instance_store()?;
+    let instance = store
+        .instances
+        .into_iter()
+        .find(|item| item.id == instance_id)
+        .ok_or("实例不存在")?;
+
+    let extra_args = modules::process::parse_extra_args(&instance.extra_args);
+    let pid = modules::process::start_vscode_with_args(&instance.user_data_dir, &extra_args)?;
+    let _ = modules::copilot_instance::update_instance_after_start(&instance.id, pid)?;
+    Ok(())
+}
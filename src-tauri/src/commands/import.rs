@@ -21,11 +21,28 @@ pub async fn import_from_local() -> Result<models::Account, String> {
     modules::import::import_from_local_logic().await
 }
 
+#[tauri::command]
+pub async fn import_from_tabular(
+    content: String,
+    options: Option<modules::import::TabularImportOptions>,
+) -> Result<modules::import::TabularImportReport, String> {
+    modules::import::import_from_tabular_logic(content, options.unwrap_or_default()).await
+}
+
 #[tauri::command]
 pub async fn import_from_json(json_content: String) -> Result<Vec<models::Account>, String> {
     modules::import::import_from_json_logic(json_content).await
 }
 
+/// 按条件导出账号（full / redacted / csv）
+#[tauri::command]
+pub async fn export_accounts_filtered(
+    filter: Option<modules::account_export::ExportFilter>,
+    mode: Option<modules::account_export::ExportMode>,
+) -> Result<String, String> {
+    modules::account_export::export_accounts(&filter.unwrap_or_default(), mode.unwrap_or_default())
+}
+
 #[tauri::command]
 pub async fn export_accounts(account_ids: Vec<String>) -> Result<String, String> {
     let mut accounts_to_export = Vec::new();
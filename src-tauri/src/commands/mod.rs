@@ -8,5 +8,9 @@ pub mod update;
 pub mod group;
 pub mod codex;
 pub mod copilot;
+pub mod api_key;
 pub mod instance;
 pub mod codex_instance;
+pub mod copilot_instance;
+pub mod backup;
+pub mod account_sync;
@@ -18,6 +18,15 @@ pub struct NetworkConfig {
     pub default_port: u16,
 }
 
+/// 托盘图标配置（前端使用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrayIconConfig {
+    /// 是否按剩余配额动态绘制托盘图标
+    pub enabled: bool,
+    /// 统计的模型分组 ID（为空则统计全部模型）
+    pub group_id: String,
+}
+
 /// 通用设置配置（前端使用）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneralConfig {
@@ -123,6 +132,16 @@ pub fn save_network_config(ws_enabled: bool, ws_port: u16) -> Result<bool, Strin
         antigravity_app_path: current.antigravity_app_path,
         codex_app_path: current.codex_app_path,
         opencode_sync_on_switch: current.opencode_sync_on_switch,
+        wakeup_max_concurrency: current.wakeup_max_concurrency,
+        endpoints: current.endpoints,
+        account_sync_folder: current.account_sync_folder,
+        account_sync_passphrase: current.account_sync_passphrase,
+        background_token_refresh: current.background_token_refresh,
+        health_check_interval_minutes: current.health_check_interval_minutes,
+        vscode_app_path: current.vscode_app_path,
+        copilot_config_dir: current.copilot_config_dir,
+        tray_quota_icon: current.tray_quota_icon,
+        tray_quota_group: current.tray_quota_group,
     };
     
     config::save_user_config(&new_config)?;
@@ -197,6 +216,16 @@ pub fn save_general_config(
         antigravity_app_path: normalized_antigravity_path,
         codex_app_path: normalized_codex_path,
         opencode_sync_on_switch,
+        wakeup_max_concurrency: current.wakeup_max_concurrency,
+        endpoints: current.endpoints,
+        account_sync_folder: current.account_sync_folder,
+        account_sync_passphrase: current.account_sync_passphrase,
+        background_token_refresh: current.background_token_refresh,
+        health_check_interval_minutes: current.health_check_interval_minutes,
+        vscode_app_path: current.vscode_app_path,
+        copilot_config_dir: current.copilot_config_dir,
+        tray_quota_icon: current.tray_quota_icon,
+        tray_quota_group: current.tray_quota_group,
     };
     
     config::save_user_config(&new_config)?;
@@ -223,6 +252,7 @@ pub fn set_app_path(app: String, path: String) -> Result<(), String> {
         "antigravity" => current.antigravity_app_path = normalized_path,
         "codex" => current.codex_app_path = normalized_path,
         "opencode" => current.opencode_app_path = normalized_path,
+        "vscode" => current.vscode_app_path = normalized_path,
         _ => return Err("未知应用类型".to_string()),
     }
     config::save_user_config(&current)?;
@@ -232,7 +262,7 @@ pub fn set_app_path(app: String, path: String) -> Result<(), String> {
 #[tauri::command]
 pub fn detect_app_path(app: String) -> Result<Option<String>, String> {
     match app.as_str() {
-        "antigravity" | "codex" | "opencode" => Ok(modules::process::detect_and_save_app_path(app.as_str())),
+        "antigravity" | "codex" | "opencode" | "vscode" => Ok(modules::process::detect_and_save_app_path(app.as_str())),
         _ => Err("未知应用类型".to_string()),
     }
 }
@@ -244,6 +274,28 @@ pub fn set_wakeup_override(enabled: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// 获取托盘图标配置
+#[tauri::command]
+pub fn get_tray_icon_config() -> Result<TrayIconConfig, String> {
+    let user_config = config::get_user_config();
+    Ok(TrayIconConfig {
+        enabled: user_config.tray_quota_icon,
+        group_id: user_config.tray_quota_group,
+    })
+}
+
+/// 保存托盘图标配置并立即重绘
+#[tauri::command]
+pub fn save_tray_icon_config(app: tauri::AppHandle, enabled: bool, group_id: String) -> Result<(), String> {
+    let current = config::get_user_config();
+    config::save_user_config(&UserConfig {
+        tray_quota_icon: enabled,
+        tray_quota_group: group_id.trim().to_string(),
+        ..current
+    })?;
+    modules::tray::update_tray_icon(&app)
+}
+
 /// 执行窗口关闭操作
 /// action: "minimize" | "quit"
 /// remember: 是否记住选择
@@ -10,7 +10,9 @@ pub async fn trigger_wakeup(
 ) -> Result<modules::wakeup::WakeupResponse, String> {
     let final_prompt = prompt.unwrap_or_else(|| "hi".to_string());
     let final_tokens = max_output_tokens.unwrap_or(0);
-    modules::wakeup::trigger_wakeup(&account_id, &model, &final_prompt, final_tokens).await
+    modules::wakeup::trigger_wakeup(&account_id, &model, &final_prompt, final_tokens)
+        .await
+        .map_err(|e| e.message)
 }
 
 #[tauri::command]
@@ -24,16 +26,79 @@ pub async fn wakeup_sync_state(
     enabled: bool,
     tasks: Vec<modules::wakeup_scheduler::WakeupTaskInput>,
 ) -> Result<(), String> {
-    modules::wakeup_scheduler::sync_state(enabled, tasks);
+    modules::wakeup_scheduler::sync_state(enabled, tasks)?;
     modules::wakeup_scheduler::ensure_started(app);
     Ok(())
 }
 
+#[tauri::command]
+pub fn wakeup_get_state() -> Result<modules::wakeup_scheduler::WakeupStateSnapshot, String> {
+    Ok(modules::wakeup_scheduler::get_state_snapshot())
+}
+
+#[tauri::command]
+pub fn wakeup_set_enabled(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<modules::wakeup_scheduler::WakeupStateSnapshot, String> {
+    let snapshot = modules::wakeup_scheduler::set_enabled(enabled);
+    modules::wakeup_scheduler::ensure_started(app);
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub fn wakeup_upsert_task(
+    app: AppHandle,
+    task: modules::wakeup_scheduler::WakeupTaskInput,
+) -> Result<modules::wakeup_scheduler::WakeupTaskInput, String> {
+    let saved = modules::wakeup_scheduler::upsert_task(task)?;
+    modules::wakeup_scheduler::ensure_started(app);
+    Ok(saved)
+}
+
+#[tauri::command]
+pub fn wakeup_delete_task(task_id: String) -> Result<(), String> {
+    modules::wakeup_scheduler::delete_task(&task_id)
+}
+
+#[tauri::command]
+pub fn wakeup_preview_schedule(
+    schedule: modules::wakeup_scheduler::ScheduleConfig,
+    count: Option<usize>,
+) -> Result<Vec<modules::wakeup_scheduler::WakeupRunPreview>, String> {
+    modules::wakeup_scheduler::preview_schedule(schedule, count)
+}
+
+/// 设置唤醒请求的全局并发上限（立即生效，进行中的请求不受影响）
+#[tauri::command]
+pub fn wakeup_set_max_concurrency(limit: u32) -> Result<u32, String> {
+    modules::wakeup_scheduler::set_max_concurrency(limit)
+}
+
+#[tauri::command]
+pub fn wakeup_dry_run() -> Result<modules::wakeup_scheduler::WakeupDryRunReport, String> {
+    modules::wakeup_scheduler::dry_run()
+}
+
 #[tauri::command]
 pub fn wakeup_load_history() -> Result<Vec<modules::wakeup_history::WakeupHistoryItem>, String> {
     modules::wakeup_history::load_history()
 }
 
+#[tauri::command]
+pub fn wakeup_query_history(
+    filter: Option<modules::wakeup_history::WakeupHistoryFilter>,
+) -> Result<Vec<modules::wakeup_history::WakeupHistoryItem>, String> {
+    modules::wakeup_history::query_history(filter.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn wakeup_history_stats(
+    filter: Option<modules::wakeup_history::WakeupHistoryFilter>,
+) -> Result<modules::wakeup_history::WakeupHistoryStats, String> {
+    modules::wakeup_history::history_stats(filter.unwrap_or_default())
+}
+
 #[tauri::command]
 pub fn wakeup_clear_history() -> Result<(), String> {
     modules::wakeup_history::clear_history()
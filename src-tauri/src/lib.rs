@@ -62,6 +62,15 @@ pub fn run() {
                 }
             }
             
+            // 启动时与账号池同步文件夹同步
+            if !modules::config::get_user_config().account_sync_folder.trim().is_empty() {
+                tauri::async_runtime::spawn_blocking(|| {
+                    if let Err(e) = modules::account_sync::sync_now() {
+                        logger::log_warn(&format!("[AccountSync] 启动时同步失败: {}", e));
+                    }
+                });
+            }
+            
             // 启动 WebSocket 服务（使用 Tauri 的 async runtime）
             tauri::async_runtime::spawn(async {
                 modules::websocket::start_server().await;
@@ -71,6 +80,16 @@ pub fn run() {
             if let Err(e) = modules::tray::create_tray(app.handle()) {
                 logger::log_error(&format!("[Tray] 创建系统托盘失败: {}", e));
             }
+
+            // 恢复唤醒任务并启动调度器（不依赖前端同步）
+            modules::wakeup_scheduler::load_persisted_state();
+            modules::wakeup_scheduler::ensure_started(app.handle().clone());
+
+            // 后台提前刷新各平台 Token
+            modules::token_refresher::ensure_started(app.handle().clone());
+
+            // 定时账号健康检查（按设置的间隔执行）
+            modules::health_check::ensure_started(app.handle().clone());
             
             Ok(())
         })
@@ -116,6 +135,9 @@ pub fn run() {
             commands::account::update_account_tags,
             commands::account::sync_current_from_client,
             commands::account::sync_from_extension,
+            commands::account::refresh_all_tokens,
+            commands::account::run_account_health_check,
+            commands::account::get_last_health_report,
             
             // Device Commands
             commands::device::get_device_profiles,
@@ -152,7 +174,9 @@ pub fn run() {
             commands::import::import_fingerprints_from_json,
             commands::import::import_from_local,
             commands::import::import_from_json,
+            commands::import::import_from_tabular,
             commands::import::export_accounts,
+            commands::import::export_accounts_filtered,
             
             // System Commands
             commands::system::open_data_folder,
@@ -165,6 +189,8 @@ pub fn run() {
             commands::system::set_app_path,
             commands::system::detect_app_path,
             commands::system::set_wakeup_override,
+            commands::system::get_tray_icon_config,
+            commands::system::save_tray_icon_config,
             commands::system::handle_window_close,
             commands::system::open_folder,
             commands::system::delete_corrupted_file,
@@ -173,8 +199,26 @@ pub fn run() {
             commands::wakeup::trigger_wakeup,
             commands::wakeup::fetch_available_models,
             commands::wakeup::wakeup_sync_state,
+            commands::wakeup::wakeup_get_state,
+            commands::wakeup::wakeup_set_enabled,
+            commands::wakeup::wakeup_upsert_task,
+            commands::wakeup::wakeup_delete_task,
+            commands::wakeup::wakeup_preview_schedule,
+            commands::wakeup::wakeup_set_max_concurrency,
+            commands::wakeup::wakeup_dry_run,
             commands::wakeup::wakeup_load_history,
+            commands::wakeup::wakeup_query_history,
+            commands::wakeup::wakeup_history_stats,
             commands::wakeup::wakeup_clear_history,
+
+            // Backup Commands
+            commands::backup::create_backup,
+            commands::backup::preview_backup_restore,
+            commands::backup::restore_backup,
+            commands::account_sync::get_account_sync_status,
+            commands::account_sync::set_account_sync_folder,
+            commands::account_sync::set_account_sync_passphrase,
+            commands::account_sync::sync_accounts_now,
             
             // Update Commands
             commands::update::check_for_updates,
@@ -211,6 +255,8 @@ pub fn run() {
             commands::codex::add_codex_account_with_token,
             commands::codex::is_codex_oauth_port_in_use,
             commands::codex::close_codex_oauth_port,
+            commands::codex::list_credential_sinks,
+            commands::codex::save_credential_sinks,
             commands::codex::update_codex_account_tags,
 
             // Copilot Commands
@@ -226,6 +272,17 @@ pub fn run() {
             commands::copilot::refresh_all_copilot_quotas,
             commands::copilot::refresh_current_copilot_quota,
             commands::copilot::update_copilot_account_tags,
+            commands::copilot::get_copilot_credential_settings,
+            commands::copilot::save_copilot_credential_settings,
+            commands::api_key::list_api_key_accounts,
+            commands::api_key::get_current_api_key_account,
+            commands::api_key::add_api_key_account,
+            commands::api_key::update_api_key_account,
+            commands::api_key::update_api_key_account_tags,
+            commands::api_key::delete_api_key_accounts,
+            commands::api_key::switch_api_key_account,
+            commands::api_key::refresh_api_key_usage,
+            commands::api_key::refresh_all_api_key_usages,
 
             // Codex Instance Commands
             commands::codex_instance::codex_get_instance_defaults,
@@ -238,6 +295,16 @@ pub fn run() {
             commands::codex_instance::codex_open_instance_window,
             commands::codex_instance::codex_force_stop_instance,
             commands::codex_instance::codex_close_all_instances,
+            commands::copilot_instance::copilot_get_instance_defaults,
+            commands::copilot_instance::copilot_list_instances,
+            commands::copilot_instance::copilot_create_instance,
+            commands::copilot_instance::copilot_update_instance,
+            commands::copilot_instance::copilot_delete_instance,
+            commands::copilot_instance::copilot_start_instance,
+            commands::copilot_instance::copilot_stop_instance,
+            commands::copilot_instance::copilot_open_instance_window,
+            commands::copilot_instance::copilot_force_stop_instance,
+            commands::copilot_instance::copilot_close_all_instances,
 
             // Instance Commands
             commands::instance::get_instance_defaults,
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// OpenAI 兼容接口的 API Key 账号
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyAccount {
+    pub id: String,
+    pub label: String,
+    /// 接口基础地址（如 https://api.openai.com/v1）
+    pub base_url: String,
+    pub api_key: String,
+    /// 自定义用量查询地址（为空则尝试 dashboard/billing 接口）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_url: Option<String>,
+    #[serde(default)]
+    pub usage: Option<ApiKeyUsage>,
+    pub tags: Option<Vec<String>>,
+    pub created_at: i64,
+    pub last_used: i64,
+}
+
+/// API Key 用量（单位：美元）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyUsage {
+    /// 额度上限
+    pub total_granted: Option<f64>,
+    /// 已用额度
+    pub total_used: Option<f64>,
+    /// 剩余额度
+    pub remaining: Option<f64>,
+    pub fetched_at: i64,
+}
+
+/// API Key 账号存储
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiKeyStore {
+    #[serde(default)]
+    pub accounts: Vec<ApiKeyAccount>,
+    #[serde(default)]
+    pub current_account_id: Option<String>,
+}
+
+impl ApiKeyAccount {
+    pub fn new(id: String, label: String, base_url: String, api_key: String, usage_url: Option<String>) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id,
+            label,
+            base_url,
+            api_key,
+            usage_url,
+            usage: None,
+            tags: None,
+            created_at: now,
+            last_used: now,
+        }
+    }
+
+    pub fn update_last_used(&mut self) {
+        self.last_used = chrono::Utc::now().timestamp();
+    }
+
+    /// 脱敏后的 Key（保留前后各 4 位）
+    pub fn masked_key(&self) -> String {
+        let chars: Vec<char> = self.api_key.chars().collect();
+        if chars.len() <= 12 {
+            return "****".to_string();
+        }
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}…{}", head, tail)
+    }
+}
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Codex 账号数据结构
@@ -11,6 +12,14 @@ pub struct CodexAccount {
     pub tokens: CodexTokens,
     pub quota: Option<CodexQuota>,
     pub tags: Option<Vec<String>>,
+    /// 刷新令牌失效（如 invalid_grant）时禁用，重新登录后恢复
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>,
+    /// 禁用时间（Unix 时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_at: Option<i64>,
     pub created_at: i64,
     pub last_used: i64,
 }
@@ -25,7 +34,7 @@ pub struct CodexTokens {
 }
 
 /// Codex 配额数据（5小时配额 + 周配额）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CodexQuota {
     /// 5小时配额百分比 (0-100)
     pub hourly_percentage: i32,
@@ -35,11 +44,83 @@ pub struct CodexQuota {
     pub weekly_percentage: i32,
     /// 周配额重置时间 (Unix timestamp)
     pub weekly_reset_time: Option<i64>,
+    /// 5小时窗口长度（秒）
+    #[serde(default)]
+    pub hourly_window_seconds: Option<i64>,
+    /// 周窗口长度（秒）
+    #[serde(default)]
+    pub weekly_window_seconds: Option<i64>,
+    /// 是否允许继续请求
+    #[serde(default = "default_allowed")]
+    pub allowed: bool,
+    /// 是否已触发限额
+    #[serde(default)]
+    pub limit_reached: bool,
+    /// 订阅类型（plus / pro / team 等）
+    #[serde(default)]
+    pub plan_type: Option<String>,
+    /// Code Review 限额
+    #[serde(default)]
+    pub code_review: Option<CodexRateLimit>,
     /// 原始响应数据
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_data: Option<serde_json::Value>,
 }
 
+/// 单个速率限制（主窗口）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CodexRateLimit {
+    /// 剩余百分比 (0-100)
+    pub percentage: i32,
+    /// 重置时间 (Unix timestamp)
+    pub reset_time: Option<i64>,
+    /// 窗口长度（秒）
+    pub window_seconds: Option<i64>,
+    #[serde(default = "default_allowed")]
+    pub allowed: bool,
+    #[serde(default)]
+    pub limit_reached: bool,
+}
+
+fn default_allowed() -> bool {
+    true
+}
+
+/// 配额状态等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodexQuotaLevel {
+    Normal,
+    /// 剩余不足 `CODEX_LOW_QUOTA_PERCENTAGE`
+    Low,
+    /// 已触发限额或剩余为 0
+    Exhausted,
+}
+
+/// 低配额阈值（与前端 critical 档一致）
+pub const CODEX_LOW_QUOTA_PERCENTAGE: i32 = 10;
+
+impl CodexQuota {
+    /// 当前配额等级：区分「已耗尽」与「即将耗尽」（含 Code Review 配额）
+    pub fn level(&self) -> CodexQuotaLevel {
+        let code_review = self.code_review.as_ref();
+        let remaining = self
+            .hourly_percentage
+            .min(self.weekly_percentage)
+            .min(code_review.map_or(100, |limit| limit.percentage));
+        let limited = self.limit_reached
+            || !self.allowed
+            || code_review.is_some_and(|limit| limit.limit_reached || !limit.allowed);
+        if limited || remaining <= 0 {
+            CodexQuotaLevel::Exhausted
+        } else if remaining < CODEX_LOW_QUOTA_PERCENTAGE {
+            CodexQuotaLevel::Low
+        } else {
+            CodexQuotaLevel::Normal
+        }
+    }
+}
+
 /// ~/.codex/auth.json 文件格式
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodexAuthFile {
@@ -130,6 +211,9 @@ impl CodexAccount {
             tokens,
             quota: None,
             tags: None,
+            disabled: false,
+            disabled_reason: None,
+            disabled_at: None,
             created_at: now,
             last_used: now,
         }
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,11 +11,19 @@ pub struct CopilotAccount {
     pub token: String,
     pub quota: Option<CopilotQuota>,
     pub tags: Option<Vec<String>>,
+    /// GitHub Token 被撤销（校验返回 401）时禁用，重新登录后恢复
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>,
+    /// 禁用时间（Unix 时间戳）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_at: Option<i64>,
     pub created_at: i64,
     pub last_used: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CopilotQuota {
     pub used_requests: i64,
     pub included_requests: Option<i64>,
@@ -22,10 +31,32 @@ pub struct CopilotQuota {
     pub usage_items_count: usize,
     pub copilot_plan: Option<String>,
     pub quota_reset_date: Option<String>,
+    /// 全部配额快照（premium_interactions / chat / completions 等）
+    #[serde(default)]
+    pub snapshots: Vec<CopilotQuotaSnapshot>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_data: Option<serde_json::Value>,
 }
 
+/// 单个配额快照（对应 `quota_snapshots` 中的一项）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CopilotQuotaSnapshot {
+    /// 快照类型，如 premium_interactions、chat、completions
+    pub quota_id: String,
+    pub entitlement: Option<i64>,
+    pub remaining: Option<i64>,
+    /// 剩余百分比 (0-100)
+    pub percent_remaining: Option<f64>,
+    #[serde(default)]
+    pub unlimited: bool,
+    /// 是否允许超额使用
+    #[serde(default)]
+    pub overage_permitted: bool,
+    /// 已产生的超额次数
+    #[serde(default)]
+    pub overage_count: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CopilotAccountIndex {
     pub version: String,
@@ -76,6 +107,9 @@ impl CopilotAccount {
             token,
             quota: None,
             tags: None,
+            disabled: false,
+            disabled_reason: None,
+            disabled_at: None,
             created_at: now,
             last_used: now,
         }
@@ -1,4 +1,5 @@
 pub mod account;
+pub mod api_key;
 pub mod codex;
 pub mod copilot;
 pub mod instance;
@@ -17,8 +17,10 @@ const ACCOUNTS_DIR: &str = "accounts";
 
 /// 获取数据目录路径
 pub fn get_data_dir() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or("无法获取用户主目录")?;
-    let data_dir = home.join(DATA_DIR);
+    let data_dir = match modules::config::data_dir_override() {
+        Some(dir) => dir,
+        None => dirs::home_dir().ok_or("无法获取用户主目录")?.join(DATA_DIR),
+    };
     
     if !data_dir.exists() {
         fs::create_dir_all(&data_dir)
@@ -107,10 +109,12 @@ pub fn save_account(account: &Account) -> Result<(), String> {
         .map_err(|e| format!("序列化账号数据失败: {}", e))?;
     
     fs::write(&account_path, content)
-        .map_err(|e| format!("保存账号数据失败: {}", e))
+        .map_err(|e| format!("保存账号数据失败: {}", e))?;
+    modules::account_sync::record_account_saved(account);
+    Ok(())
 }
 
-fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
+pub(crate) fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
     let mut result: Vec<String> = Vec::new();
     let mut seen: HashSet<String> = HashSet::new();
 
@@ -167,6 +171,26 @@ pub fn list_accounts() -> Result<Vec<Account>, String> {
 
 /// 添加账号
 pub fn add_account(email: String, name: Option<String>, token: TokenData) -> Result<Account, String> {
+    create_account(email, name, token, None)
+}
+
+/// 添加账号并使用指定的指纹绑定（不生成新指纹，用于同步等已知绑定关系的场景）
+pub fn add_account_with_fingerprint(
+    email: String,
+    name: Option<String>,
+    token: TokenData,
+    fingerprint_id: Option<String>,
+) -> Result<Account, String> {
+    create_account(email, name, token, Some(fingerprint_id))
+}
+
+/// `fingerprint_id` 为 `None` 时为新账号生成指纹
+fn create_account(
+    email: String,
+    name: Option<String>,
+    token: TokenData,
+    fingerprint_id: Option<Option<String>>,
+) -> Result<Account, String> {
     let _lock = ACCOUNT_INDEX_LOCK.lock().map_err(|e| format!("获取锁失败: {}", e))?;
     let mut index = load_account_index()?;
     
@@ -178,8 +202,10 @@ pub fn add_account(email: String, name: Option<String>, token: TokenData) -> Res
     let mut account = Account::new(account_id.clone(), email.clone(), token);
     account.name = name.clone();
 
-    let fingerprint = crate::modules::fingerprint::generate_fingerprint(email.clone())?;
-    account.fingerprint_id = Some(fingerprint.id.clone());
+    account.fingerprint_id = match fingerprint_id {
+        Some(fingerprint_id) => fingerprint_id,
+        None => Some(crate::modules::fingerprint::generate_fingerprint(email.clone())?.id),
+    };
     
     save_account(&account)?;
     
@@ -273,8 +299,12 @@ pub fn delete_account(account_id: &str) -> Result<(), String> {
     let account_path = accounts_dir.join(format!("{}.json", account_id));
     
     if account_path.exists() {
+        let email = load_account(account_id).map(|acc| acc.email).ok();
         fs::remove_file(&account_path)
             .map_err(|e| format!("删除账号文件失败: {}", e))?;
+        if let Some(email) = email {
+            modules::account_sync::record_account_deleted(&email);
+        }
     }
     
     Ok(())
@@ -296,7 +326,12 @@ pub fn delete_accounts(account_ids: &[String]) -> Result<(), String> {
         
         let account_path = accounts_dir.join(format!("{}.json", account_id));
         if account_path.exists() {
-            let _ = fs::remove_file(&account_path);
+            let email = load_account(account_id).map(|acc| acc.email).ok();
+            if fs::remove_file(&account_path).is_ok() {
+                if let Some(email) = email {
+                    modules::account_sync::record_account_deleted(&email);
+                }
+            }
         }
     }
     
@@ -411,6 +446,9 @@ pub fn update_account_quota(account_id: &str, quota: QuotaData) -> Result<(), St
                 merged_quota.last_updated = quota.last_updated;
                 account.update_quota(merged_quota);
                 save_account(&account)?;
+                broadcast_quota_updated(&account);
+                // 托盘图标跟随当前账号的配额，任何途径（命令、单账号刷新、WS/HTTP）更新后都需重绘
+                redraw_tray_icon_if_current(&account);
                 return Ok(());
             }
         }
@@ -421,9 +459,36 @@ pub fn update_account_quota(account_id: &str, quota: QuotaData) -> Result<(), St
     if let Some(ref quota) = account.quota {
         let _ = modules::quota_cache::write_quota_cache("authorized", &account.email, quota);
     }
+    broadcast_quota_updated(&account);
+    redraw_tray_icon_if_current(&account);
     Ok(())
 }
 
+fn broadcast_quota_updated(account: &Account) {
+    use modules::websocket::{AccountProvider, ProviderQuota};
+    if let Some(ref quota) = account.quota {
+        modules::websocket::broadcast_quota_updated(
+            AccountProvider::Antigravity,
+            &account.id,
+            &account.email,
+            ProviderQuota::Antigravity(quota.clone()),
+        );
+    }
+}
+
+/// 当前账号的配额变化后重绘托盘图标
+fn redraw_tray_icon_if_current(account: &Account) {
+    let is_current = get_current_account_id().ok().flatten().as_deref() == Some(account.id.as_str());
+    if !is_current {
+        return;
+    }
+    if let Some(app_handle) = crate::get_app_handle() {
+        if let Err(e) = modules::tray::update_tray_icon(app_handle) {
+            modules::logger::log_warn(&format!("[Tray] 更新托盘图标失败: {}", e));
+        }
+    }
+}
+
 /// 设备指纹信息（兼容旧 API）
 #[derive(Debug, Serialize)]
 pub struct DeviceProfiles {
@@ -0,0 +1,381 @@
+//! 按条件导出账号
+//!
+//! - `full`：包含凭据，Antigravity 为可重新导入的 `{email, refresh_token}`，Codex/Copilot 为完整账号
+//! - `redacted`：仅邮箱、标签、状态与配额，用于汇报
+//! - `csv`：每个账号每个模型一行的配额汇总
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::codex::CodexAccount;
+use crate::models::copilot::CopilotAccount;
+use crate::models::{Account, QuotaData};
+use crate::modules::websocket::AccountProvider;
+use crate::modules::{account, codex_account, copilot_account};
+
+/// 导出筛选条件（各条件同时满足；列表为空表示不限）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExportFilter {
+    #[serde(default)]
+    pub providers: Vec<AccountProvider>,
+    /// 任一标签匹配即可（不区分大小写）
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 按禁用状态筛选
+    #[serde(default)]
+    pub disabled: Option<bool>,
+    /// 订阅等级 / 套餐（不区分大小写）
+    #[serde(default)]
+    pub tiers: Vec<String>,
+    #[serde(default)]
+    pub account_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportMode {
+    #[default]
+    Full,
+    Redacted,
+    Csv,
+}
+
+/// 脱敏后的模型配额
+#[derive(Debug, Clone, Serialize)]
+pub struct RedactedQuota {
+    pub model: String,
+    pub remaining_percentage: Option<i32>,
+    pub reset_time: Option<String>,
+}
+
+/// 脱敏后的账号
+#[derive(Debug, Clone, Serialize)]
+pub struct RedactedAccount {
+    pub provider: AccountProvider,
+    pub email: String,
+    pub tags: Vec<String>,
+    pub disabled: bool,
+    pub tier: Option<String>,
+    pub quota: Vec<RedactedQuota>,
+}
+
+/// 各平台账号统一视图
+struct ExportItem {
+    provider: AccountProvider,
+    id: String,
+    email: String,
+    tags: Vec<String>,
+    disabled: bool,
+    tier: Option<String>,
+    quota: Vec<RedactedQuota>,
+    credentials: serde_json::Value,
+}
+
+fn antigravity_quota(quota: Option<&QuotaData>) -> Vec<RedactedQuota> {
+    quota
+        .map(|q| {
+            q.models
+                .iter()
+                .map(|m| RedactedQuota {
+                    model: m.name.clone(),
+                    remaining_percentage: Some(m.percentage),
+                    reset_time: Some(m.reset_time.clone()).filter(|t| !t.is_empty()),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn format_timestamp(ts: Option<i64>) -> Option<String> {
+    ts.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.to_rfc3339())
+}
+
+fn from_antigravity(account: Account) -> ExportItem {
+    ExportItem {
+        provider: AccountProvider::Antigravity,
+        quota: antigravity_quota(account.quota.as_ref()),
+        tier: account.quota.as_ref().and_then(|q| q.subscription_tier.clone()),
+        credentials: serde_json::json!({
+            "email": account.email,
+            "refresh_token": account.token.refresh_token,
+        }),
+        id: account.id,
+        email: account.email,
+        tags: account.tags,
+        disabled: account.disabled,
+    }
+}
+
+fn from_codex(account: CodexAccount) -> ExportItem {
+    let quota = account
+        .quota
+        .as_ref()
+        .map(|q| {
+            let mut items = vec![
+                RedactedQuota {
+                    model: "5h".to_string(),
+                    remaining_percentage: Some(q.hourly_percentage),
+                    reset_time: format_timestamp(q.hourly_reset_time),
+                },
+                RedactedQuota {
+                    model: "weekly".to_string(),
+                    remaining_percentage: Some(q.weekly_percentage),
+                    reset_time: format_timestamp(q.weekly_reset_time),
+                },
+            ];
+            if let Some(code_review) = &q.code_review {
+                items.push(RedactedQuota {
+                    model: "code_review".to_string(),
+                    remaining_percentage: Some(code_review.percentage),
+                    reset_time: format_timestamp(code_review.reset_time),
+                });
+            }
+            items
+        })
+        .unwrap_or_default();
+    ExportItem {
+        provider: AccountProvider::Codex,
+        id: account.id.clone(),
+        email: account.email.clone(),
+        tags: account.tags.clone().unwrap_or_default(),
+        disabled: account.disabled,
+        tier: account.plan_type.clone(),
+        quota,
+        credentials: serde_json::to_value(&account).unwrap_or_default(),
+    }
+}
+
+fn from_copilot(account: CopilotAccount) -> ExportItem {
+    let quota = account
+        .quota
+        .as_ref()
+        .map(|q| {
+            if q.snapshots.is_empty() {
+                let remaining_percentage = match (q.remaining_requests, q.included_requests) {
+                    (Some(remaining), Some(included)) if included > 0 => {
+                        Some(((remaining as f64 / included as f64) * 100.0).round() as i32)
+                    }
+                    _ => None,
+                };
+                return vec![RedactedQuota {
+                    model: "premium_requests".to_string(),
+                    remaining_percentage,
+                    reset_time: q.quota_reset_date.clone(),
+                }];
+            }
+            q.snapshots
+                .iter()
+                .map(|snapshot| RedactedQuota {
+                    model: snapshot.quota_id.clone(),
+                    remaining_percentage: if snapshot.unlimited {
+                        Some(100)
+                    } else {
+                        snapshot.percent_remaining.map(|p| p.round() as i32)
+                    },
+                    reset_time: q.quota_reset_date.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    ExportItem {
+        provider: AccountProvider::Copilot,
+        id: account.id.clone(),
+        email: account.email.clone().unwrap_or_else(|| account.username.clone()),
+        tags: account.tags.clone().unwrap_or_default(),
+        disabled: account.disabled,
+        tier: account.plan.clone(),
+        quota,
+        credentials: serde_json::to_value(&account).unwrap_or_default(),
+    }
+}
+
+fn matches(item: &ExportItem, filter: &ExportFilter) -> bool {
+    if !filter.providers.is_empty() && !filter.providers.contains(&item.provider) {
+        return false;
+    }
+    if !filter.account_ids.is_empty() && !filter.account_ids.contains(&item.id) {
+        return false;
+    }
+    if let Some(disabled) = filter.disabled {
+        if item.disabled != disabled {
+            return false;
+        }
+    }
+    if !filter.tags.is_empty()
+        && !item
+            .tags
+            .iter()
+            .any(|tag| filter.tags.iter().any(|wanted| wanted.eq_ignore_ascii_case(tag)))
+    {
+        return false;
+    }
+    if !filter.tiers.is_empty() {
+        let Some(tier) = item.tier.as_deref() else {
+            return false;
+        };
+        if !filter.tiers.iter().any(|wanted| wanted.eq_ignore_ascii_case(tier)) {
+            return false;
+        }
+    }
+    true
+}
+
+fn wants(filter: &ExportFilter, provider: AccountProvider) -> bool {
+    filter.providers.is_empty() || filter.providers.contains(&provider)
+}
+
+fn collect_items(filter: &ExportFilter) -> Result<Vec<ExportItem>, String> {
+    let mut items = Vec::new();
+    if wants(filter, AccountProvider::Antigravity) {
+        items.extend(account::list_accounts()?.into_iter().map(from_antigravity));
+    }
+    if wants(filter, AccountProvider::Codex) {
+        items.extend(codex_account::list_accounts().into_iter().map(from_codex));
+    }
+    if wants(filter, AccountProvider::Copilot) {
+        items.extend(copilot_account::list_accounts().into_iter().map(from_copilot));
+    }
+    items.retain(|item| matches(item, filter));
+    Ok(items)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn provider_name(provider: AccountProvider) -> &'static str {
+    match provider {
+        AccountProvider::Antigravity => "antigravity",
+        AccountProvider::Codex => "codex",
+        AccountProvider::Copilot => "copilot",
+    }
+}
+
+fn render(items: Vec<ExportItem>, mode: ExportMode) -> Result<String, String> {
+    match mode {
+        ExportMode::Full => {
+            let mut grouped = serde_json::Map::new();
+            for item in items {
+                grouped
+                    .entry(provider_name(item.provider))
+                    .or_insert_with(|| serde_json::Value::Array(Vec::new()))
+                    .as_array_mut()
+                    .expect("provider group is array")
+                    .push(item.credentials);
+            }
+            serde_json::to_string_pretty(&grouped).map_err(|e| format!("序列化失败: {}", e))
+        }
+        ExportMode::Redacted => {
+            let redacted: Vec<RedactedAccount> = items
+                .into_iter()
+                .map(|item| RedactedAccount {
+                    provider: item.provider,
+                    email: item.email,
+                    tags: item.tags,
+                    disabled: item.disabled,
+                    tier: item.tier,
+                    quota: item.quota,
+                })
+                .collect();
+            serde_json::to_string_pretty(&redacted).map_err(|e| format!("序列化失败: {}", e))
+        }
+        ExportMode::Csv => {
+            let mut out = String::from("provider,email,tier,tags,disabled,model,remaining_percentage,reset_time\n");
+            for item in items {
+                let prefix = [
+                    provider_name(item.provider).to_string(),
+                    item.email.clone(),
+                    item.tier.clone().unwrap_or_default(),
+                    item.tags.join(";"),
+                    item.disabled.to_string(),
+                ];
+                let rows: Vec<[String; 3]> = if item.quota.is_empty() {
+                    vec![[String::new(), String::new(), String::new()]]
+                } else {
+                    item.quota
+                        .iter()
+                        .map(|q| {
+                            [
+                                q.model.clone(),
+                                q.remaining_percentage.map(|p| p.to_string()).unwrap_or_default(),
+                                q.reset_time.clone().unwrap_or_default(),
+                            ]
+                        })
+                        .collect()
+                };
+                for row in rows {
+                    let line: Vec<String> = prefix.iter().chain(row.iter()).map(|v| csv_field(v)).collect();
+                    out.push_str(&line.join(","));
+                    out.push('\n');
+                }
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// 按条件导出账号
+pub fn export_accounts(filter: &ExportFilter, mode: ExportMode) -> Result<String, String> {
+    let items = collect_items(filter)?;
+    crate::modules::logger::log_info(&format!("[Export] 导出 {} 个账号 (mode={:?})", items.len(), mode));
+    render(items, mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(provider: AccountProvider, tags: &[&str], disabled: bool, tier: Option<&str>) -> ExportItem {
+        ExportItem {
+            provider,
+            id: "id-1".to_string(),
+            email: "a@example.com".to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            disabled,
+            tier: tier.map(str::to_string),
+            quota: vec![RedactedQuota {
+                model: "gemini, pro".to_string(),
+                remaining_percentage: Some(40),
+                reset_time: None,
+            }],
+            credentials: serde_json::json!({"refresh_token": "secret"}),
+        }
+    }
+
+    #[test]
+    fn filter_combines_conditions() {
+        let filter = ExportFilter {
+            tags: vec!["Team".to_string()],
+            disabled: Some(false),
+            tiers: vec!["pro".to_string()],
+            ..Default::default()
+        };
+        assert!(matches(&item(AccountProvider::Antigravity, &["team"], false, Some("PRO")), &filter));
+        assert!(!matches(&item(AccountProvider::Antigravity, &["other"], false, Some("PRO")), &filter));
+        assert!(!matches(&item(AccountProvider::Antigravity, &["team"], true, Some("PRO")), &filter));
+        assert!(!matches(&item(AccountProvider::Antigravity, &["team"], false, None), &filter));
+
+        let by_provider = ExportFilter {
+            providers: vec![AccountProvider::Codex],
+            ..Default::default()
+        };
+        assert!(!matches(&item(AccountProvider::Antigravity, &[], false, None), &by_provider));
+    }
+
+    #[test]
+    fn redacted_and_csv_omit_credentials() {
+        let redacted = render(vec![item(AccountProvider::Codex, &[], false, None)], ExportMode::Redacted).unwrap();
+        assert!(!redacted.contains("secret"));
+
+        let csv = render(vec![item(AccountProvider::Codex, &["a", "b"], false, Some("plus"))], ExportMode::Csv).unwrap();
+        assert!(!csv.contains("secret"));
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "codex,a@example.com,plus,a;b,false,\"gemini, pro\",40,"
+        );
+    }
+}
@@ -0,0 +1,1108 @@
+//! 账号池文件夹同步
+//!
+//! 将账号、标签、指纹及绑定关系、分组设置同步到用户指定的文件夹（如网络共享盘），
+//! 多台设备指向同一文件夹即可共享账号池。
+//!
+//! 设计说明:
+//! - 每条记录（如 `account:<email>`）带 `updated_at` / `updated_by`，删除以墓碑记录表示；
+//!   `updated_at` 取本地实际修改的时间（保存账号、指纹、分组时记入编辑记录），而非同步时间
+//! - 本地状态文件保存上次同步后的记录元数据，据此判断本地是否修改、删除
+//! - 合并规则确定：`updated_at` 大者胜，相同时依次比较设备 ID 与内容哈希
+//! - 双方在上次同步后都修改了同一条记录时计为冲突，按上述规则取胜方并写入报告
+//! - 共享文件整体原子替换；并发写入丢失的修改会在下次同步时重新推送
+//! - refresh_token 使用同步口令加密后写入（各设备需设置相同口令）；未设置口令时不写入 Token，
+//!   新设备上的账号需设置口令后才能创建
+//! - 共享文件记录写入代数及各设备最近读取的代数，墓碑在所有已知设备都读取过后才清除
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use base64::{engine::general_purpose, Engine as _};
+
+use crate::models::TokenData;
+use crate::modules::fingerprint::{self, Fingerprint};
+use crate::modules::{account, backup, config, group_settings, logger};
+
+/// 共享文件夹中的同步文件名
+const SYNC_FILE: &str = "cockpit_sync.json";
+
+/// 本地同步状态文件名（位于数据目录）
+const SYNC_STATE_FILE: &str = "account_sync_state.json";
+
+/// 本地编辑记录文件名（位于数据目录）
+const EDIT_JOURNAL_FILE: &str = "account_sync_edits.json";
+
+/// 同步文件格式版本（v2: 加密 Token、设备代数）
+const SYNC_FORMAT_VERSION: u32 = 2;
+
+/// 账号记录中加密后的 refresh_token 字段
+const SEALED_TOKEN_FIELD: &str = "refresh_token_sealed";
+
+const GROUP_SETTINGS_KEY: &str = "group_settings";
+
+/// 防止并发同步
+static SYNC_LOCK: Mutex<()> = Mutex::new(());
+
+/// 编辑记录（首次使用时从文件加载）
+static EDIT_JOURNAL: Mutex<Option<BTreeMap<String, LocalEdit>>> = Mutex::new(None);
+
+/// 同步条目最近一次在本地被修改时的内容哈希与时间（毫秒）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LocalEdit {
+    hash: String,
+    edited_at: i64,
+}
+
+/// 单条同步记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncRecord {
+    /// 最后修改时间（毫秒）
+    pub updated_at: i64,
+    /// 最后修改的设备 ID
+    pub updated_by: String,
+    /// 墓碑标记
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+    /// 内容哈希（墓碑为空）
+    #[serde(default)]
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl SyncRecord {
+    fn same_content(&self, other: &SyncRecord) -> bool {
+        self.deleted == other.deleted && self.hash == other.hash
+    }
+
+    /// 确定性比较：时间戳、设备 ID、内容哈希依次比较
+    fn wins_over(&self, other: &SyncRecord) -> bool {
+        (self.updated_at, &self.updated_by, self.deleted, &self.hash)
+            > (other.updated_at, &other.updated_by, other.deleted, &other.hash)
+    }
+
+    /// 相对上次同步的记录是否发生了变化
+    fn changed_since(&self, base: Option<&SyncRecord>) -> bool {
+        match base {
+            Some(base) => base.updated_at != self.updated_at || !base.same_content(self),
+            None => true,
+        }
+    }
+
+    fn metadata(&self) -> SyncRecord {
+        SyncRecord { data: None, ..self.clone() }
+    }
+}
+
+/// 共享文件夹中的同步文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SyncSnapshot {
+    format_version: u32,
+    updated_at: i64,
+    updated_by: String,
+    /// 写入代数，每次写入递增
+    #[serde(default)]
+    generation: u64,
+    /// 各设备最近一次同步写入的代数
+    #[serde(default)]
+    peers: BTreeMap<String, u64>,
+    /// 墓碑首次写入时的代数
+    #[serde(default)]
+    tombstones: BTreeMap<String, u64>,
+    /// 加密 refresh_token 使用的盐（base64）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token_salt: Option<String>,
+    #[serde(default)]
+    records: BTreeMap<String, SyncRecord>,
+}
+
+/// 本地同步状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SyncState {
+    #[serde(default)]
+    device_id: String,
+    #[serde(default)]
+    last_synced_at: Option<i64>,
+    /// 上次同步后各记录的元数据（不含内容）
+    #[serde(default)]
+    records: BTreeMap<String, SyncRecord>,
+}
+
+/// 冲突中的胜出方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncSide {
+    Local,
+    Remote,
+}
+
+/// 冲突记录
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflict {
+    pub key: String,
+    pub local_updated_at: i64,
+    pub local_deleted: bool,
+    pub remote_updated_at: i64,
+    pub remote_updated_by: String,
+    pub remote_deleted: bool,
+    pub winner: SyncSide,
+}
+
+/// 同步结果
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSyncReport {
+    pub folder: String,
+    pub synced_at: i64,
+    /// 从共享文件夹拉取并应用到本地的记录
+    pub pulled: Vec<String>,
+    /// 推送到共享文件夹的记录
+    pub pushed: Vec<String>,
+    pub conflicts: Vec<SyncConflict>,
+    /// 应用失败的记录（下次同步会重试）
+    pub errors: Vec<String>,
+}
+
+/// 同步状态
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSyncStatus {
+    pub folder: String,
+    pub device_id: String,
+    pub last_synced_at: Option<i64>,
+    /// 是否已设置同步口令（设置后才会同步 refresh_token）
+    pub tokens_encrypted: bool,
+}
+
+/// 合并结果
+#[derive(Debug, Default)]
+struct MergeOutcome {
+    merged: BTreeMap<String, SyncRecord>,
+    /// 远端胜出且与本地不同，需要应用到本地
+    apply: Vec<String>,
+    pushed: Vec<String>,
+    conflicts: Vec<SyncConflict>,
+}
+
+/// 计算内容哈希（对象键排序后序列化，保证与插入顺序无关）
+///
+/// refresh_token 以摘要参与计算，密文不参与，使哈希与是否加密、随机 nonce 无关。
+fn content_hash(value: &Value) -> String {
+    fn canonical(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let sorted: BTreeMap<&String, Value> = map.iter().map(|(k, v)| (k, canonical(v))).collect();
+                Value::Object(sorted.into_iter().map(|(k, v)| (k.clone(), v)).collect())
+            }
+            Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+            other => other.clone(),
+        }
+    }
+    let mut value = canonical(value);
+    if let Value::Object(map) = &mut value {
+        map.remove(SEALED_TOKEN_FIELD);
+        if let Some(Value::String(token)) = map.get_mut("refresh_token") {
+            *token = backup::sha256_hex(token.as_bytes());
+        }
+    }
+    backup::sha256_hex(value.to_string().as_bytes())
+}
+
+/// refresh_token 加解密（PBKDF2 + AES-256-GCM，与加密备份相同）
+struct TokenCipher {
+    key: ring::aead::LessSafeKey,
+}
+
+impl TokenCipher {
+    fn new(passphrase: &str, salt: &[u8]) -> Result<Self, String> {
+        Ok(Self { key: backup::derive_key(passphrase, salt)? })
+    }
+
+    /// 加密后输出 base64(nonce || 密文 || tag)，以记录键作为附加数据
+    fn seal(&self, record_key: &str, token: &str) -> Result<String, String> {
+        use rand::RngCore;
+        let mut nonce = [0u8; backup::NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let mut buffer = token.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                ring::aead::Nonce::assume_unique_for_key(nonce),
+                ring::aead::Aad::from(record_key.as_bytes()),
+                &mut buffer,
+            )
+            .map_err(|_| "加密 Token 失败".to_string())?;
+        let mut output = nonce.to_vec();
+        output.extend_from_slice(&buffer);
+        Ok(general_purpose::STANDARD.encode(output))
+    }
+
+    fn open(&self, record_key: &str, sealed: &str) -> Result<String, String> {
+        let mut data = general_purpose::STANDARD
+            .decode(sealed)
+            .map_err(|e| format!("Token 密文格式错误: {}", e))?;
+        if data.len() < backup::NONCE_LEN {
+            return Err("Token 密文格式错误".to_string());
+        }
+        let mut nonce = [0u8; backup::NONCE_LEN];
+        nonce.copy_from_slice(&data[..backup::NONCE_LEN]);
+        let plaintext = self
+            .key
+            .open_in_place(
+                ring::aead::Nonce::assume_unique_for_key(nonce),
+                ring::aead::Aad::from(record_key.as_bytes()),
+                &mut data[backup::NONCE_LEN..],
+            )
+            .map_err(|_| "解密 Token 失败，同步口令可能与其他设备不一致".to_string())?;
+        String::from_utf8(plaintext.to_vec()).map_err(|_| "Token 内容无效".to_string())
+    }
+}
+
+fn account_data_mut<'a>(key: &str, record: &'a mut SyncRecord) -> Option<&'a mut serde_json::Map<String, Value>> {
+    if !key.starts_with("account:") {
+        return None;
+    }
+    match record.data.as_mut() {
+        Some(Value::Object(map)) => Some(map),
+        _ => None,
+    }
+}
+
+/// 写入共享文件前加密 refresh_token
+///
+/// 未设置口令时不写入明文；内容未变的记录沿用远端已有的密文，避免丢失其他设备写入的 Token。
+fn seal_tokens(
+    records: &mut BTreeMap<String, SyncRecord>,
+    remote: &BTreeMap<String, SyncRecord>,
+    cipher: Option<&TokenCipher>,
+) -> Result<(), String> {
+    for (key, record) in records.iter_mut() {
+        let hash = record.hash.clone();
+        let Some(map) = account_data_mut(key, record) else {
+            continue;
+        };
+        let Some(Value::String(token)) = map.remove("refresh_token") else {
+            continue;
+        };
+        let sealed = match cipher {
+            Some(cipher) if !token.is_empty() => Some(Value::String(cipher.seal(key, &token)?)),
+            Some(_) => None,
+            None => remote
+                .get(key)
+                .filter(|r| r.hash == hash)
+                .and_then(|r| r.data.as_ref()?.get(SEALED_TOKEN_FIELD).cloned()),
+        };
+        if let Some(sealed) = sealed {
+            map.insert(SEALED_TOKEN_FIELD.to_string(), sealed);
+        }
+    }
+    Ok(())
+}
+
+/// 解密远端记录中的 refresh_token；无法解密的密文原样保留
+fn open_tokens(records: &mut BTreeMap<String, SyncRecord>, cipher: Option<&TokenCipher>) {
+    let Some(cipher) = cipher else {
+        return;
+    };
+    for (key, record) in records.iter_mut() {
+        let Some(map) = account_data_mut(key, record) else {
+            continue;
+        };
+        let Some(Value::String(sealed)) = map.get(SEALED_TOKEN_FIELD) else {
+            continue;
+        };
+        match cipher.open(key, sealed) {
+            Ok(token) => {
+                map.remove(SEALED_TOKEN_FIELD);
+                map.insert("refresh_token".to_string(), Value::String(token));
+            }
+            Err(e) => logger::log_warn(&format!("[AccountSync] {}: {}", key, e)),
+        }
+    }
+}
+
+/// 根据上次同步状态为当前本地数据生成带时间戳的记录（含本地删除产生的墓碑）
+///
+/// 修改时间优先取编辑记录中内容一致的时间，没有记录（如在应用外修改了文件）时才使用 `now`。
+fn build_local_records(
+    current: BTreeMap<String, Value>,
+    base: &BTreeMap<String, SyncRecord>,
+    edits: &BTreeMap<String, LocalEdit>,
+    device_id: &str,
+    now: i64,
+) -> BTreeMap<String, SyncRecord> {
+    let mut records = BTreeMap::new();
+    let edited_at = |key: &str, hash: &str| {
+        edits
+            .get(key)
+            .filter(|edit| edit.hash == hash)
+            .map(|edit| edit.edited_at)
+            .unwrap_or(now)
+    };
+
+    for (key, value) in current {
+        let hash = content_hash(&value);
+        let record = match base.get(&key) {
+            Some(prev) if !prev.deleted && prev.hash == hash => SyncRecord {
+                data: Some(value),
+                ..prev.metadata()
+            },
+            _ => SyncRecord {
+                updated_at: edited_at(&key, &hash),
+                updated_by: device_id.to_string(),
+                deleted: false,
+                hash,
+                data: Some(value),
+            },
+        };
+        records.insert(key, record);
+    }
+
+    for (key, prev) in base {
+        if records.contains_key(key) {
+            continue;
+        }
+        let tombstone = if prev.deleted {
+            prev.metadata()
+        } else {
+            SyncRecord {
+                updated_at: edited_at(key, ""),
+                updated_by: device_id.to_string(),
+                deleted: true,
+                hash: String::new(),
+                data: None,
+            }
+        };
+        records.insert(key.clone(), tombstone);
+    }
+
+    records
+}
+
+/// 三方合并本地记录与远端记录
+fn merge_records(
+    local: &BTreeMap<String, SyncRecord>,
+    remote: &BTreeMap<String, SyncRecord>,
+    base: &BTreeMap<String, SyncRecord>,
+) -> MergeOutcome {
+    let mut outcome = MergeOutcome::default();
+    let keys: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+
+    for key in keys {
+        match (local.get(key), remote.get(key)) {
+            // 上次同步已写入的墓碑已被清除，无需再推送
+            (Some(l), None) if l.deleted && !l.changed_since(base.get(key)) => {}
+            (Some(l), None) => {
+                outcome.pushed.push(key.clone());
+                outcome.merged.insert(key.clone(), l.clone());
+            }
+            (None, Some(r)) => {
+                if !r.deleted {
+                    outcome.apply.push(key.clone());
+                }
+                outcome.merged.insert(key.clone(), r.clone());
+            }
+            (Some(l), Some(r)) => {
+                if l.same_content(r) {
+                    let newer = if r.wins_over(l) { r } else { l };
+                    outcome.merged.insert(key.clone(), SyncRecord {
+                        data: l.data.clone().or_else(|| r.data.clone()),
+                        ..newer.metadata()
+                    });
+                    continue;
+                }
+
+                let winner = if r.wins_over(l) { SyncSide::Remote } else { SyncSide::Local };
+                let base_record = base.get(key);
+                if l.changed_since(base_record) && r.changed_since(base_record) {
+                    outcome.conflicts.push(SyncConflict {
+                        key: key.clone(),
+                        local_updated_at: l.updated_at,
+                        local_deleted: l.deleted,
+                        remote_updated_at: r.updated_at,
+                        remote_updated_by: r.updated_by.clone(),
+                        remote_deleted: r.deleted,
+                        winner,
+                    });
+                }
+
+                match winner {
+                    SyncSide::Remote => {
+                        outcome.apply.push(key.clone());
+                        outcome.merged.insert(key.clone(), r.clone());
+                    }
+                    SyncSide::Local => {
+                        outcome.pushed.push(key.clone());
+                        outcome.merged.insert(key.clone(), l.clone());
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    outcome
+}
+
+/// 清除所有已知设备都已读取过的墓碑，返回保留墓碑的首次写入代数
+///
+/// `peers` 为各设备最近同步写入的代数，设备读取过某代数的文件后才会写入更高代数。
+fn prune_tombstones(
+    merged: &mut BTreeMap<String, SyncRecord>,
+    remote: &SyncSnapshot,
+    peers: &BTreeMap<String, u64>,
+    generation: u64,
+) -> BTreeMap<String, u64> {
+    let mut tombstones = BTreeMap::new();
+    merged.retain(|key, record| {
+        if !record.deleted {
+            return true;
+        }
+        let written_in = match (remote.records.get(key), remote.tombstones.get(key)) {
+            (Some(r), Some(&written_in)) if r.deleted => written_in,
+            _ => generation,
+        };
+        if peers.values().all(|&seen| seen > written_in) {
+            return false;
+        }
+        tombstones.insert(key.clone(), written_in);
+        true
+    });
+    tombstones
+}
+
+/// 应用顺序：先指纹与账号，再标签与绑定，最后分组设置
+fn apply_priority(key: &str) -> u8 {
+    match key.split_once(':').map(|(kind, _)| kind).unwrap_or(key) {
+        "fingerprint" => 0,
+        "account" => 1,
+        "tags" | "binding" => 2,
+        _ => 3,
+    }
+}
+
+/// 收集本地需要同步的数据
+fn collect_local_data() -> Result<BTreeMap<String, Value>, String> {
+    let mut data = BTreeMap::new();
+
+    for acc in account::list_accounts()? {
+        for (key, value) in account_entries(&acc) {
+            if let Some(value) = value {
+                data.insert(key, value);
+            }
+        }
+    }
+
+    for fp in fingerprint::load_fingerprint_store()?.fingerprints {
+        data.insert(format!("fingerprint:{}", fp.id), fingerprint_value(&fp));
+    }
+
+    let groups = serde_json::to_value(group_settings::load_group_settings())
+        .map_err(|e| format!("序列化分组设置失败: {}", e))?;
+    data.insert(GROUP_SETTINGS_KEY.to_string(), groups);
+
+    Ok(data)
+}
+
+/// 账号对应的同步条目（`None` 表示该条目不存在，如没有标签）
+fn account_entries(acc: &crate::models::Account) -> Vec<(String, Option<Value>)> {
+    let email = acc.email.trim().to_string();
+    let tags = (!acc.tags.is_empty()).then(|| {
+        let mut tags = acc.tags.clone();
+        tags.sort();
+        tags.dedup();
+        serde_json::json!(tags)
+    });
+    vec![
+        (
+            format!("account:{}", email),
+            Some(serde_json::json!({
+                "email": email,
+                "name": acc.name,
+                "refresh_token": acc.token.refresh_token,
+            })),
+        ),
+        (format!("tags:{}", email), tags),
+        (format!("binding:{}", email), acc.fingerprint_id.clone().map(Value::String)),
+    ]
+}
+
+fn fingerprint_value(fp: &Fingerprint) -> Value {
+    serde_json::json!({
+        "name": fp.name,
+        "profile": fp.profile,
+        "created_at": fp.created_at,
+    })
+}
+
+fn edit_journal_path() -> Result<PathBuf, String> {
+    Ok(account::get_data_dir()?.join(EDIT_JOURNAL_FILE))
+}
+
+/// 在编辑记录上执行操作，记录有变化时写回文件
+fn update_edit_journal(update: impl FnOnce(&mut BTreeMap<String, LocalEdit>) -> bool) {
+    let Ok(mut guard) = EDIT_JOURNAL.lock() else {
+        return;
+    };
+    let journal = guard.get_or_insert_with(|| {
+        edit_journal_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    });
+    if !update(journal) {
+        return;
+    }
+    if let Err(e) = edit_journal_path().and_then(|path| write_json_atomic(&path, &*journal)) {
+        logger::log_warn(&format!("[AccountSync] 保存编辑记录失败: {}", e));
+    }
+}
+
+/// 记录条目内容的变化（`None` 表示已删除），内容未变时保留原修改时间
+fn record_edits(entries: Vec<(String, Option<Value>)>) {
+    let now = chrono::Utc::now().timestamp_millis();
+    update_edit_journal(|journal| {
+        let mut changed = false;
+        for (key, value) in entries {
+            let hash = value.as_ref().map(content_hash).unwrap_or_default();
+            if journal.get(&key).map(|edit| edit.hash.as_str()) != Some(hash.as_str()) {
+                journal.insert(key, LocalEdit { hash, edited_at: now });
+                changed = true;
+            }
+        }
+        changed
+    });
+}
+
+/// 保存账号后调用，记录账号、标签与指纹绑定的修改时间
+pub(crate) fn record_account_saved(acc: &crate::models::Account) {
+    record_edits(account_entries(acc));
+}
+
+/// 删除账号后调用
+pub(crate) fn record_account_deleted(email: &str) {
+    let email = email.trim();
+    record_edits(
+        ["account", "tags", "binding"]
+            .iter()
+            .map(|kind| (format!("{}:{}", kind, email), None))
+            .collect(),
+    );
+}
+
+/// 保存指纹存储后调用
+pub(crate) fn record_fingerprints_saved(fingerprints: &[Fingerprint]) {
+    record_edits(
+        fingerprints
+            .iter()
+            .map(|fp| (format!("fingerprint:{}", fp.id), Some(fingerprint_value(fp))))
+            .collect(),
+    );
+}
+
+/// 删除指纹后调用
+pub(crate) fn record_fingerprint_deleted(fingerprint_id: &str) {
+    record_edits(vec![(format!("fingerprint:{}", fingerprint_id), None)]);
+}
+
+/// 保存分组设置后调用
+pub(crate) fn record_group_settings_saved(settings: &group_settings::GroupSettings) {
+    if let Ok(value) = serde_json::to_value(settings) {
+        record_edits(vec![(GROUP_SETTINGS_KEY.to_string(), Some(value))]);
+    }
+}
+
+fn find_account_by_email(email: &str) -> Result<Option<crate::models::Account>, String> {
+    Ok(account::list_accounts()?.into_iter().find(|acc| acc.email.trim() == email))
+}
+
+fn record_data<T: serde::de::DeserializeOwned>(key: &str, record: &SyncRecord) -> Result<T, String> {
+    let data = record.data.clone().ok_or_else(|| format!("记录缺少内容: {}", key))?;
+    serde_json::from_value(data).map_err(|e| format!("记录格式错误 ({}): {}", key, e))
+}
+
+/// 合并结果中账号的指纹绑定（指纹需已存在于本地）
+fn synced_binding(email: &str, merged: &BTreeMap<String, SyncRecord>) -> Result<Option<String>, String> {
+    let key = format!("binding:{}", email);
+    let Some(record) = merged.get(&key).filter(|record| !record.deleted) else {
+        return Ok(None);
+    };
+    let fingerprint_id: String = record_data(&key, record)?;
+    if fingerprint_id != "original" && fingerprint::get_fingerprint(&fingerprint_id).is_err() {
+        return Ok(None);
+    }
+    Ok(Some(fingerprint_id))
+}
+
+/// 将远端记录应用到本地
+fn apply_record(key: &str, record: &SyncRecord, merged: &BTreeMap<String, SyncRecord>) -> Result<(), String> {
+    let (kind, id) = key.split_once(':').unwrap_or((key, ""));
+    match kind {
+        "fingerprint" => {
+            if record.deleted {
+                return match fingerprint::delete_fingerprint(id) {
+                    Err(e) if e == "指纹不存在" => Ok(()),
+                    other => other,
+                };
+            }
+            #[derive(Deserialize)]
+            struct FingerprintData {
+                name: String,
+                profile: crate::models::DeviceProfile,
+                created_at: i64,
+            }
+            let data: FingerprintData = record_data(key, record)?;
+            let mut store = fingerprint::load_fingerprint_store()?;
+            let incoming = Fingerprint {
+                id: id.to_string(),
+                name: data.name,
+                profile: data.profile,
+                created_at: data.created_at,
+            };
+            match store.fingerprints.iter_mut().find(|fp| fp.id == id) {
+                Some(existing) => *existing = incoming,
+                None => store.fingerprints.push(incoming),
+            }
+            fingerprint::save_fingerprint_store(&store)
+        }
+        "account" => {
+            let local = find_account_by_email(id)?;
+            if record.deleted {
+                return match local {
+                    Some(acc) => account::delete_account(&acc.id),
+                    None => Ok(()),
+                };
+            }
+            #[derive(Deserialize)]
+            struct AccountData {
+                email: String,
+                name: Option<String>,
+                #[serde(default)]
+                refresh_token: Option<String>,
+            }
+            let data: AccountData = record_data(key, record)?;
+            let refresh_token = data.refresh_token.filter(|token| !token.is_empty());
+            // access_token 留空，首次使用时通过 refresh_token 刷新
+            let new_token =
+                |refresh_token: String| TokenData::new(String::new(), refresh_token, 0, Some(data.email.clone()), None, None);
+            match local {
+                Some(acc) => {
+                    let token = match refresh_token {
+                        Some(refresh_token) if refresh_token != acc.token.refresh_token => new_token(refresh_token),
+                        // 记录未携带可解密的 Token 时保留本地 Token
+                        _ => acc.token,
+                    };
+                    account::upsert_account(data.email, data.name, token).map(|_| ())
+                }
+                None => {
+                    let refresh_token = refresh_token
+                        .ok_or_else(|| "记录未携带可解密的 refresh_token，请设置与其他设备相同的同步口令".to_string())?;
+                    let token = new_token(refresh_token);
+                    // 直接使用同步的绑定关系创建账号，避免生成随后被覆盖的新指纹
+                    let fingerprint_id = synced_binding(id, merged)?;
+                    account::add_account_with_fingerprint(data.email, data.name, token, fingerprint_id).map(|_| ())
+                }
+            }
+        }
+        "tags" => {
+            let Some(acc) = find_account_by_email(id)? else {
+                return if record.deleted { Ok(()) } else { Err(format!("账号不存在: {}", id)) };
+            };
+            let tags: Vec<String> = if record.deleted { Vec::new() } else { record_data(key, record)? };
+            account::update_account_tags(&acc.id, tags).map(|_| ())
+        }
+        "binding" => {
+            let Some(mut acc) = find_account_by_email(id)? else {
+                return if record.deleted { Ok(()) } else { Err(format!("账号不存在: {}", id)) };
+            };
+            if record.deleted {
+                acc.fingerprint_id = None;
+                return account::save_account(&acc);
+            }
+            let fingerprint_id: String = record_data(key, record)?;
+            if fingerprint_id != "original" {
+                fingerprint::get_fingerprint(&fingerprint_id)?;
+            }
+            acc.fingerprint_id = Some(fingerprint_id);
+            account::save_account(&acc)
+        }
+        GROUP_SETTINGS_KEY => {
+            if record.deleted {
+                return Ok(());
+            }
+            let settings: group_settings::GroupSettings = record_data(key, record)?;
+            group_settings::save_group_settings(&settings)
+        }
+        _ => Err(format!("未知的同步记录类型: {}", key)),
+    }
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(account::get_data_dir()?.join(SYNC_STATE_FILE))
+}
+
+fn load_state() -> Result<SyncState, String> {
+    let path = state_path()?;
+    let mut state: SyncState = if path.exists() {
+        let content = fs::read_to_string(&path).map_err(|e| format!("读取同步状态失败: {}", e))?;
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            logger::log_warn(&format!("[AccountSync] 解析同步状态失败，将重新同步: {}", e));
+            SyncState::default()
+        })
+    } else {
+        SyncState::default()
+    };
+    if state.device_id.is_empty() {
+        state.device_id = uuid::Uuid::new_v4().to_string();
+    }
+    Ok(state)
+}
+
+fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(value).map_err(|e| format!("序列化失败: {}", e))?;
+    // 共享文件夹中可能有多台设备同时写入，临时文件名需唯一
+    let temp_path = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    fs::write(&temp_path, content).map_err(|e| format!("写入文件失败 ({}): {}", temp_path.display(), e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("替换文件失败 ({}): {}", path.display(), e))
+}
+
+fn read_snapshot(path: &Path) -> Result<SyncSnapshot, String> {
+    if !path.exists() {
+        return Ok(SyncSnapshot::default());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("读取同步文件失败: {}", e))?;
+    let snapshot: SyncSnapshot =
+        serde_json::from_str(&content).map_err(|e| format!("同步文件格式错误: {}", e))?;
+    if snapshot.format_version > SYNC_FORMAT_VERSION {
+        return Err(format!(
+            "同步文件版本 ({}) 高于当前支持的版本 ({})，请升级应用",
+            snapshot.format_version, SYNC_FORMAT_VERSION
+        ));
+    }
+    Ok(snapshot)
+}
+
+/// 按共享文件中的盐派生 Token 密钥；未设置口令时返回 `None`
+fn token_cipher(remote: &SyncSnapshot) -> Result<(Option<TokenCipher>, Option<String>), String> {
+    let passphrase = config::get_user_config().account_sync_passphrase;
+    if passphrase.is_empty() {
+        return Ok((None, remote.token_salt.clone()));
+    }
+    let salt = match remote.token_salt.as_deref().map(|salt| general_purpose::STANDARD.decode(salt)) {
+        Some(Ok(salt)) if !salt.is_empty() => salt,
+        _ => {
+            use rand::RngCore;
+            let mut salt = vec![0u8; backup::SALT_LEN];
+            rand::thread_rng().fill_bytes(&mut salt);
+            salt
+        }
+    };
+    let cipher = TokenCipher::new(&passphrase, &salt)?;
+    Ok((Some(cipher), Some(general_purpose::STANDARD.encode(salt))))
+}
+
+fn configured_folder() -> Result<PathBuf, String> {
+    let folder = config::get_user_config().account_sync_folder;
+    if folder.trim().is_empty() {
+        return Err("未设置同步文件夹".to_string());
+    }
+    let path = PathBuf::from(folder.trim());
+    if !path.is_dir() {
+        return Err(format!("同步文件夹不可用: {}", path.display()));
+    }
+    Ok(path)
+}
+
+/// 获取同步状态
+pub fn get_status() -> Result<AccountSyncStatus, String> {
+    let state = load_state()?;
+    Ok(AccountSyncStatus {
+        folder: config::get_user_config().account_sync_folder,
+        device_id: state.device_id,
+        last_synced_at: state.last_synced_at,
+        tokens_encrypted: !config::get_user_config().account_sync_passphrase.is_empty(),
+    })
+}
+
+/// 立即与同步文件夹同步
+pub fn sync_now() -> Result<AccountSyncReport, String> {
+    let _guard = SYNC_LOCK.lock().map_err(|e| format!("获取同步锁失败: {}", e))?;
+    let folder = configured_folder()?;
+    let sync_file = folder.join(SYNC_FILE);
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let mut state = load_state()?;
+    let mut edits = BTreeMap::new();
+    update_edit_journal(|journal| {
+        edits = journal.clone();
+        false
+    });
+    let local = build_local_records(collect_local_data()?, &state.records, &edits, &state.device_id, now);
+    let mut remote = read_snapshot(&sync_file)?;
+    let (cipher, token_salt) = token_cipher(&remote)?;
+    let sealed_remote = remote.records.clone();
+    open_tokens(&mut remote.records, cipher.as_ref());
+    let mut outcome = merge_records(&local, &remote.records, &state.records);
+
+    let mut apply = outcome.apply.clone();
+    apply.sort_by_key(|key| (apply_priority(key), key.clone()));
+
+    let mut pulled = Vec::new();
+    let mut failed = Vec::new();
+    let mut errors = Vec::new();
+    for key in apply {
+        match apply_record(&key, &outcome.merged[&key], &outcome.merged) {
+            Ok(()) => pulled.push(key),
+            Err(e) => {
+                logger::log_warn(&format!("[AccountSync] 应用记录失败 {}: {}", key, e));
+                errors.push(format!("{}: {}", key, e));
+                failed.push(key);
+            }
+        }
+    }
+
+    let generation = remote.generation + 1;
+    let mut peers = remote.peers.clone();
+    // 旧版本同步文件没有设备列表，写过记录的设备都视为已知设备
+    for record in remote.records.values() {
+        peers.entry(record.updated_by.clone()).or_insert(0);
+    }
+    peers.insert(state.device_id.clone(), generation);
+    let tombstones = prune_tombstones(&mut outcome.merged, &remote, &peers, generation);
+
+    let mut shared_records = outcome.merged.clone();
+    seal_tokens(&mut shared_records, &sealed_remote, cipher.as_ref())?;
+    write_json_atomic(
+        &sync_file,
+        &SyncSnapshot {
+            format_version: SYNC_FORMAT_VERSION,
+            updated_at: now,
+            updated_by: state.device_id.clone(),
+            generation,
+            peers,
+            tombstones,
+            token_salt,
+            records: shared_records,
+        },
+    )?;
+
+    let mut records: BTreeMap<String, SyncRecord> = outcome
+        .merged
+        .iter()
+        .map(|(key, record)| (key.clone(), record.metadata()))
+        .collect();
+    // 应用失败的记录保留上次同步的状态，下次同步时重试
+    for key in failed {
+        match state.records.get(&key) {
+            Some(prev) => records.insert(key, prev.clone()),
+            None => records.remove(&key),
+        };
+    }
+    state.records = records;
+    state.last_synced_at = Some(now);
+    write_json_atomic(&state_path()?, &state)?;
+
+    // 已同步的编辑记录不再需要
+    update_edit_journal(|journal| {
+        let before = journal.len();
+        journal.retain(|key, edit| match state.records.get(key) {
+            Some(record) => record.hash != edit.hash,
+            None => !edit.hash.is_empty(),
+        });
+        journal.len() != before
+    });
+
+    if !pulled.is_empty() {
+        crate::modules::websocket::broadcast_data_changed("account_sync");
+    }
+
+    logger::log_info(&format!(
+        "[AccountSync] 同步完成: 拉取 {} 条, 推送 {} 条, 冲突 {} 条, 失败 {} 条",
+        pulled.len(),
+        outcome.pushed.len(),
+        outcome.conflicts.len(),
+        errors.len()
+    ));
+
+    Ok(AccountSyncReport {
+        folder: folder.to_string_lossy().to_string(),
+        synced_at: now,
+        pulled,
+        pushed: outcome.pushed,
+        conflicts: outcome.conflicts,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(updated_at: i64, device: &str, value: Option<Value>) -> SyncRecord {
+        SyncRecord {
+            updated_at,
+            updated_by: device.to_string(),
+            deleted: value.is_none(),
+            hash: value.as_ref().map(content_hash).unwrap_or_default(),
+            data: value,
+        }
+    }
+
+    #[test]
+    fn local_changes_get_new_timestamps_and_tombstones() {
+        let base: BTreeMap<String, SyncRecord> = [
+            ("tags:a".to_string(), record(1, "other", Some(serde_json::json!(["x"]))).metadata()),
+            ("tags:b".to_string(), record(1, "other", Some(serde_json::json!(["y"]))).metadata()),
+        ]
+        .into();
+        let current: BTreeMap<String, Value> = [
+            ("tags:a".to_string(), serde_json::json!(["x"])),
+            ("tags:c".to_string(), serde_json::json!(["z"])),
+        ]
+        .into();
+
+        let local = build_local_records(current, &base, &BTreeMap::new(), "me", 50);
+        assert_eq!(local["tags:a"].updated_at, 1);
+        assert_eq!(local["tags:a"].updated_by, "other");
+        assert_eq!(local["tags:c"].updated_at, 50);
+        assert!(local["tags:b"].deleted);
+        assert_eq!(local["tags:b"].updated_at, 50);
+    }
+
+    #[test]
+    fn local_changes_use_recorded_edit_time() {
+        let base: BTreeMap<String, SyncRecord> = [
+            ("tags:a".to_string(), record(1, "other", Some(serde_json::json!(["x"]))).metadata()),
+            ("tags:b".to_string(), record(1, "other", Some(serde_json::json!(["y"]))).metadata()),
+        ]
+        .into();
+        let current: BTreeMap<String, Value> = [
+            ("tags:a".to_string(), serde_json::json!(["x", "new"])),
+            ("tags:c".to_string(), serde_json::json!(["z"])),
+        ]
+        .into();
+        let edits: BTreeMap<String, LocalEdit> = [
+            (
+                "tags:a".to_string(),
+                LocalEdit { hash: content_hash(&serde_json::json!(["x", "new"])), edited_at: 10 },
+            ),
+            ("tags:b".to_string(), LocalEdit { hash: String::new(), edited_at: 20 }),
+            // 内容已被再次修改（如在应用外），记录失效
+            ("tags:c".to_string(), LocalEdit { hash: "stale".to_string(), edited_at: 30 }),
+        ]
+        .into();
+
+        let local = build_local_records(current, &base, &edits, "me", 50);
+        assert_eq!(local["tags:a"].updated_at, 10);
+        assert_eq!(local["tags:b"].updated_at, 20);
+        assert!(local["tags:b"].deleted);
+        assert_eq!(local["tags:c"].updated_at, 50);
+
+        // 离线期间较早的修改不会覆盖其他设备较新的修改
+        let remote: BTreeMap<String, SyncRecord> =
+            [("tags:a".to_string(), record(40, "other", Some(serde_json::json!(["remote"]))))].into();
+        let outcome = merge_records(&local, &remote, &base);
+        assert_eq!(outcome.merged["tags:a"].updated_at, 40);
+    }
+
+    #[test]
+    fn merge_is_deterministic_and_reports_conflicts() {
+        let base: BTreeMap<String, SyncRecord> =
+            [("k".to_string(), record(1, "a", Some(serde_json::json!(1))).metadata())].into();
+        let local: BTreeMap<String, SyncRecord> =
+            [("k".to_string(), record(5, "a", Some(serde_json::json!(2))))].into();
+        let remote: BTreeMap<String, SyncRecord> = [
+            ("k".to_string(), record(5, "b", Some(serde_json::json!(3)))),
+            ("n".to_string(), record(2, "b", Some(serde_json::json!(4)))),
+        ]
+        .into();
+
+        let outcome = merge_records(&local, &remote, &base);
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].winner, SyncSide::Remote);
+        assert_eq!(outcome.apply, vec!["k".to_string(), "n".to_string()]);
+
+        // 交换双方后结果一致
+        let swapped = merge_records(&remote, &local, &base);
+        assert_eq!(swapped.merged["k"], outcome.merged["k"]);
+    }
+
+    #[test]
+    fn remote_tombstone_beats_stale_local_copy() {
+        let base: BTreeMap<String, SyncRecord> =
+            [("account:a".to_string(), record(1, "a", Some(serde_json::json!({"x": 1}))).metadata())].into();
+        let local: BTreeMap<String, SyncRecord> =
+            [("account:a".to_string(), record(1, "a", Some(serde_json::json!({"x": 1}))))].into();
+        let remote: BTreeMap<String, SyncRecord> = [("account:a".to_string(), record(9, "b", None))].into();
+
+        let outcome = merge_records(&local, &remote, &base);
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(outcome.apply, vec!["account:a".to_string()]);
+        assert!(outcome.merged["account:a"].deleted);
+    }
+
+    #[test]
+    fn tombstones_are_kept_until_every_peer_has_seen_them() {
+        let remote = SyncSnapshot {
+            generation: 4,
+            tombstones: [("tags:old".to_string(), 2)].into(),
+            records: [("tags:old".to_string(), record(1, "a", None))].into(),
+            ..Default::default()
+        };
+        let mut merged: BTreeMap<String, SyncRecord> = [
+            ("tags:old".to_string(), record(1, "a", None)),
+            ("tags:new".to_string(), record(3, "a", None)),
+            ("tags:live".to_string(), record(3, "a", Some(serde_json::json!(["x"])))),
+        ]
+        .into();
+
+        let peers: BTreeMap<String, u64> = [("a".to_string(), 5), ("b".to_string(), 2)].into();
+        let tombstones = prune_tombstones(&mut merged.clone(), &remote, &peers, 5);
+        assert_eq!(tombstones, [("tags:old".to_string(), 2), ("tags:new".to_string(), 5)].into());
+
+        // b 写入过更高代数后才清除旧墓碑，新墓碑仍需等待所有设备
+        let peers: BTreeMap<String, u64> = [("a".to_string(), 5), ("b".to_string(), 3)].into();
+        let tombstones = prune_tombstones(&mut merged, &remote, &peers, 5);
+        assert_eq!(tombstones, [("tags:new".to_string(), 5)].into());
+        assert!(!merged.contains_key("tags:old"));
+        assert!(merged.contains_key("tags:live"));
+    }
+
+    #[test]
+    fn refresh_tokens_are_sealed_and_keep_stable_hashes() {
+        let cipher = TokenCipher::new("secret", b"0123456789abcdef").unwrap();
+        let data = serde_json::json!({"email": "a@x.com", "name": null, "refresh_token": "rt"});
+        let mut records: BTreeMap<String, SyncRecord> =
+            [("account:a@x.com".to_string(), record(1, "a", Some(data.clone())))].into();
+
+        seal_tokens(&mut records, &BTreeMap::new(), Some(&cipher)).unwrap();
+        let sealed = records["account:a@x.com"].data.clone().unwrap();
+        assert!(sealed.get("refresh_token").is_none());
+        assert!(!sealed[SEALED_TOKEN_FIELD].as_str().unwrap().contains("rt"));
+
+        // 未设置口令的设备沿用远端密文
+        let remote = records.clone();
+        let mut plain: BTreeMap<String, SyncRecord> =
+            [("account:a@x.com".to_string(), record(1, "a", Some(data.clone())))].into();
+        seal_tokens(&mut plain, &remote, None).unwrap();
+        assert_eq!(plain, remote);
+
+        open_tokens(&mut records, Some(&cipher));
+        assert_eq!(records["account:a@x.com"].data.as_ref().unwrap()["refresh_token"], "rt");
+
+        let other = TokenCipher::new("other", b"0123456789abcdef").unwrap();
+        let mut foreign = remote.clone();
+        open_tokens(&mut foreign, Some(&other));
+        assert_eq!(foreign, remote);
+    }
+
+    #[test]
+    fn content_hash_ignores_key_order() {
+        let a: Value = serde_json::from_str(r#"{"a":1,"b":{"c":2,"d":3}}"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{"b":{"d":3,"c":2},"a":1}"#).unwrap();
+        assert_eq!(content_hash(&a), content_hash(&b));
+    }
+}
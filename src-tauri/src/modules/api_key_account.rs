@@ -0,0 +1,508 @@
+//! OpenAI 兼容接口的 API Key 账号
+//!
+//! - 账号保存在数据目录的 `api_key_accounts.json`
+//! - 用量查询：优先使用账号配置的 `usage_url`，否则尝试 `dashboard/billing` 接口
+//!   （OpenAI 旧版接口，多数兼容网关仍支持）
+//! - 切换：写入 Codex `auth.json` 的 `OPENAI_API_KEY` 与 OpenCode `auth.json` 的 openai 记录；
+//!   自定义接口地址写入 Codex `config.toml` 的模型提供方与 OpenCode `opencode.json` 的
+//!   `provider.openai.options.baseURL`，官方地址（及切换回 Codex OAuth 账号）时移除
+
+use std::fs;
+use std::path::PathBuf;
+
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::models::api_key::{ApiKeyAccount, ApiKeyStore, ApiKeyUsage};
+use crate::modules::atomic_write;
+use crate::modules::credential_sink::{self, SinkFormat, SinkResult};
+use crate::modules::{account, codex_account, logger, opencode_auth};
+
+const API_KEY_ACCOUNTS_FILE: &str = "api_key_accounts.json";
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+/// 覆盖 Codex auth.json 前的备份目录（位于数据目录）
+const CODEX_AUTH_BACKUP_DIR: &str = "codex_auth_backups";
+/// 保留的 auth.json 备份数量
+const MAX_CODEX_AUTH_BACKUPS: usize = 5;
+/// 写入 Codex config.toml 的模型提供方 ID
+const CODEX_PROVIDER_ID: &str = "cockpit_api_key";
+/// OpenCode 配置中 openai 接口地址的位置
+const OPENCODE_BASE_URL_KEY: &str = "provider.openai.options.baseURL";
+/// 用量查询的统计区间（dashboard/billing/usage 最多支持 100 天）
+const USAGE_LOOKBACK_DAYS: i64 = 99;
+
+static STORE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// 切换结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKeySwitchResult {
+    pub account: ApiKeyAccount,
+    pub targets: Vec<SinkResult>,
+}
+
+fn store_path() -> Result<PathBuf, String> {
+    Ok(account::get_data_dir()?.join(API_KEY_ACCOUNTS_FILE))
+}
+
+pub fn load_store() -> Result<ApiKeyStore, String> {
+    let path = store_path()?;
+    if !path.exists() {
+        return Ok(ApiKeyStore::default());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("读取 API Key 账号失败: {}", e))?;
+    if content.trim().is_empty() {
+        return Ok(ApiKeyStore::default());
+    }
+    serde_json::from_str(&content).map_err(|e| format!("解析 API Key 账号失败: {}", e))
+}
+
+fn save_store(store: &ApiKeyStore) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(store).map_err(|e| format!("序列化失败: {}", e))?;
+    atomic_write::write_file_atomic(&store_path()?, content.as_bytes())
+}
+
+/// 修改存储（持锁读-改-写）
+fn update_store<T>(f: impl FnOnce(&mut ApiKeyStore) -> Result<T, String>) -> Result<T, String> {
+    let _lock = STORE_LOCK.lock().map_err(|_| "无法获取 API Key 账号锁")?;
+    let mut store = load_store()?;
+    let result = f(&mut store)?;
+    save_store(&store)?;
+    Ok(result)
+}
+
+pub fn list_accounts() -> Vec<ApiKeyAccount> {
+    load_store().map(|store| store.accounts).unwrap_or_default()
+}
+
+pub fn load_account(account_id: &str) -> Option<ApiKeyAccount> {
+    list_accounts().into_iter().find(|acc| acc.id == account_id)
+}
+
+pub fn get_current_account() -> Option<ApiKeyAccount> {
+    let store = load_store().ok()?;
+    let id = store.current_account_id?;
+    store.accounts.into_iter().find(|acc| acc.id == id)
+}
+
+fn normalize_base_url(base_url: &str) -> String {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        DEFAULT_BASE_URL.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn normalize_optional(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+pub fn add_account(
+    label: String,
+    base_url: String,
+    api_key: String,
+    usage_url: Option<String>,
+) -> Result<ApiKeyAccount, String> {
+    let api_key = api_key.trim().to_string();
+    if api_key.is_empty() {
+        return Err("API Key 不能为空".to_string());
+    }
+    let base_url = normalize_base_url(&base_url);
+    let label = match label.trim() {
+        "" => base_url.clone(),
+        label => label.to_string(),
+    };
+
+    update_store(|store| {
+        if store
+            .accounts
+            .iter()
+            .any(|acc| acc.api_key == api_key && acc.base_url == base_url)
+        {
+            return Err("该 API Key 已存在".to_string());
+        }
+        let account = ApiKeyAccount::new(
+            format!("apikey_{}", uuid::Uuid::new_v4().simple()),
+            label,
+            base_url,
+            api_key,
+            normalize_optional(usage_url),
+        );
+        store.accounts.push(account.clone());
+        Ok(account)
+    })
+}
+
+pub fn update_account(
+    account_id: &str,
+    label: Option<String>,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    usage_url: Option<Option<String>>,
+) -> Result<ApiKeyAccount, String> {
+    update_store(|store| {
+        let acc = store
+            .accounts
+            .iter_mut()
+            .find(|acc| acc.id == account_id)
+            .ok_or_else(|| format!("账号不存在: {}", account_id))?;
+        if let Some(label) = label.filter(|l| !l.trim().is_empty()) {
+            acc.label = label.trim().to_string();
+        }
+        if let Some(base_url) = base_url {
+            acc.base_url = normalize_base_url(&base_url);
+        }
+        if let Some(api_key) = api_key.filter(|k| !k.trim().is_empty()) {
+            acc.api_key = api_key.trim().to_string();
+            acc.usage = None;
+        }
+        if let Some(usage_url) = usage_url {
+            acc.usage_url = normalize_optional(usage_url);
+        }
+        Ok(acc.clone())
+    })
+}
+
+pub fn update_account_tags(account_id: &str, tags: Vec<String>) -> Result<ApiKeyAccount, String> {
+    update_store(|store| {
+        let acc = store
+            .accounts
+            .iter_mut()
+            .find(|acc| acc.id == account_id)
+            .ok_or_else(|| format!("账号不存在: {}", account_id))?;
+        acc.tags = Some(account::normalize_tags(tags)?);
+        Ok(acc.clone())
+    })
+}
+
+pub fn remove_accounts(account_ids: &[String]) -> Result<(), String> {
+    update_store(|store| {
+        store.accounts.retain(|acc| !account_ids.contains(&acc.id));
+        if store
+            .current_account_id
+            .as_ref()
+            .is_some_and(|id| account_ids.contains(id))
+        {
+            store.current_account_id = None;
+        }
+        Ok(())
+    })
+}
+
+/// 去掉基础地址末尾的 `/v1`，得到 dashboard 接口的根地址
+fn dashboard_root(base_url: &str) -> String {
+    let base = normalize_base_url(base_url);
+    base.strip_suffix("/v1").unwrap_or(&base).to_string()
+}
+
+/// 解析常见的用量响应格式
+///
+/// - `credit_grants`：`total_granted` / `total_used` / `total_available`
+/// - `subscription`：`hard_limit_usd`
+/// - `usage`：`total_usage`（单位：美分）
+fn parse_usage_value(value: &Value, usage: &mut ApiKeyUsage) {
+    if let Some(granted) = value.get("total_granted").and_then(Value::as_f64) {
+        usage.total_granted = Some(granted);
+    }
+    if let Some(granted) = value.get("hard_limit_usd").and_then(Value::as_f64) {
+        usage.total_granted = Some(granted);
+    }
+    if let Some(used) = value.get("total_used").and_then(Value::as_f64) {
+        usage.total_used = Some(used);
+    }
+    if let Some(cents) = value.get("total_usage").and_then(Value::as_f64) {
+        usage.total_used = Some(cents / 100.0);
+    }
+    if let Some(available) = value.get("total_available").and_then(Value::as_f64) {
+        usage.remaining = Some(available);
+    }
+}
+
+fn finish_usage(mut usage: ApiKeyUsage) -> Result<ApiKeyUsage, String> {
+    if usage.remaining.is_none() {
+        if let (Some(granted), Some(used)) = (usage.total_granted, usage.total_used) {
+            usage.remaining = Some((granted - used).max(0.0));
+        }
+    }
+    if usage.total_granted.is_none() && usage.total_used.is_none() && usage.remaining.is_none() {
+        return Err("用量响应中没有可识别的额度字段".to_string());
+    }
+    Ok(usage)
+}
+
+async fn get_json(client: &reqwest::Client, url: &str, api_key: &str) -> Result<Value, String> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", api_key))
+            .map_err(|e| format!("构建 Authorization 头失败: {}", e))?,
+    );
+    headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+
+    let response = client
+        .get(url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    let status = response.status();
+    let body = response.text().await.map_err(|e| format!("读取响应失败: {}", e))?;
+    if !status.is_success() {
+        let body_preview: String = body.chars().take(200).collect();
+        return Err(format!("API 返回错误 {} - {}", status, body_preview));
+    }
+    serde_json::from_str(&body).map_err(|e| format!("解析 JSON 失败: {}", e))
+}
+
+/// 查询 API Key 的用量
+pub async fn fetch_usage(account: &ApiKeyAccount) -> Result<ApiKeyUsage, String> {
+    let client = reqwest::Client::new();
+    let mut usage = ApiKeyUsage {
+        total_granted: None,
+        total_used: None,
+        remaining: None,
+        fetched_at: chrono::Utc::now().timestamp(),
+    };
+
+    if let Some(usage_url) = &account.usage_url {
+        let value = get_json(&client, usage_url, &account.api_key).await?;
+        parse_usage_value(&value, &mut usage);
+        return finish_usage(usage);
+    }
+
+    let root = dashboard_root(&account.base_url);
+    let subscription = get_json(
+        &client,
+        &format!("{}/v1/dashboard/billing/subscription", root),
+        &account.api_key,
+    )
+    .await
+    .map_err(|e| format!("该接口不支持用量查询: {}", e))?;
+    parse_usage_value(&subscription, &mut usage);
+
+    let today = chrono::Utc::now().date_naive();
+    let start = today - chrono::Duration::days(USAGE_LOOKBACK_DAYS);
+    let end = today + chrono::Duration::days(1);
+    let usage_url = format!(
+        "{}/v1/dashboard/billing/usage?start_date={}&end_date={}",
+        root,
+        start.format("%Y-%m-%d"),
+        end.format("%Y-%m-%d")
+    );
+    match get_json(&client, &usage_url, &account.api_key).await {
+        Ok(value) => parse_usage_value(&value, &mut usage),
+        Err(e) => logger::log_warn(&format!("[ApiKey] {} 查询已用额度失败: {}", account.label, e)),
+    }
+    finish_usage(usage)
+}
+
+/// 刷新用量并保存
+pub async fn refresh_usage(account_id: &str) -> Result<ApiKeyUsage, String> {
+    let acc = load_account(account_id).ok_or_else(|| format!("账号不存在: {}", account_id))?;
+    let usage = fetch_usage(&acc).await?;
+    update_store(|store| {
+        if let Some(acc) = store.accounts.iter_mut().find(|acc| acc.id == account_id) {
+            acc.usage = Some(usage.clone());
+        }
+        Ok(())
+    })?;
+    Ok(usage)
+}
+
+/// 写入 Codex auth.json（API Key 模式，不含 OAuth tokens）
+///
+/// 覆盖前先回收当前 OAuth 账号被轮换的 Token，并备份原文件。
+fn write_codex_auth(acc: &ApiKeyAccount) -> Result<PathBuf, String> {
+    let path = codex_account::get_auth_json_path();
+    codex_account::sync_tokens_from_dir(&codex_account::get_codex_home());
+    let backup_dir = account::get_data_dir()?.join(CODEX_AUTH_BACKUP_DIR);
+    if let Some(backup_path) = atomic_write::backup_file(&path, &backup_dir, MAX_CODEX_AUTH_BACKUPS)? {
+        logger::log_info(&format!("[ApiKey] 已备份 {}: {}", path.display(), backup_path.display()));
+    }
+    let content = serde_json::to_string_pretty(&json!({
+        "OPENAI_API_KEY": acc.api_key,
+        "tokens": null,
+        "last_refresh": null,
+    }))
+    .map_err(|e| format!("序列化失败: {}", e))?;
+    atomic_write::write_file_atomic(&path, content.as_bytes())?;
+    Ok(path)
+}
+
+/// 写入 Codex config.toml：自定义接口地址时添加模型提供方并设为当前提供方，否则移除
+///
+/// 提供方设置 `requires_openai_auth`，使 Codex 从 auth.json 读取 API Key。
+fn write_codex_provider(base_url: Option<(&str, &str)>) -> Result<PathBuf, String> {
+    let path = codex_account::get_codex_home().join("config.toml");
+    if base_url.is_none() && !path.exists() {
+        return Ok(path);
+    }
+    credential_sink::edit_document_file(&path, SinkFormat::Toml, |document| {
+        apply_codex_provider(document, base_url)
+    })?;
+    Ok(path)
+}
+
+/// 修改 config.toml 文档，返回是否有变化
+fn apply_codex_provider(document: &mut Value, base_url: Option<(&str, &str)>) -> Result<bool, String> {
+    let Some((label, base_url)) = base_url else {
+        let mut changed =
+            credential_sink::remove_from_document(document, &format!("model_providers.{}", CODEX_PROVIDER_ID));
+        if document.get("model_provider").and_then(Value::as_str) == Some(CODEX_PROVIDER_ID) {
+            changed |= credential_sink::remove_from_document(document, "model_provider");
+        }
+        return Ok(changed);
+    };
+    let provider = json!({
+        "name": label,
+        "base_url": base_url,
+        "requires_openai_auth": true,
+    });
+    if document.pointer(&format!("/model_providers/{}", CODEX_PROVIDER_ID)) == Some(&provider)
+        && document.get("model_provider").and_then(Value::as_str) == Some(CODEX_PROVIDER_ID)
+    {
+        return Ok(false);
+    }
+    credential_sink::merge_into_document(document, Some("model_provider"), json!(CODEX_PROVIDER_ID))?;
+    credential_sink::merge_into_document(
+        document,
+        Some(&format!("model_providers.{}", CODEX_PROVIDER_ID)),
+        provider,
+    )?;
+    Ok(true)
+}
+
+/// 写入 OpenCode opencode.json 的 openai 接口地址，`None` 时移除
+fn write_opencode_base_url(base_url: Option<&str>) -> Result<(), String> {
+    let path = opencode_auth::get_opencode_config_path()?;
+    if base_url.is_none() && !path.exists() {
+        return Ok(());
+    }
+    credential_sink::edit_document_file(&path, SinkFormat::Json, |document| match base_url {
+        Some(base_url) => {
+            let current = document.pointer("/provider/openai/options/baseURL").and_then(Value::as_str);
+            if current == Some(base_url) {
+                return Ok(false);
+            }
+            credential_sink::merge_into_document(document, Some(OPENCODE_BASE_URL_KEY), json!(base_url))?;
+            Ok(true)
+        }
+        None => Ok(credential_sink::remove_from_document(document, OPENCODE_BASE_URL_KEY)),
+    })
+}
+
+/// 移除写入 Codex 与 OpenCode 配置的自定义接口地址（切换到 Codex OAuth 账号时调用）
+pub fn clear_custom_base_urls() {
+    if let Err(e) = write_codex_provider(None) {
+        logger::log_warn(&format!("[ApiKey] 移除 Codex 自定义接口地址失败: {}", e));
+    }
+    if let Err(e) = write_opencode_base_url(None) {
+        logger::log_warn(&format!("[ApiKey] 移除 OpenCode 自定义接口地址失败: {}", e));
+    }
+}
+
+/// 切换到 API Key 账号：写入 Codex 与 OpenCode 的凭据文件及接口地址
+pub fn switch_account(account_id: &str) -> Result<ApiKeySwitchResult, String> {
+    let acc = load_account(account_id).ok_or_else(|| format!("账号不存在: {}", account_id))?;
+    let base_url = normalize_base_url(&acc.base_url);
+    let custom_base_url = (base_url != DEFAULT_BASE_URL).then_some(base_url.as_str());
+    let mut targets = Vec::new();
+
+    // 先写接口地址：失败时不写入 Key，避免第三方 Key 被发送到官方地址
+    write_codex_provider(custom_base_url.map(|url| (acc.label.as_str(), url)))
+        .map_err(|e| format!("写入 Codex config.toml 失败: {}", e))?;
+    let codex_path = write_codex_auth(&acc)?;
+    targets.push(SinkResult {
+        id: "codex".to_string(),
+        name: "Codex".to_string(),
+        path: codex_path.to_string_lossy().to_string(),
+        updated: true,
+        error: None,
+    });
+    if let Some(sink) = credential_sink::enabled_builtin("opencode") {
+        let result = write_opencode_base_url(custom_base_url)
+            .map_err(|e| format!("写入 opencode.json 失败: {}", e))
+            .and_then(|()| {
+                let value = json!({ "type": "api", "key": acc.api_key });
+                credential_sink::merge_json_file(std::path::Path::new(&sink.path), "openai", value)
+            });
+        match &result {
+            Ok(()) => credential_sink::run_restart_hook(&sink),
+            Err(e) => logger::log_warn(&format!("[ApiKey] OpenCode 凭据更新失败: {}", e)),
+        }
+        targets.push(SinkResult {
+            id: sink.id.clone(),
+            name: sink.name.clone(),
+            path: sink.path.clone(),
+            updated: result.is_ok(),
+            error: result.err(),
+        });
+    }
+
+    let account = update_store(|store| {
+        store.current_account_id = Some(account_id.to_string());
+        let acc = store
+            .accounts
+            .iter_mut()
+            .find(|acc| acc.id == account_id)
+            .ok_or_else(|| format!("账号不存在: {}", account_id))?;
+        acc.update_last_used();
+        Ok(acc.clone())
+    })?;
+    logger::log_info(&format!("[ApiKey] 已切换到 {} ({})", account.label, account.masked_key()));
+
+    Ok(ApiKeySwitchResult { account, targets })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_subscription_and_usage_responses() {
+        let mut usage = ApiKeyUsage {
+            total_granted: None,
+            total_used: None,
+            remaining: None,
+            fetched_at: 0,
+        };
+        parse_usage_value(&json!({ "hard_limit_usd": 120.0, "has_payment_method": true }), &mut usage);
+        parse_usage_value(&json!({ "object": "list", "total_usage": 4550.0 }), &mut usage);
+        let usage = finish_usage(usage).unwrap();
+        assert_eq!(usage.total_granted, Some(120.0));
+        assert_eq!(usage.total_used, Some(45.5));
+        assert_eq!(usage.remaining, Some(74.5));
+
+        assert_eq!(dashboard_root("https://relay.example.com/v1/"), "https://relay.example.com");
+        assert_eq!(dashboard_root(""), "https://api.openai.com");
+    }
+
+    #[test]
+    fn codex_provider_is_added_and_removed() {
+        let mut document = json!({
+            "model": "o3",
+            "model_providers": { "other": { "base_url": "https://other.example.com" } },
+        });
+        let relay = Some(("Relay", "https://relay.example.com/v1"));
+        assert!(apply_codex_provider(&mut document, relay).unwrap());
+        assert_eq!(document["model_provider"], CODEX_PROVIDER_ID);
+        assert_eq!(document["model_providers"][CODEX_PROVIDER_ID]["base_url"], "https://relay.example.com/v1");
+        assert_eq!(document["model_providers"][CODEX_PROVIDER_ID]["requires_openai_auth"], true);
+        assert!(!apply_codex_provider(&mut document, relay).unwrap());
+
+        assert!(apply_codex_provider(&mut document, None).unwrap());
+        assert_eq!(
+            document,
+            json!({
+                "model": "o3",
+                "model_providers": { "other": { "base_url": "https://other.example.com" } },
+            })
+        );
+
+        // 用户自行选择的其他提供方保持不变
+        let mut document = json!({ "model_provider": "other" });
+        assert!(!apply_codex_provider(&mut document, None).unwrap());
+        assert_eq!(document["model_provider"], "other");
+    }
+}
@@ -0,0 +1,193 @@
+//! 多文件原子写入
+//!
+//! 所有文件先写入同目录下的临时文件，再依次替换目标文件；任一文件替换失败时，
+//! 按原内容恢复已替换的文件并清理剩余临时文件。替换成功后仍可用返回的原内容回滚，
+//! 供调用方在后续步骤失败时撤销写入。覆盖用户文件前可用 [`backup_file`] 保留带时间戳的备份。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::modules::logger;
+
+/// 已写入临时文件、等待替换的文件
+#[derive(Debug)]
+pub(crate) struct StagedFile {
+    pub(crate) path: PathBuf,
+    temp_path: PathBuf,
+}
+
+impl StagedFile {
+    /// 放弃替换，删除临时文件
+    pub(crate) fn discard(&self) {
+        let _ = fs::remove_file(&self.temp_path);
+    }
+}
+
+/// 已替换的文件及其原内容（`None` 表示原先不存在）
+#[derive(Debug)]
+pub(crate) struct ReplacedFile {
+    pub(crate) path: PathBuf,
+    original: Option<Vec<u8>>,
+}
+
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("file");
+    path.with_file_name(format!(
+        ".{}.tmp.{}",
+        file_name,
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+    ))
+}
+
+fn replace_file(temp_path: &Path, path: &Path) -> Result<(), String> {
+    if let Err(err) = fs::rename(temp_path, path) {
+        // Windows 上目标文件存在时 rename 可能失败
+        if path.exists() {
+            fs::remove_file(path).map_err(|e| format!("删除旧文件失败: {}", e))?;
+            fs::rename(temp_path, path).map_err(|e| format!("替换文件失败: {}", e))?;
+        } else {
+            return Err(format!("替换文件失败: {}", err));
+        }
+    }
+    Ok(())
+}
+
+/// 将内容写入目标文件旁的临时文件（自动创建目录）
+pub(crate) fn stage(path: &Path, content: &[u8]) -> Result<StagedFile, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let temp_path = temp_path_for(path);
+    if let Err(e) = fs::write(&temp_path, content) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("写入临时文件失败: {}", e));
+    }
+    Ok(StagedFile {
+        path: path.to_path_buf(),
+        temp_path,
+    })
+}
+
+/// 依次替换暂存的文件，`before_replace` 在替换每个文件前调用（如备份原文件）
+///
+/// 任一文件失败时回滚已替换的文件并删除所有临时文件，返回失败文件的下标与错误。
+pub(crate) fn replace_all(
+    staged: &[StagedFile],
+    mut before_replace: impl FnMut(&Path) -> Result<(), String>,
+) -> Result<Vec<ReplacedFile>, (usize, String)> {
+    let mut replaced = Vec::new();
+    for (index, file) in staged.iter().enumerate() {
+        let result = before_replace(&file.path).and_then(|()| {
+            let original = fs::read(&file.path).ok();
+            replace_file(&file.temp_path, &file.path)?;
+            Ok(original)
+        });
+        match result {
+            Ok(original) => replaced.push(ReplacedFile {
+                path: file.path.clone(),
+                original,
+            }),
+            Err(e) => {
+                logger::log_error(&format!("[AtomicWrite] 替换 {} 失败，回滚: {}", file.path.display(), e));
+                rollback(replaced);
+                for file in &staged[index..] {
+                    file.discard();
+                }
+                return Err((index, e));
+            }
+        }
+    }
+    Ok(replaced)
+}
+
+/// 按原内容恢复已替换的文件（逆序）
+pub(crate) fn rollback(replaced: Vec<ReplacedFile>) {
+    for file in replaced.into_iter().rev() {
+        let restored = match file.original {
+            Some(bytes) => fs::write(&file.path, bytes),
+            None => fs::remove_file(&file.path),
+        };
+        if let Err(e) = restored {
+            logger::log_error(&format!("[AtomicWrite] 回滚 {} 失败: {}", file.path.display(), e));
+        }
+    }
+}
+
+/// 将原文件备份到 `backup_dir`，每个文件名只保留最近 `keep` 份；原文件不存在时返回 `None`
+pub(crate) fn backup_file(path: &Path, backup_dir: &Path, keep: usize) -> Result<Option<PathBuf>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::create_dir_all(backup_dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or("file");
+    let backup_path = backup_dir.join(format!(
+        "{}.{}.bak",
+        file_name,
+        chrono::Local::now().format("%Y%m%d-%H%M%S%3f")
+    ));
+    fs::copy(path, &backup_path).map_err(|e| format!("备份 {} 失败: {}", path.display(), e))?;
+
+    let prefix = format!("{}.", file_name);
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".bak"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    backups.sort();
+    while backups.len() > keep {
+        let oldest = backups.remove(0);
+        let _ = fs::remove_file(oldest);
+    }
+    Ok(Some(backup_path))
+}
+
+/// 原子写入单个文件（先写临时文件再替换）
+pub(crate) fn write_file_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    let staged = stage(path, content)?;
+    replace_all(std::slice::from_ref(&staged), |_| Ok(()))
+        .map(|_| ())
+        .map_err(|(_, e)| e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_all_rolls_back_on_failure_and_on_request() {
+        let dir = std::env::temp_dir().join(format!("atomic_write_{}", uuid::Uuid::new_v4()));
+        let existing = dir.join("existing.json");
+        let created = dir.join("created.json");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&existing, "old").unwrap();
+
+        let staged = vec![stage(&existing, b"new").unwrap(), stage(&created, b"new").unwrap()];
+        let result = replace_all(&staged, |path| {
+            if path == created {
+                Err("备份失败".to_string())
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result.unwrap_err().0, 1);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "old");
+        assert!(!created.exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let staged = vec![stage(&existing, b"new").unwrap(), stage(&created, b"new").unwrap()];
+        let replaced = replace_all(&staged, |_| Ok(())).unwrap();
+        assert_eq!(fs::read_to_string(&created).unwrap(), "new");
+        rollback(replaced);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "old");
+        assert!(!created.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,690 @@
+//! 数据目录完整备份与恢复
+//!
+//! 备份文件格式：`MAGIC | flags | [salt | nonce] | body`，body 为 gzip 压缩的 JSON 归档，
+//! 设置密码时使用 PBKDF2-HMAC-SHA256 派生密钥并以 AES-256-GCM 加密。
+//! 归档包含 `~/.antigravity_cockpit` 下除日志、运行时状态、多开实例目录和临时文件以外的全部文件，
+//! SQLite 数据库以一致性快照形式保存。无法读取的文件（如被运行中的程序锁定）会被跳过并在结果中列出。
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::modules::{self, logger, wakeup_history};
+
+/// 归档格式版本（不兼容变更时递增）
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MAGIC: &[u8; 8] = b"CKPTBAK\0";
+const FLAG_ENCRYPTED: u8 = 0x01;
+pub(crate) const SALT_LEN: usize = 16;
+pub(crate) const NONCE_LEN: usize = 12;
+const PBKDF2_ITERATIONS: u32 = 210_000;
+
+/// 不参与备份的顶层目录/文件
+///
+/// `instances` 下是多开实例的完整编辑器用户目录（缓存、workspaceStorage 等，可达数 GB），
+/// 实例配置本身保存在顶层的 `*instances.json` 中。
+const EXCLUDED_TOP_LEVEL: [&str; 4] = ["logs", "backups", "instances", "server.json"];
+/// 不参与备份的文件后缀
+const EXCLUDED_SUFFIXES: [&str; 5] = [".tmp", ".lock", "-wal", "-shm", "-journal"];
+/// 恢复前自动保存的安全备份目录
+const SAFETY_BACKUP_DIR: &str = "backups";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupArchive {
+    format_version: u32,
+    app_version: String,
+    created_at: i64,
+    files: Vec<BackupEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupEntry {
+    /// 相对数据目录的路径（使用 `/` 分隔）
+    path: String,
+    modified_at: i64,
+    sha256: String,
+    /// base64 编码的文件内容
+    content: String,
+}
+
+impl BackupEntry {
+    fn bytes(&self) -> Result<Vec<u8>, String> {
+        general_purpose::STANDARD
+            .decode(&self.content)
+            .map_err(|e| format!("备份文件内容损坏 ({}): {}", self.path, e))
+    }
+}
+
+/// 创建备份的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupSummary {
+    pub path: String,
+    pub file_count: usize,
+    pub total_size: u64,
+    pub encrypted: bool,
+    pub created_at: i64,
+    /// 因无法读取而跳过的文件
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupFileStatus {
+    /// 本地不存在
+    New,
+    /// 与本地内容一致
+    Identical,
+    /// 与本地内容不同
+    Conflict,
+}
+
+/// 恢复预览中的单个文件
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupFilePreview {
+    pub path: String,
+    pub status: BackupFileStatus,
+    pub backup_size: u64,
+    pub backup_modified_at: i64,
+    pub local_size: Option<u64>,
+    pub local_modified_at: Option<i64>,
+}
+
+/// 恢复预览
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupRestorePreview {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: i64,
+    pub encrypted: bool,
+    pub files: Vec<BackupFilePreview>,
+    pub new_count: usize,
+    pub conflict_count: usize,
+    pub identical_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RestoreMode {
+    /// 合并：新增缺失文件，账号/实例等索引按 ID 合并，其余冲突文件保留较新的版本
+    Merge,
+    /// 替换：先保存当前数据的安全备份，再用备份内容整体替换
+    Replace,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupRestoreResult {
+    pub mode: RestoreMode,
+    /// 直接写入的文件
+    pub written: Vec<String>,
+    /// 与本地内容合并的文件
+    pub merged: Vec<String>,
+    /// 保留本地版本的冲突文件
+    pub kept_local: Vec<String>,
+    /// 替换模式下删除的本地文件
+    pub removed: Vec<String>,
+    /// 替换前自动创建的安全备份
+    pub safety_backup: Option<String>,
+}
+
+fn data_dir() -> Result<PathBuf, String> {
+    modules::account::get_data_dir()
+}
+
+fn is_excluded(relative: &str) -> bool {
+    let top = relative.split('/').next().unwrap_or_default();
+    EXCLUDED_TOP_LEVEL.contains(&top)
+        || EXCLUDED_SUFFIXES.iter().any(|suffix| relative.ends_with(suffix))
+}
+
+/// 校验归档中的相对路径，防止写出数据目录
+fn safe_relative_path(relative: &str) -> Result<PathBuf, String> {
+    let mut path = PathBuf::new();
+    for part in relative.split('/') {
+        if part.is_empty() || part == "." || part == ".." || part.contains('\\') || part.contains(':') {
+            return Err(format!("备份包含非法路径: {}", relative));
+        }
+        path.push(part);
+    }
+    if path.as_os_str().is_empty() {
+        return Err("备份包含空路径".to_string());
+    }
+    Ok(path)
+}
+
+fn modified_at(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// 列出数据目录中需要备份的文件（相对路径，`/` 分隔）
+fn collect_files(root: &Path) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = fs::read_dir(&dir).map_err(|e| format!("读取目录失败 ({}): {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            if is_excluded(&relative) {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(path);
+            } else if file_type.is_file() {
+                files.push(relative);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 读取待备份文件内容（数据库使用快照，避免读到写入中的页）
+fn read_for_backup(root: &Path, relative: &str) -> Result<Vec<u8>, String> {
+    let path = root.join(safe_relative_path(relative)?);
+    if relative == wakeup_history::HISTORY_DB_FILE {
+        let snapshot = std::env::temp_dir().join(format!("cockpit-history-{}.db", uuid::Uuid::new_v4()));
+        wakeup_history::export_snapshot(&snapshot)?;
+        let bytes = fs::read(&snapshot).map_err(|e| format!("读取唤醒历史快照失败: {}", e));
+        let _ = fs::remove_file(&snapshot);
+        return bytes;
+    }
+    fs::read(&path).map_err(|e| format!("读取文件失败 ({}): {}", relative, e))
+}
+
+/// 构建归档，返回 (归档, 跳过的文件)
+fn build_archive(root: &Path) -> Result<(BackupArchive, Vec<String>), String> {
+    Ok(archive_files(root, collect_files(root)?))
+}
+
+fn archive_files(root: &Path, relatives: Vec<String>) -> (BackupArchive, Vec<String>) {
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    for relative in relatives {
+        let bytes = match read_for_backup(root, &relative) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                logger::log_warn(&format!("[Backup] 跳过无法读取的文件: {}", e));
+                skipped.push(relative);
+                continue;
+            }
+        };
+        files.push(BackupEntry {
+            modified_at: modified_at(&root.join(&relative)),
+            sha256: sha256_hex(&bytes),
+            content: general_purpose::STANDARD.encode(&bytes),
+            path: relative,
+        });
+    }
+    let archive = BackupArchive {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+        files,
+    };
+    (archive, skipped)
+}
+
+/// 由口令派生 AES-256-GCM 密钥（PBKDF2-HMAC-SHA256）
+pub(crate) fn derive_key(password: &str, salt: &[u8]) -> Result<ring::aead::LessSafeKey, String> {
+    let mut key = [0u8; 32];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(PBKDF2_ITERATIONS).expect("non-zero iterations"),
+        salt,
+        password.as_bytes(),
+        &mut key,
+    );
+    let unbound = ring::aead::UnboundKey::new(&ring::aead::AES_256_GCM, &key)
+        .map_err(|_| "初始化加密密钥失败".to_string())?;
+    Ok(ring::aead::LessSafeKey::new(unbound))
+}
+
+fn encode_archive(archive: &BackupArchive, password: Option<&str>) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(archive).map_err(|e| format!("序列化备份失败: {}", e))?;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&json).map_err(|e| format!("压缩备份失败: {}", e))?;
+    let mut body = encoder.finish().map_err(|e| format!("压缩备份失败: {}", e))?;
+
+    let password = password.filter(|p| !p.is_empty());
+    let flags = if password.is_some() { FLAG_ENCRYPTED } else { 0 };
+    let mut output = Vec::with_capacity(body.len() + 64);
+    output.extend_from_slice(MAGIC);
+    output.push(flags);
+
+    if let Some(password) = password {
+        use rand::RngCore;
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let key = derive_key(password, &salt)?;
+        key.seal_in_place_append_tag(
+            ring::aead::Nonce::assume_unique_for_key(nonce),
+            ring::aead::Aad::from(&output[..]),
+            &mut body,
+        )
+        .map_err(|_| "加密备份失败".to_string())?;
+        output.extend_from_slice(&salt);
+        output.extend_from_slice(&nonce);
+    }
+
+    output.extend_from_slice(&body);
+    Ok(output)
+}
+
+/// 解析备份文件，返回 (归档, 是否加密)
+fn decode_archive(data: &[u8], password: Option<&str>) -> Result<(BackupArchive, bool), String> {
+    let header_len = MAGIC.len() + 1;
+    if data.len() < header_len || &data[..MAGIC.len()] != MAGIC {
+        return Err("不是有效的备份文件".to_string());
+    }
+    let flags = data[MAGIC.len()];
+    let encrypted = flags & FLAG_ENCRYPTED != 0;
+
+    let body = if encrypted {
+        let password = password
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| "备份已加密，请输入密码".to_string())?;
+        if data.len() < header_len + SALT_LEN + NONCE_LEN {
+            return Err("备份文件已损坏".to_string());
+        }
+        let salt = &data[header_len..header_len + SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&data[header_len + SALT_LEN..header_len + SALT_LEN + NONCE_LEN]);
+        let mut ciphertext = data[header_len + SALT_LEN + NONCE_LEN..].to_vec();
+
+        let key = derive_key(password, salt)?;
+        let plaintext = key
+            .open_in_place(
+                ring::aead::Nonce::assume_unique_for_key(nonce),
+                ring::aead::Aad::from(&data[..header_len]),
+                &mut ciphertext,
+            )
+            .map_err(|_| "密码错误或备份文件已损坏".to_string())?;
+        plaintext.to_vec()
+    } else {
+        data[header_len..].to_vec()
+    };
+
+    let mut json = Vec::new();
+    flate2::read::GzDecoder::new(&body[..])
+        .read_to_end(&mut json)
+        .map_err(|e| format!("解压备份失败: {}", e))?;
+    let archive: BackupArchive =
+        serde_json::from_slice(&json).map_err(|e| format!("解析备份失败: {}", e))?;
+
+    if archive.format_version > BACKUP_FORMAT_VERSION {
+        return Err(format!(
+            "备份格式版本 {} 高于当前支持的版本 {}，请升级应用后再恢复",
+            archive.format_version, BACKUP_FORMAT_VERSION
+        ));
+    }
+    for entry in &archive.files {
+        safe_relative_path(&entry.path)?;
+        if sha256_hex(&entry.bytes()?) != entry.sha256 {
+            return Err(format!("备份文件校验失败: {}", entry.path));
+        }
+    }
+
+    Ok((archive, encrypted))
+}
+
+fn write_file_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let temp_path = path.with_extension("restore.tmp");
+    fs::write(&temp_path, bytes).map_err(|e| format!("写入文件失败 ({}): {}", path.display(), e))?;
+    fs::rename(&temp_path, path).map_err(|e| format!("替换文件失败 ({}): {}", path.display(), e))
+}
+
+/// 合并两个 JSON 文档：对象逐键合并，元素带 `id` 的数组按 ID 取并集（本地优先），其余保留本地值
+fn merge_json(local: &serde_json::Value, incoming: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    fn item_id(value: &Value) -> Option<&str> {
+        value.get("id").and_then(|id| id.as_str())
+    }
+
+    match (local, incoming) {
+        (Value::Object(local_map), Value::Object(incoming_map)) => {
+            let mut merged = local_map.clone();
+            for (key, incoming_value) in incoming_map {
+                let value = match local_map.get(key) {
+                    Some(local_value) => merge_json(local_value, incoming_value),
+                    None => incoming_value.clone(),
+                };
+                merged.insert(key.clone(), value);
+            }
+            Value::Object(merged)
+        }
+        (Value::Array(local_items), Value::Array(incoming_items))
+            if local_items.iter().chain(incoming_items).all(|item| item_id(item).is_some()) =>
+        {
+            let known: HashSet<&str> = local_items.iter().filter_map(item_id).collect();
+            let mut merged = local_items.clone();
+            merged.extend(
+                incoming_items
+                    .iter()
+                    .filter(|item| item_id(item).map(|id| !known.contains(id)).unwrap_or(false))
+                    .cloned(),
+            );
+            Value::Array(merged)
+        }
+        _ => local.clone(),
+    }
+}
+
+/// 创建备份
+pub fn create_backup(target: &Path, password: Option<&str>) -> Result<BackupSummary, String> {
+    let root = data_dir()?;
+    let (archive, skipped) = build_archive(&root)?;
+    let encoded = encode_archive(&archive, password)?;
+    write_file_atomic(target, &encoded)?;
+
+    let total_size = archive
+        .files
+        .iter()
+        .map(|entry| entry.bytes().map(|b| b.len() as u64).unwrap_or(0))
+        .sum();
+    logger::log_info(&format!(
+        "[Backup] 备份已创建: {} ({} 个文件，跳过 {} 个)",
+        target.display(),
+        archive.files.len(),
+        skipped.len()
+    ));
+
+    Ok(BackupSummary {
+        path: target.to_string_lossy().into_owned(),
+        file_count: archive.files.len(),
+        total_size,
+        encrypted: password.is_some_and(|p| !p.is_empty()),
+        created_at: archive.created_at,
+        skipped,
+    })
+}
+
+/// 预览恢复：校验备份并列出与本地数据的差异
+pub fn preview_restore(source: &Path, password: Option<&str>) -> Result<BackupRestorePreview, String> {
+    let data = fs::read(source).map_err(|e| format!("读取备份文件失败: {}", e))?;
+    let (archive, encrypted) = decode_archive(&data, password)?;
+    let root = data_dir()?;
+
+    let mut files = Vec::with_capacity(archive.files.len());
+    for entry in &archive.files {
+        let bytes = entry.bytes()?;
+        let local_path = root.join(safe_relative_path(&entry.path)?);
+        let (status, local_size, local_modified_at) = if local_path.is_file() {
+            // 本地文件无法读取时按冲突处理
+            let local_bytes = read_for_backup(&root, &entry.path).ok();
+            let status = match &local_bytes {
+                Some(local_bytes) if sha256_hex(local_bytes) == entry.sha256 => BackupFileStatus::Identical,
+                _ => BackupFileStatus::Conflict,
+            };
+            let local_size = local_bytes
+                .map(|bytes| bytes.len() as u64)
+                .or_else(|| fs::metadata(&local_path).ok().map(|meta| meta.len()));
+            (status, local_size, Some(modified_at(&local_path)))
+        } else {
+            (BackupFileStatus::New, None, None)
+        };
+        files.push(BackupFilePreview {
+            path: entry.path.clone(),
+            status,
+            backup_size: bytes.len() as u64,
+            backup_modified_at: entry.modified_at,
+            local_size,
+            local_modified_at,
+        });
+    }
+
+    let count = |status: BackupFileStatus| files.iter().filter(|f| f.status == status).count();
+    Ok(BackupRestorePreview {
+        format_version: archive.format_version,
+        app_version: archive.app_version,
+        created_at: archive.created_at,
+        encrypted,
+        new_count: count(BackupFileStatus::New),
+        conflict_count: count(BackupFileStatus::Conflict),
+        identical_count: count(BackupFileStatus::Identical),
+        files,
+    })
+}
+
+/// 恢复备份
+pub fn restore_backup(
+    source: &Path,
+    password: Option<&str>,
+    mode: RestoreMode,
+) -> Result<BackupRestoreResult, String> {
+    let data = fs::read(source).map_err(|e| format!("读取备份文件失败: {}", e))?;
+    let (archive, _) = decode_archive(&data, password)?;
+    let root = data_dir()?;
+
+    let mut result = BackupRestoreResult {
+        mode,
+        written: Vec::new(),
+        merged: Vec::new(),
+        kept_local: Vec::new(),
+        removed: Vec::new(),
+        safety_backup: None,
+    };
+
+    if mode == RestoreMode::Replace {
+        let safety_dir = root.join(SAFETY_BACKUP_DIR);
+        let safety_path = safety_dir.join(format!(
+            "pre_restore_{}.ckptbak",
+            chrono::Local::now().format("%Y%m%d_%H%M%S")
+        ));
+        create_backup(&safety_path, None)?;
+        result.safety_backup = Some(safety_path.to_string_lossy().into_owned());
+
+        let incoming: HashSet<&str> = archive.files.iter().map(|f| f.path.as_str()).collect();
+        for relative in collect_files(&root)? {
+            if incoming.contains(relative.as_str()) || relative == wakeup_history::HISTORY_DB_FILE {
+                continue;
+            }
+            let path = root.join(safe_relative_path(&relative)?);
+            fs::remove_file(&path).map_err(|e| format!("删除文件失败 ({}): {}", relative, e))?;
+            result.removed.push(relative);
+        }
+    }
+
+    let entries: BTreeMap<&str, &BackupEntry> =
+        archive.files.iter().map(|f| (f.path.as_str(), f)).collect();
+    for (relative, entry) in entries {
+        let bytes = entry.bytes()?;
+        let local_path = root.join(safe_relative_path(relative)?);
+        let exists = local_path.is_file();
+
+        if relative == wakeup_history::HISTORY_DB_FILE {
+            let temp = std::env::temp_dir().join(format!("cockpit-restore-{}.db", uuid::Uuid::new_v4()));
+            fs::write(&temp, &bytes).map_err(|e| format!("写入临时文件失败: {}", e))?;
+            let outcome = if mode == RestoreMode::Merge && exists {
+                wakeup_history::merge_from(&temp).map(|_| result.merged.push(relative.to_string()))
+            } else {
+                wakeup_history::replace_with(&temp).map(|_| result.written.push(relative.to_string()))
+            };
+            let _ = fs::remove_file(&temp);
+            outcome?;
+            continue;
+        }
+
+        if mode == RestoreMode::Replace || !exists {
+            write_file_atomic(&local_path, &bytes)?;
+            result.written.push(relative.to_string());
+            continue;
+        }
+
+        let local_bytes = fs::read(&local_path).map_err(|e| format!("读取文件失败 ({}): {}", relative, e))?;
+        if sha256_hex(&local_bytes) == entry.sha256 {
+            continue;
+        }
+
+        // 顶层 JSON（账号索引、实例、指纹等）可按 ID 合并
+        let mergeable = relative.ends_with(".json") && !relative.contains('/');
+        let local_json = serde_json::from_slice::<serde_json::Value>(&local_bytes).ok();
+        let incoming_json = serde_json::from_slice::<serde_json::Value>(&bytes).ok();
+        match (local_json, incoming_json) {
+            (Some(local), Some(incoming)) if mergeable && local.is_object() && incoming.is_object() => {
+                let merged = merge_json(&local, &incoming);
+                let json = serde_json::to_vec_pretty(&merged).map_err(|e| format!("序列化失败: {}", e))?;
+                write_file_atomic(&local_path, &json)?;
+                result.merged.push(relative.to_string());
+            }
+            _ if entry.modified_at > modified_at(&local_path) => {
+                write_file_atomic(&local_path, &bytes)?;
+                result.written.push(relative.to_string());
+            }
+            _ => result.kept_local.push(relative.to_string()),
+        }
+    }
+
+    reload_after_restore();
+    logger::log_info(&format!(
+        "[Backup] 恢复完成 ({:?}): 写入 {}, 合并 {}, 保留本地 {}, 删除 {}",
+        mode,
+        result.written.len(),
+        result.merged.len(),
+        result.kept_local.len(),
+        result.removed.len()
+    ));
+    Ok(result)
+}
+
+/// 恢复后刷新内存中的配置与调度状态，并通知前端/扩展
+fn reload_after_restore() {
+    match modules::config::load_user_config() {
+        Ok(config) => {
+            if let Err(e) = modules::config::save_user_config(&config) {
+                logger::log_warn(&format!("[Backup] 重新加载配置失败: {}", e));
+            }
+        }
+        Err(e) => logger::log_warn(&format!("[Backup] 读取恢复后的配置失败: {}", e)),
+    }
+    modules::wakeup_scheduler::load_persisted_state();
+    modules::websocket::broadcast_data_changed("backup_restore");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_archive() -> BackupArchive {
+        let content = br#"{"accounts":[]}"#;
+        BackupArchive {
+            format_version: BACKUP_FORMAT_VERSION,
+            app_version: "0.0.0".to_string(),
+            created_at: 1,
+            files: vec![BackupEntry {
+                path: "accounts.json".to_string(),
+                modified_at: 1,
+                sha256: sha256_hex(content),
+                content: general_purpose::STANDARD.encode(content),
+            }],
+        }
+    }
+
+    #[test]
+    fn roundtrip_plain_and_encrypted() {
+        let plain = encode_archive(&sample_archive(), None).unwrap();
+        let (archive, encrypted) = decode_archive(&plain, None).unwrap();
+        assert!(!encrypted);
+        assert_eq!(archive.files.len(), 1);
+
+        let sealed = encode_archive(&sample_archive(), Some("secret")).unwrap();
+        assert!(decode_archive(&sealed, None).is_err());
+        assert!(decode_archive(&sealed, Some("wrong")).is_err());
+        let (archive, encrypted) = decode_archive(&sealed, Some("secret")).unwrap();
+        assert!(encrypted);
+        assert_eq!(archive.files[0].path, "accounts.json");
+    }
+
+    #[test]
+    fn rejects_newer_format_and_unsafe_paths() {
+        let mut archive = sample_archive();
+        archive.format_version = BACKUP_FORMAT_VERSION + 1;
+        let encoded = encode_archive(&archive, None).unwrap();
+        assert!(decode_archive(&encoded, None).unwrap_err().contains("版本"));
+
+        assert!(safe_relative_path("../etc/passwd").is_err());
+        assert!(safe_relative_path("accounts/../../x").is_err());
+        assert!(safe_relative_path("C:/x").is_err());
+        assert!(safe_relative_path("accounts/a.json").is_ok());
+    }
+
+    #[test]
+    fn merge_json_unions_by_id() {
+        let local = serde_json::json!({
+            "current_account_id": "a",
+            "accounts": [{"id": "a", "email": "local"}],
+        });
+        let incoming = serde_json::json!({
+            "current_account_id": "b",
+            "accounts": [{"id": "a", "email": "backup"}, {"id": "b"}],
+            "version": "1.0",
+        });
+        let merged = merge_json(&local, &incoming);
+        assert_eq!(merged["current_account_id"], "a");
+        assert_eq!(merged["accounts"].as_array().unwrap().len(), 2);
+        assert_eq!(merged["accounts"][0]["email"], "local");
+        assert_eq!(merged["version"], "1.0");
+    }
+
+    #[test]
+    fn excludes_runtime_files() {
+        assert!(is_excluded("server.json"));
+        assert!(is_excluded("logs/app.log"));
+        assert!(is_excluded("wakeup_history.db-wal"));
+        assert!(is_excluded("instances/copilot/abc/User/workspaceStorage/state.vscdb"));
+        assert!(!is_excluded("accounts/a.json"));
+        assert!(!is_excluded("copilot_instances.json"));
+        assert!(!is_excluded("wakeup_history.db"));
+    }
+
+    #[test]
+    fn unreadable_files_are_skipped() {
+        let root = std::env::temp_dir().join(format!("backup_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("instances/copilot/p1")).unwrap();
+        fs::write(root.join("accounts.json"), "{}").unwrap();
+        fs::write(root.join("instances/copilot/p1/Cookies"), "x").unwrap();
+        let mut files = collect_files(&root).unwrap();
+        assert_eq!(files, ["accounts.json"]);
+
+        // 列出后被删除（或被锁定）的文件不会中断备份
+        files.push("gone.json".to_string());
+        let (archive, skipped) = archive_files(&root, files);
+        assert_eq!(archive.files.len(), 1);
+        assert_eq!(skipped, ["gone.json"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -178,6 +178,11 @@ pub fn upsert_account(tokens: CodexTokens) -> Result<CodexAccount, String> {
         acc.user_id = user_id;
         acc.plan_type = plan_type.clone();
         acc.account_id = account_id;
+        if acc.disabled {
+            acc.disabled = false;
+            acc.disabled_reason = None;
+            acc.disabled_at = None;
+        }
         acc.update_last_used();
         acc
     } else {
@@ -286,6 +291,41 @@ pub fn write_auth_file_to_dir(base_dir: &Path, account: &CodexAccount) -> Result
     Ok(())
 }
 
+/// 将目录下 auth.json 中的 Token 写回对应账号，返回匹配到的账号 ID
+///
+/// Codex 运行期间会自行轮换 refresh_token，覆盖 auth.json 或刷新前需先回收，否则存储中的旧令牌会失效。
+pub fn sync_tokens_from_dir(base_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(base_dir.join("auth.json")).ok()?;
+    let auth_file: CodexAuthFile = serde_json::from_str(&content).ok()?;
+    let (email, _, _, _) = extract_user_info(&auth_file.tokens.id_token).ok()?;
+    let summary = load_account_index().accounts.into_iter().find(|a| a.email == email)?;
+    let mut account = load_account(&summary.id)?;
+
+    let tokens = auth_file.tokens;
+    let unchanged = account.tokens.access_token == tokens.access_token
+        && account.tokens.refresh_token == tokens.refresh_token;
+    let expiry = |token: &str| decode_jwt_payload(token).ok().and_then(|payload| payload.exp);
+    // 仅在 auth.json 中的 Token 不比存储中的旧时回收
+    if unchanged || expiry(&tokens.access_token) < expiry(&account.tokens.access_token) {
+        return Some(account.id);
+    }
+
+    account.tokens = CodexTokens {
+        id_token: tokens.id_token,
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token.or(account.tokens.refresh_token),
+    };
+    match save_account(&account) {
+        Ok(()) => logger::log_info(&format!(
+            "已从 {} 回收 Codex 账号 {} 的最新 Token",
+            base_dir.display(),
+            account.email
+        )),
+        Err(e) => logger::log_warn(&format!("回收 Codex 账号 {} 的 Token 失败: {}", account.email, e)),
+    }
+    Some(account.id)
+}
+
 /// 准备账号注入：如有必要刷新 Token 并写回存储
 pub async fn prepare_account_for_injection(account_id: &str) -> Result<CodexAccount, String> {
     let mut account = load_account(account_id).ok_or_else(|| format!("账号不存在: {}", account_id))?;
@@ -312,6 +352,8 @@ pub async fn prepare_account_for_injection(account_id: &str) -> Result<CodexAcco
 
 /// 切换账号（写入 auth.json）
 pub fn switch_account(account_id: &str) -> Result<CodexAccount, String> {
+    // 覆盖前先回收当前账号被 Codex 轮换过的 Token
+    sync_tokens_from_dir(&get_codex_home());
     let account = load_account(account_id).ok_or_else(|| format!("账号不存在: {}", account_id))?;
     write_auth_file_to_dir(&get_codex_home(), &account)?;
 
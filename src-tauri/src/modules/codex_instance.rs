@@ -315,6 +315,8 @@ pub fn clear_all_pids() -> Result<(), String> {
 }
 
 pub async fn inject_account_to_profile(profile_dir: &Path, account_id: &str) -> Result<(), String> {
+    // 覆盖前先回收该目录中被 Codex 轮换过的 Token
+    modules::codex_account::sync_tokens_from_dir(profile_dir);
     let account = modules::codex_account::prepare_account_for_injection(account_id).await?;
     modules::codex_account::write_auth_file_to_dir(profile_dir, &account)
 }
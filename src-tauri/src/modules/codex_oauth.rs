@@ -1,5 +1,6 @@
 use crate::models::codex::CodexTokens;
 use crate::modules::logger;
+use crate::utils::http::HttpError;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use rand::Rng;
 use sha2::{Sha256, Digest};
@@ -361,6 +362,11 @@ pub fn is_token_expired(access_token: &str) -> bool {
 
 /// 使用 refresh_token 刷新 access_token
 pub async fn refresh_access_token(refresh_token: &str) -> Result<CodexTokens, String> {
+    request_token_refresh(refresh_token).await.map_err(|e| e.message)
+}
+
+/// 刷新 Token，失败时保留状态码与 OAuth 错误码
+pub async fn request_token_refresh(refresh_token: &str) -> Result<CodexTokens, HttpError> {
     let client = reqwest::Client::new();
     
     let params = [
@@ -376,15 +382,16 @@ pub async fn refresh_access_token(refresh_token: &str) -> Result<CodexTokens, St
         .form(&params)
         .send()
         .await
-        .map_err(|e| format!("Token 刷新请求失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("Token 刷新请求失败: {}", e)))?;
     
     let status = response.status();
     let body = response.text().await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("读取响应失败: {}", e)))?;
     
     if !status.is_success() {
-        logger::log_error(&format!("Token 刷新失败: {} - {}", status, &body[..body.len().min(200)]));
-        return Err(format!("Token 刷新失败: {}", status));
+        let detail: String = body.chars().take(200).collect();
+        logger::log_error(&format!("Token 刷新失败: {} - {}", status, detail));
+        return Err(HttpError::response(status, &body, format!("Token 刷新失败: {} - {}", status, detail)));
     }
     
     logger::log_info("Codex Token 刷新成功");
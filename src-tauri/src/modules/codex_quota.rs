@@ -1,11 +1,9 @@
-use crate::models::codex::{CodexQuota, CodexAccount};
-use crate::modules::{codex_account, logger};
+use crate::models::codex::{CodexAccount, CodexQuota, CodexQuotaLevel, CodexRateLimit};
+use crate::modules::{codex_account, endpoints, logger};
+use crate::utils::http::HttpError;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, ACCEPT};
 use serde::{Deserialize, Serialize};
 
-// 使用 wham/usage 端点（Quotio 使用的）
-const USAGE_URL: &str = "https://chatgpt.com/backend-api/wham/usage";
-
 /// 使用率窗口（5小时/周）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct WindowInfo {
@@ -44,6 +42,11 @@ struct UsageResponse {
 
 /// 查询单个账号的配额
 pub async fn fetch_quota(account: &CodexAccount) -> Result<CodexQuota, String> {
+    request_quota(account).await.map_err(|e| e.message)
+}
+
+/// 查询配额，失败时保留状态码
+pub async fn request_quota(account: &CodexAccount) -> Result<CodexQuota, HttpError> {
     let client = reqwest::Client::new();
     
     let mut headers = HeaderMap::new();
@@ -70,25 +73,26 @@ pub async fn fetch_quota(account: &CodexAccount) -> Result<CodexQuota, String> {
         }
     }
     
-    logger::log_info(&format!("Codex 配额请求: {} (account_id: {:?})", USAGE_URL, account_id));
+    let usage_url = endpoints::codex_usage_url();
+    logger::log_info(&format!("Codex 配额请求: {} (account_id: {:?})", usage_url, account_id));
     
     let response = client
-        .get(USAGE_URL)
+        .get(&usage_url)
         .headers(headers)
         .send()
         .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("请求失败: {}", e)))?;
     
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
         // 截断响应，避免日志太长
         let body_preview = if body.len() > 200 { &body[..200] } else { &body };
-        return Err(format!("API 返回错误 {} - {}", status, body_preview));
+        return Err(HttpError::response(status, &body, format!("API 返回错误 {} - {}", status, body_preview)));
     }
     
     let body = response.text().await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("读取响应失败: {}", e)))?;
     
     logger::log_info(&format!("Codex 配额响应: {}", &body[..body.len().min(500)]));
     
@@ -96,7 +100,18 @@ pub async fn fetch_quota(account: &CodexAccount) -> Result<CodexQuota, String> {
     let usage: UsageResponse = serde_json::from_str(&body)
         .map_err(|e| format!("解析 JSON 失败: {}", e))?;
     
-    parse_quota_from_usage(&usage, &body)
+    Ok(parse_quota_from_usage(&usage, &body)?)
+}
+
+/// 窗口剩余百分比与重置时间
+fn window_remaining(window: Option<&WindowInfo>) -> (i32, Option<i64>, Option<i64>) {
+    match window {
+        Some(window) => {
+            let used = window.used_percent.unwrap_or(0);
+            ((100 - used).clamp(0, 100), window.reset_at, window.limit_window_seconds)
+        }
+        None => (100, None, None),
+    }
 }
 
 /// 从使用率响应中解析配额信息
@@ -104,24 +119,24 @@ fn parse_quota_from_usage(usage: &UsageResponse, raw_body: &str) -> Result<Codex
     let rate_limit = usage.rate_limit.as_ref();
     
     // Primary window = 5小时配额（session）
-    let (hourly_percentage, hourly_reset_time) = if let Some(primary) = rate_limit.and_then(|r| r.primary_window.as_ref()) {
-        let used = primary.used_percent.unwrap_or(0);
-        let remaining = 100 - used;
-        let reset_at = primary.reset_at;
-        (remaining, reset_at)
-    } else {
-        (100, None)
-    };
+    let (hourly_percentage, hourly_reset_time, hourly_window_seconds) =
+        window_remaining(rate_limit.and_then(|r| r.primary_window.as_ref()));
     
     // Secondary window = 周配额
-    let (weekly_percentage, weekly_reset_time) = if let Some(secondary) = rate_limit.and_then(|r| r.secondary_window.as_ref()) {
-        let used = secondary.used_percent.unwrap_or(0);
-        let remaining = 100 - used;
-        let reset_at = secondary.reset_at;
-        (remaining, reset_at)
-    } else {
-        (100, None)
-    };
+    let (weekly_percentage, weekly_reset_time, weekly_window_seconds) =
+        window_remaining(rate_limit.and_then(|r| r.secondary_window.as_ref()));
+
+    // Code Review 仅有主窗口
+    let code_review = usage.code_review_rate_limit.as_ref().map(|limit| {
+        let (percentage, reset_time, window_seconds) = window_remaining(limit.primary_window.as_ref());
+        CodexRateLimit {
+            percentage,
+            reset_time,
+            window_seconds,
+            allowed: limit.allowed.unwrap_or(true),
+            limit_reached: limit.limit_reached.unwrap_or(false),
+        }
+    });
     
     // 保存原始响应
     let raw_data: Option<serde_json::Value> = serde_json::from_str(raw_body).ok();
@@ -131,10 +146,49 @@ fn parse_quota_from_usage(usage: &UsageResponse, raw_body: &str) -> Result<Codex
         hourly_reset_time,
         weekly_percentage,
         weekly_reset_time,
+        hourly_window_seconds,
+        weekly_window_seconds,
+        allowed: rate_limit.and_then(|r| r.allowed).unwrap_or(true),
+        limit_reached: rate_limit.and_then(|r| r.limit_reached).unwrap_or(false),
+        plan_type: usage.plan_type.clone(),
+        code_review,
         raw_data,
     })
 }
 
+/// 配额等级变为「不足」或「耗尽」时通知前端
+fn notify_level_change(account: &CodexAccount, previous: Option<CodexQuotaLevel>, quota: &CodexQuota) {
+    let level = quota.level();
+    if level == CodexQuotaLevel::Normal || previous == Some(level) {
+        return;
+    }
+    let code_review_percentage = quota.code_review.as_ref().map(|limit| limit.percentage);
+    logger::log_warn(&format!(
+        "[CodexQuota] 账号 {} 配额{}: 5h {}% / 周 {}% / Code Review {}",
+        account.email,
+        if level == CodexQuotaLevel::Exhausted { "已耗尽" } else { "不足" },
+        quota.hourly_percentage,
+        quota.weekly_percentage,
+        code_review_percentage.map_or("-".to_string(), |p| format!("{}%", p))
+    ));
+    if let Some(app_handle) = crate::get_app_handle() {
+        use tauri::Emitter;
+        let _ = app_handle.emit(
+            "codex:quota_alert",
+            serde_json::json!({
+                "account_id": account.id,
+                "email": account.email,
+                "level": level,
+                "hourly_percentage": quota.hourly_percentage,
+                "weekly_percentage": quota.weekly_percentage,
+                "hourly_reset_time": quota.hourly_reset_time,
+                "weekly_reset_time": quota.weekly_reset_time,
+                "code_review_percentage": code_review_percentage,
+            }),
+        );
+    }
+}
+
 /// 刷新账号配额并保存（包含 token 自动刷新）
 pub async fn refresh_account_quota(account_id: &str) -> Result<CodexQuota, String> {
     let mut account = codex_account::load_account(account_id)
@@ -162,9 +216,17 @@ pub async fn refresh_account_quota(account_id: &str) -> Result<CodexQuota, Strin
     }
     
     let quota = fetch_quota(&account).await?;
+    let previous_level = account.quota.as_ref().map(|q| q.level());
     
     account.quota = Some(quota.clone());
     codex_account::save_account(&account)?;
+    notify_level_change(&account, previous_level, &quota);
+    crate::modules::websocket::broadcast_quota_updated(
+        crate::modules::websocket::AccountProvider::Codex,
+        &account.id,
+        &account.email,
+        crate::modules::websocket::ProviderQuota::Codex(quota.clone()),
+    );
     
     Ok(quota)
 }
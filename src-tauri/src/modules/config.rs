@@ -32,6 +32,24 @@ pub struct ServerStatus {
     pub pid: u32,
     /// 启动时间戳
     pub started_at: i64,
+    /// WebSocket 完整权限令牌（每次启动随机生成）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+    /// WebSocket 只读令牌（不可获取 Token、不可修改数据）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_token: Option<String>,
+    /// 本地 HTTP API（与 WebSocket 使用相同令牌）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_api: Option<HttpApiInfo>,
+}
+
+/// 本地 HTTP API 信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpApiInfo {
+    /// 实际绑定的端口
+    pub port: u16,
+    /// 接口基础地址，例如 `http://127.0.0.1:19529/api/v1`
+    pub base_url: String,
 }
 
 /// 用户配置（持久化存储）
@@ -70,6 +88,53 @@ pub struct UserConfig {
     /// 切换 Codex 时是否自动重启 OpenCode
     #[serde(default = "default_opencode_sync_on_switch")]
     pub opencode_sync_on_switch: bool,
+    /// 唤醒任务全局最大并发请求数（跨任务，通过 wakeup_set_max_concurrency 修改后立即生效）
+    #[serde(default = "default_wakeup_max_concurrency")]
+    pub wakeup_max_concurrency: u32,
+    /// 上游服务地址覆盖（为空则使用默认地址，环境变量优先）
+    #[serde(default)]
+    pub endpoints: EndpointOverrides,
+    /// 账号池同步文件夹（为空则不同步）
+    #[serde(default = "default_account_sync_folder")]
+    pub account_sync_folder: String,
+    /// 账号池同步口令，用于加密同步文件中的 refresh_token（为空则不同步 Token）
+    #[serde(default = "default_account_sync_passphrase")]
+    pub account_sync_passphrase: String,
+    /// 后台提前刷新各平台 Token
+    #[serde(default = "default_background_token_refresh")]
+    pub background_token_refresh: bool,
+    /// 账号健康检查间隔（分钟），-1 表示禁用定时检查
+    #[serde(default = "default_health_check_interval")]
+    pub health_check_interval_minutes: i32,
+    /// VS Code 启动路径（为空则使用默认路径）
+    #[serde(default = "default_vscode_app_path")]
+    pub vscode_app_path: String,
+    /// GitHub Copilot 凭据目录（为空则使用默认 github-copilot 目录）
+    #[serde(default = "default_copilot_config_dir")]
+    pub copilot_config_dir: String,
+    /// 托盘图标是否按当前账号的最低剩余配额动态绘制
+    #[serde(default = "default_tray_quota_icon")]
+    pub tray_quota_icon: bool,
+    /// 托盘图标统计的模型分组 ID（为空则统计全部模型）
+    #[serde(default = "default_tray_quota_group")]
+    pub tray_quota_group: String,
+}
+
+/// 上游服务地址覆盖
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EndpointOverrides {
+    /// Cloud Code 基础地址列表（按顺序尝试）
+    #[serde(default)]
+    pub cloud_code_base_urls: Vec<String>,
+    /// Antigravity 配额查询完整地址
+    #[serde(default)]
+    pub quota_api_url: Option<String>,
+    /// Codex 使用率查询完整地址
+    #[serde(default)]
+    pub codex_usage_url: Option<String>,
+    /// GitHub API 基础地址
+    #[serde(default)]
+    pub github_api_base: Option<String>,
 }
 
 /// 窗口关闭行为
@@ -101,6 +166,15 @@ fn default_opencode_app_path() -> String { String::new() }
 fn default_antigravity_app_path() -> String { String::new() }
 fn default_codex_app_path() -> String { String::new() }
 fn default_opencode_sync_on_switch() -> bool { true }
+fn default_wakeup_max_concurrency() -> u32 { 4 }
+fn default_account_sync_folder() -> String { String::new() }
+fn default_account_sync_passphrase() -> String { String::new() }
+fn default_background_token_refresh() -> bool { true }
+fn default_health_check_interval() -> i32 { -1 }
+fn default_vscode_app_path() -> String { String::new() }
+fn default_copilot_config_dir() -> String { String::new() }
+fn default_tray_quota_icon() -> bool { true }
+fn default_tray_quota_group() -> String { String::new() }
 
 impl Default for UserConfig {
     fn default() -> Self {
@@ -116,6 +190,16 @@ impl Default for UserConfig {
             antigravity_app_path: default_antigravity_app_path(),
             codex_app_path: default_codex_app_path(),
             opencode_sync_on_switch: default_opencode_sync_on_switch(),
+            wakeup_max_concurrency: default_wakeup_max_concurrency(),
+            endpoints: EndpointOverrides::default(),
+            account_sync_folder: default_account_sync_folder(),
+            account_sync_passphrase: default_account_sync_passphrase(),
+            background_token_refresh: default_background_token_refresh(),
+            health_check_interval_minutes: default_health_check_interval(),
+            vscode_app_path: default_vscode_app_path(),
+            copilot_config_dir: default_copilot_config_dir(),
+            tray_quota_icon: default_tray_quota_icon(),
+            tray_quota_group: default_tray_quota_group(),
         }
     }
 }
@@ -124,6 +208,12 @@ impl Default for UserConfig {
 struct RuntimeState {
     /// 当前实际使用的端口
     actual_port: Option<u16>,
+    /// WebSocket 鉴权令牌
+    auth_tokens: Option<WsAuthTokens>,
+    /// 本地 HTTP API 端口
+    http_port: Option<u16>,
+    /// 服务启动时间戳
+    started_at: i64,
     /// 用户配置
     user_config: UserConfig,
 }
@@ -135,13 +225,41 @@ fn get_runtime_state() -> &'static RwLock<RuntimeState> {
     RUNTIME_STATE.get_or_init(|| {
         RwLock::new(RuntimeState {
             actual_port: None,
+            auth_tokens: None,
+            http_port: None,
+            started_at: chrono::Utc::now().timestamp(),
             user_config: load_user_config().unwrap_or_default(),
         })
     })
 }
 
+/// 测试中使用的数据目录（避免写入真实的 ~/.antigravity_cockpit）
+#[cfg(test)]
+static TEST_DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 测试中覆盖数据目录（进程内只生效一次）
+#[cfg(test)]
+pub(crate) fn set_test_data_dir(path: PathBuf) {
+    let _ = TEST_DATA_DIR.set(path);
+}
+
+/// 数据目录覆盖（仅测试构建中可能存在）
+pub(crate) fn data_dir_override() -> Option<PathBuf> {
+    #[cfg(test)]
+    {
+        TEST_DATA_DIR.get().cloned()
+    }
+    #[cfg(not(test))]
+    {
+        None
+    }
+}
+
 /// 获取数据目录路径
 pub fn get_data_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = data_dir_override() {
+        return Ok(dir);
+    }
     let home = dirs::home_dir().ok_or("无法获取 Home 目录")?;
     Ok(home.join(DATA_DIR))
 }
@@ -149,6 +267,9 @@ pub fn get_data_dir() -> Result<PathBuf, String> {
 /// 获取共享目录路径（供其他模块使用）
 /// 与 get_data_dir 相同，但不返回 Result
 pub fn get_shared_dir() -> PathBuf {
+    if let Some(dir) = data_dir_override() {
+        return dir;
+    }
     dirs::home_dir()
         .map(|h| h.join(DATA_DIR))
         .unwrap_or_else(|| PathBuf::from(DATA_DIR))
@@ -232,6 +353,67 @@ pub fn get_actual_port() -> Option<u16> {
         .and_then(|state| state.actual_port)
 }
 
+/// WebSocket 鉴权令牌
+#[derive(Debug, Clone)]
+pub struct WsAuthTokens {
+    /// 完整权限
+    pub full: String,
+    /// 只读权限
+    pub read: String,
+}
+
+fn generate_token() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 获取当前 WebSocket 鉴权令牌（首次调用时生成）
+pub fn get_ws_auth_tokens() -> WsAuthTokens {
+    if let Ok(state) = get_runtime_state().read() {
+        if let Some(tokens) = &state.auth_tokens {
+            return tokens.clone();
+        }
+    }
+    let mut state = get_runtime_state().write().expect("runtime state lock");
+    state
+        .auth_tokens
+        .get_or_insert_with(|| WsAuthTokens {
+            full: generate_token(),
+            read: generate_token(),
+        })
+        .clone()
+}
+
+/// 写入仅当前用户可读写的文件（Unix 下权限 0600）
+fn write_private_file(path: &std::path::Path, content: &str) -> Result<(), String> {
+    let temp_path = path.with_extension("json.tmp");
+    {
+        use std::io::Write;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&temp_path)
+            .map_err(|e| format!("写入状态文件失败: {}", e))?;
+        file.write_all(content.as_bytes())
+            .map_err(|e| format!("写入状态文件失败: {}", e))?;
+    }
+    #[cfg(unix)]
+    {
+        // 已存在的临时文件不会被 mode 修改，这里再显式收紧一次
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("设置状态文件权限失败: {}", e))?;
+    }
+    fs::rename(&temp_path, path).map_err(|e| format!("替换状态文件失败: {}", e))
+}
+
 /// 保存服务状态到共享文件
 pub fn save_server_status(status: &ServerStatus) -> Result<(), String> {
     let status_path = get_server_status_path()?;
@@ -247,8 +429,8 @@ pub fn save_server_status(status: &ServerStatus) -> Result<(), String> {
     let json = serde_json::to_string_pretty(status)
         .map_err(|e| format!("序列化状态失败: {}", e))?;
     
-    fs::write(&status_path, json)
-        .map_err(|e| format!("写入状态文件失败: {}", e))?;
+    // 包含鉴权令牌，限制为仅当前用户可读
+    write_private_file(&status_path, &json)?;
     
     crate::modules::logger::log_info(&format!(
         "[Config] 服务状态已保存: ws_port={}, pid={}",
@@ -258,6 +440,36 @@ pub fn save_server_status(status: &ServerStatus) -> Result<(), String> {
     Ok(())
 }
 
+/// 按运行时状态重新生成并保存服务状态
+fn write_server_status() -> Result<(), String> {
+    let tokens = get_ws_auth_tokens();
+    let (ws_port, http_port, started_at) = {
+        let state = get_runtime_state()
+            .read()
+            .map_err(|_| "读取运行时状态失败".to_string())?;
+        (state.actual_port, state.http_port, state.started_at)
+    };
+    let Some(ws_port) = ws_port else {
+        // WebSocket 尚未启动，等其启动后一并写入
+        return Ok(());
+    };
+
+    let status = ServerStatus {
+        ws_port,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        pid: std::process::id(),
+        started_at,
+        auth_token: Some(tokens.full),
+        read_token: Some(tokens.read),
+        http_api: http_port.map(|port| HttpApiInfo {
+            port,
+            base_url: format!("http://127.0.0.1:{}/api/v1", port),
+        }),
+    };
+    
+    save_server_status(&status)
+}
+
 /// 初始化服务状态（WebSocket 启动后调用）
 pub fn init_server_status(actual_port: u16) -> Result<(), String> {
     // 更新运行时状态
@@ -265,14 +477,14 @@ pub fn init_server_status(actual_port: u16) -> Result<(), String> {
         state.actual_port = Some(actual_port);
     }
     
-    let status = ServerStatus {
-        ws_port: actual_port,
-        version: env!("CARGO_PKG_VERSION").to_string(),
-        pid: std::process::id(),
-        started_at: chrono::Utc::now().timestamp(),
-    };
-    
-    save_server_status(&status)?;
-    
-    Ok(())
+    write_server_status()
+}
+
+/// 记录本地 HTTP API 端口并更新服务状态（HTTP API 启动后调用）
+pub fn set_http_api_port(port: u16) -> Result<(), String> {
+    if let Ok(mut state) = get_runtime_state().write() {
+        state.http_port = Some(port);
+    }
+
+    write_server_status()
 }
@@ -1,5 +1,6 @@
 use crate::models::copilot::{CopilotAccount, CopilotAccountIndex, CopilotAccountSummary};
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+use crate::utils::http::HttpError;
 use serde::Deserialize;
 use std::fs;
 use std::path::PathBuf;
@@ -89,6 +90,11 @@ pub fn get_current_account() -> Option<CopilotAccount> {
 }
 
 pub async fn fetch_github_user(token: &str) -> Result<(String, Option<String>), String> {
+    request_github_user(token).await.map_err(|e| e.message)
+}
+
+/// 请求 GitHub /user，失败时保留 HTTP 状态码供调用方区分 Token 失效与网络错误
+pub async fn request_github_user(token: &str) -> Result<(String, Option<String>), HttpError> {
     let client = reqwest::Client::new();
     let mut headers = HeaderMap::new();
     headers.insert(
@@ -100,16 +106,16 @@ pub async fn fetch_github_user(token: &str) -> Result<(String, Option<String>),
     headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github+json"));
 
     let response = client
-        .get("https://api.github.com/user")
+        .get(format!("{}/user", crate::modules::endpoints::github_api_base()))
         .headers(headers)
         .send()
         .await
-        .map_err(|e| format!("请求 GitHub /user 失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("请求 GitHub /user 失败: {}", e)))?;
 
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(format!("GitHub /user 返回错误 {} - {}", status, body));
+        return Err(HttpError::response(status, &body, format!("GitHub /user 返回错误 {} - {}", status, body)));
     }
 
     let user: GitHubUserResponse = response
@@ -149,6 +155,11 @@ pub fn upsert_account(
         acc.email = email;
         acc.plan = plan;
         acc.monthly_included_requests = monthly_included_requests;
+        if acc.disabled {
+            acc.disabled = false;
+            acc.disabled_reason = None;
+            acc.disabled_at = None;
+        }
         acc.update_last_used();
         acc
     } else {
@@ -0,0 +1,223 @@
+//! GitHub Copilot 编辑器凭据
+//!
+//! JetBrains、Vim / Neovim 等编辑器的 Copilot 插件从 `github-copilot` 目录读取 GitHub Token
+//! （VS Code 的 Copilot 使用 VS Code 自身的 GitHub 登录会话，不读取这些文件）：
+//! - `hosts.json`：旧版格式，`{"github.com": {"user", "oauth_token"}}`
+//! - `apps.json`：新版格式，键为 `github.com:<App ID>`
+//!
+//! 切换账号时更新这两个文件：已有的其他条目保持不变，写入前备份原文件，
+//! 两个文件通过 `atomic_write` 一并替换，替换失败时回滚；后续步骤失败时可用
+//! [`rollback_credentials`] 撤销写入。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::models::copilot::CopilotAccount;
+use crate::modules::atomic_write::{self, ReplacedFile};
+use crate::modules::{account, config, logger};
+
+/// Copilot 插件默认 GitHub App ID
+const DEFAULT_APP_ID: &str = "Iv1.b507a08c87ecfe98";
+const GITHUB_HOST: &str = "github.com";
+const HOSTS_FILE: &str = "hosts.json";
+const APPS_FILE: &str = "apps.json";
+/// 备份目录（位于数据目录）
+const BACKUP_DIR: &str = "copilot_credential_backups";
+/// 每个文件保留的备份数量
+const MAX_BACKUPS_PER_FILE: usize = 5;
+
+/// 一次凭据写入，保存各文件的原内容用于回滚
+#[derive(Debug)]
+pub struct CopilotCredentialsWritten {
+    replaced: Vec<ReplacedFile>,
+}
+
+/// 获取 github-copilot 凭据目录（可在设置中覆盖）
+pub fn get_copilot_config_dir() -> Result<PathBuf, String> {
+    let custom = config::get_user_config().copilot_config_dir;
+    if !custom.trim().is_empty() {
+        return Ok(PathBuf::from(custom.trim()));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let local_appdata = dirs::data_local_dir().ok_or("无法获取 LOCALAPPDATA 目录")?;
+        Ok(local_appdata.join("github-copilot"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let base = match std::env::var("XDG_CONFIG_HOME") {
+            Ok(value) if !value.trim().is_empty() => PathBuf::from(value),
+            _ => dirs::home_dir().ok_or("无法获取用户主目录")?.join(".config"),
+        };
+        Ok(base.join("github-copilot"))
+    }
+}
+
+fn read_document(path: &Path) -> Result<Map<String, Value>, String> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    if content.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str(&content).map_err(|e| format!("解析 {} 失败: {}", path.display(), e))? {
+        Value::Object(map) => Ok(map),
+        _ => Err(format!("{} 不是 JSON 对象", path.display())),
+    }
+}
+
+/// 更新 hosts.json 中的 github.com 条目
+fn apply_hosts(document: &mut Map<String, Value>, username: &str, token: &str) {
+    document.insert(
+        GITHUB_HOST.to_string(),
+        json!({ "user": username, "oauth_token": token }),
+    );
+}
+
+/// 更新 apps.json 中所有 github.com 条目（不存在时按默认 App ID 新增）
+fn apply_apps(document: &mut Map<String, Value>, username: &str, token: &str) {
+    let prefix = format!("{}:", GITHUB_HOST);
+    let keys: Vec<String> = document.keys().filter(|key| key.starts_with(&prefix)).cloned().collect();
+    let keys = if keys.is_empty() {
+        vec![format!("{}{}", prefix, DEFAULT_APP_ID)]
+    } else {
+        keys
+    };
+
+    for key in keys {
+        let app_id = key[prefix.len()..].to_string();
+        let mut entry = match document.remove(&key) {
+            Some(Value::Object(entry)) => entry,
+            _ => Map::new(),
+        };
+        entry.insert("user".to_string(), Value::String(username.to_string()));
+        entry.insert("oauth_token".to_string(), Value::String(token.to_string()));
+        entry.insert("githubAppId".to_string(), Value::String(app_id));
+        document.insert(key, Value::Object(entry));
+    }
+}
+
+fn backup_dir() -> Result<PathBuf, String> {
+    let dir = account::get_data_dir()?.join(BACKUP_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("创建备份目录失败: {}", e))?;
+    Ok(dir)
+}
+
+/// 备份原文件，并清理超出数量的旧备份
+fn backup_file(path: &Path) -> Result<Option<PathBuf>, String> {
+    atomic_write::backup_file(path, &backup_dir()?, MAX_BACKUPS_PER_FILE)
+}
+
+/// 列出已有备份（新的在前）
+pub fn list_backups() -> Result<Vec<String>, String> {
+    let dir = backup_dir()?;
+    let mut backups: Vec<String> = fs::read_dir(&dir)
+        .map_err(|e| format!("读取备份目录失败: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|p| p.extension().and_then(|ext| ext.to_str()) == Some("bak"))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    backups.sort();
+    backups.reverse();
+    Ok(backups)
+}
+
+/// 将账号写入 github-copilot 凭据目录（写入前备份）
+pub fn write_credentials(account: &CopilotAccount) -> Result<CopilotCredentialsWritten, String> {
+    write_to_dir(&get_copilot_config_dir()?, account)
+}
+
+/// 撤销一次凭据写入，恢复各文件的原内容
+pub fn rollback_credentials(written: CopilotCredentialsWritten) {
+    logger::log_warn("[CopilotAuth] 回滚已写入的凭据");
+    atomic_write::rollback(written.replaced);
+}
+
+/// apps.json 总是写入；hosts.json 仅在已存在时更新（新版插件不再读取）
+fn write_to_dir(dir: &Path, account: &CopilotAccount) -> Result<CopilotCredentialsWritten, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("创建 {} 失败: {}", dir.display(), e))?;
+
+    let mut targets: Vec<(PathBuf, String)> = Vec::new();
+    let hosts_path = dir.join(HOSTS_FILE);
+    if hosts_path.exists() {
+        let mut document = read_document(&hosts_path)?;
+        apply_hosts(&mut document, &account.username, &account.token);
+        targets.push((hosts_path, serialize(&document)?));
+    }
+    let apps_path = dir.join(APPS_FILE);
+    let mut document = read_document(&apps_path)?;
+    apply_apps(&mut document, &account.username, &account.token);
+    targets.push((apps_path, serialize(&document)?));
+
+    // 第一阶段：写入临时文件
+    let mut staged = Vec::new();
+    for (path, content) in &targets {
+        match atomic_write::stage(path, content.as_bytes()) {
+            Ok(file) => staged.push(file),
+            Err(e) => {
+                staged.iter().for_each(|file| file.discard());
+                return Err(e);
+            }
+        }
+    }
+
+    // 第二阶段：备份并依次替换，失败时回滚已替换的文件
+    let mut backups = Vec::new();
+    let replaced = atomic_write::replace_all(&staged, |path| {
+        backups.push(backup_file(path)?);
+        Ok(())
+    })
+    .map_err(|(_, e)| e)?;
+
+    for (file, backup_path) in replaced.iter().zip(backups) {
+        match backup_path {
+            Some(backup_path) => logger::log_info(&format!(
+                "[CopilotAuth] 已写入 {} 的凭据: {}（备份: {}）",
+                account.username,
+                file.path.display(),
+                backup_path.display()
+            )),
+            None => logger::log_info(&format!(
+                "[CopilotAuth] 已写入 {} 的凭据: {}",
+                account.username,
+                file.path.display()
+            )),
+        }
+    }
+    Ok(CopilotCredentialsWritten { replaced })
+}
+
+fn serialize(document: &Map<String, Value>) -> Result<String, String> {
+    serde_json::to_string_pretty(document).map_err(|e| format!("序列化失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apps_updates_existing_entries_and_keeps_others() {
+        let mut document: Map<String, Value> = serde_json::from_value(json!({
+            "github.com:Iv1.custom": { "user": "old", "oauth_token": "gho_old", "githubAppId": "Iv1.custom", "extra": 1 },
+            "ghe.example.com:Iv1.custom": { "user": "corp", "oauth_token": "gho_corp" },
+        }))
+        .unwrap();
+        apply_apps(&mut document, "octocat", "gho_new");
+
+        assert_eq!(document.len(), 2);
+        let entry = &document["github.com:Iv1.custom"];
+        assert_eq!(entry["user"], "octocat");
+        assert_eq!(entry["oauth_token"], "gho_new");
+        assert_eq!(entry["extra"], 1);
+        assert_eq!(document["ghe.example.com:Iv1.custom"]["user"], "corp");
+
+        let mut empty = Map::new();
+        apply_apps(&mut empty, "octocat", "gho_new");
+        assert_eq!(empty["github.com:Iv1.b507a08c87ecfe98"]["githubAppId"], DEFAULT_APP_ID);
+    }
+}
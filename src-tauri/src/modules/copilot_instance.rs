@@ -0,0 +1,313 @@
+//! Copilot 多开实例：每个实例是独立的 VS Code `--user-data-dir`。
+//!
+//! VS Code 的 Copilot 通过 VS Code 自身的 GitHub 登录会话获取 Token（保存在系统钥匙串中），
+//! 不读取 `github-copilot` 目录下的 apps.json / hosts.json，因此实例不支持绑定账号，
+//! 需在各实例内分别登录 GitHub。旧版本保存的绑定在读取时清除。
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::models::{DefaultInstanceSettings, InstanceProfile, InstanceStore};
+use crate::modules;
+use crate::modules::instance::InstanceDefaults;
+use crate::modules::instance_store;
+
+pub use crate::modules::instance_store::{CreateInstanceParams, UpdateInstanceParams};
+
+static COPILOT_INSTANCE_STORE_LOCK: std::sync::LazyLock<Mutex<()>> =
+    std::sync::LazyLock::new(|| Mutex::new(()));
+
+const COPILOT_INSTANCES_FILE: &str = "copilot_instances.json";
+
+const ACCOUNT_BINDING_UNSUPPORTED: &str =
+    "VS Code 的 Copilot 使用 VS Code 自身的 GitHub 登录会话，不支持为实例绑定账号，请在实例内登录 GitHub";
+
+fn instances_path() -> Result<PathBuf, String> {
+    let data_dir = modules::account::get_data_dir()?;
+    Ok(data_dir.join(COPILOT_INSTANCES_FILE))
+}
+
+pub fn load_instance_store() -> Result<InstanceStore, String> {
+    let path = instances_path()?;
+    let mut store = instance_store::load_instance_store(&path, COPILOT_INSTANCES_FILE)?;
+    store.default_settings.bind_account_id = None;
+    store.default_settings.follow_local_account = false;
+    for instance in &mut store.instances {
+        instance.bind_account_id = None;
+    }
+    Ok(store)
+}
+
+pub fn save_instance_store(store: &InstanceStore) -> Result<(), String> {
+    let path = instances_path()?;
+    instance_store::save_instance_store(&path, COPILOT_INSTANCES_FILE, store)
+}
+
+pub fn load_default_settings() -> Result<DefaultInstanceSettings, String> {
+    let store = load_instance_store()?;
+    Ok(store.default_settings)
+}
+
+pub fn update_default_settings(
+    bind_account_id: Option<Option<String>>,
+    extra_args: Option<String>,
+    follow_local_account: Option<bool>,
+) -> Result<DefaultInstanceSettings, String> {
+    if matches!(bind_account_id, Some(Some(_))) || follow_local_account == Some(true) {
+        return Err(ACCOUNT_BINDING_UNSUPPORTED.to_string());
+    }
+    let _lock = COPILOT_INSTANCE_STORE_LOCK.lock().map_err(|_| "无法获取实例锁")?;
+    let mut store = load_instance_store()?;
+    let settings = &mut store.default_settings;
+
+    if let Some(args) = extra_args {
+        settings.extra_args = args.trim().to_string();
+    }
+
+    let updated = settings.clone();
+    save_instance_store(&store)?;
+    Ok(updated)
+}
+
+/// VS Code 默认用户数据目录
+pub fn get_default_vscode_user_data_dir() -> Result<PathBuf, String> {
+    #[cfg(target_os = "macos")]
+    {
+        let home = dirs::home_dir().ok_or("无法获取用户主目录")?;
+        return Ok(home.join("Library/Application Support/Code"));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let appdata = dirs::config_dir().ok_or("无法获取 APPDATA 目录")?;
+        return Ok(appdata.join("Code"));
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let home = dirs::home_dir().ok_or("无法获取用户主目录")?;
+        Ok(home.join(".config/Code"))
+    }
+}
+
+pub fn get_default_instances_root_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("无法获取用户主目录")?;
+    Ok(home.join(".antigravity_cockpit/instances/copilot"))
+}
+
+pub fn get_instance_defaults() -> Result<InstanceDefaults, String> {
+    let root_dir = get_default_instances_root_dir()?;
+    let default_user_data_dir = get_default_vscode_user_data_dir()?;
+    Ok(InstanceDefaults {
+        root_dir: root_dir.to_string_lossy().to_string(),
+        default_user_data_dir: default_user_data_dir.to_string_lossy().to_string(),
+    })
+}
+
+pub fn create_instance(params: CreateInstanceParams) -> Result<InstanceProfile, String> {
+    if params.bind_account_id.is_some() {
+        return Err(ACCOUNT_BINDING_UNSUPPORTED.to_string());
+    }
+    let _lock = COPILOT_INSTANCE_STORE_LOCK.lock().map_err(|_| "无法获取实例锁")?;
+    let mut store = load_instance_store()?;
+
+    let name = instance_store::normalize_name(&params.name)?;
+    let user_data_dir = params.user_data_dir.trim().to_string();
+    if user_data_dir.is_empty() {
+        return Err("实例目录不能为空".to_string());
+    }
+
+    instance_store::ensure_unique(&store, &name, &user_data_dir, None)?;
+
+    let user_dir_path = PathBuf::from(&user_data_dir);
+    let init_mode = params
+        .init_mode
+        .as_deref()
+        .unwrap_or("copy")
+        .to_ascii_lowercase();
+    let create_empty = init_mode == "empty";
+
+    if create_empty {
+        if user_dir_path.exists() {
+            let mut has_entries = false;
+            if let Ok(mut iter) = fs::read_dir(&user_dir_path) {
+                if iter.next().is_some() {
+                    has_entries = true;
+                }
+            }
+            if has_entries {
+                let resolved_path = instance_store::display_path(&user_dir_path);
+                return Err(format!("空白实例需要目标目录为空: {}", resolved_path));
+            }
+        }
+        fs::create_dir_all(&user_dir_path).map_err(|e| format!("创建实例目录失败: {}", e))?;
+    } else {
+        let source_dir = match params.copy_source_instance_id.as_deref() {
+            Some("__default__") | None => get_default_vscode_user_data_dir()?,
+            Some(source_id) => {
+                let source_instance = store
+                    .instances
+                    .iter()
+                    .find(|item| item.id == source_id)
+                    .ok_or("复制来源实例不存在")?;
+                PathBuf::from(&source_instance.user_data_dir)
+            }
+        };
+
+        if user_dir_path.exists() {
+            let mut has_entries = false;
+            if let Ok(mut iter) = fs::read_dir(&user_dir_path) {
+                if iter.next().is_some() {
+                    has_entries = true;
+                }
+            }
+            if has_entries {
+                let resolved_path = instance_store::display_path(&user_dir_path);
+                modules::logger::log_info(&format!(
+                    "[Copilot Instance] 复制来源实例需要空目录，但目标已存在: {}",
+                    resolved_path
+                ));
+                return Err(format!("复制来源实例需要目标目录为空: {}", resolved_path));
+            }
+        }
+
+        if !source_dir.exists() {
+            return Err("未找到复制来源目录，请先确保来源实例已初始化".to_string());
+        }
+
+        instance_store::copy_dir_recursive(&source_dir, &user_dir_path)?;
+    }
+
+    let instance = InstanceProfile {
+        id: Uuid::new_v4().to_string(),
+        name,
+        user_data_dir,
+        extra_args: params.extra_args.trim().to_string(),
+        bind_account_id: if create_empty { None } else { params.bind_account_id },
+        created_at: Utc::now().timestamp_millis(),
+        last_launched_at: None,
+        last_pid: None,
+    };
+
+    store.instances.push(instance.clone());
+    save_instance_store(&store)?;
+    Ok(instance)
+}
+
+pub fn update_instance(params: UpdateInstanceParams) -> Result<InstanceProfile, String> {
+    if matches!(params.bind_account_id, Some(Some(_))) {
+        return Err(ACCOUNT_BINDING_UNSUPPORTED.to_string());
+    }
+    let _lock = COPILOT_INSTANCE_STORE_LOCK.lock().map_err(|_| "无法获取实例锁")?;
+    let mut store = load_instance_store()?;
+    let index = store
+        .instances
+        .iter()
+        .position(|instance| instance.id == params.instance_id)
+        .ok_or("实例不存在")?;
+
+    let current_id = store.instances[index].id.clone();
+    let current_dir = store.instances[index].user_data_dir.clone();
+    let next_name = params
+        .name
+        .as_ref()
+        .map(|name| instance_store::normalize_name(name))
+        .transpose()?;
+
+    if let Some(ref normalized) = next_name {
+        instance_store::ensure_unique(&store, normalized, &current_dir, Some(&current_id))?;
+    }
+
+    let instance = &mut store.instances[index];
+    if let Some(normalized) = next_name {
+        instance.name = normalized;
+    }
+    if let Some(ref extra_args) = params.extra_args {
+        instance.extra_args = extra_args.trim().to_string();
+    }
+    if let Some(bind) = params.bind_account_id.clone() {
+        instance.bind_account_id = bind;
+    }
+
+    let updated = instance.clone();
+    save_instance_store(&store)?;
+    Ok(updated)
+}
+
+pub fn delete_instance(instance_id: &str) -> Result<(), String> {
+    let _lock = COPILOT_INSTANCE_STORE_LOCK.lock().map_err(|_| "无法获取实例锁")?;
+    let mut store = load_instance_store()?;
+    let index = store
+        .instances
+        .iter()
+        .position(|instance| instance.id == instance_id)
+        .ok_or("实例不存在")?;
+    let user_data_dir = store.instances[index].user_data_dir.clone();
+
+    if !user_data_dir.trim().is_empty() {
+        let dir_path = PathBuf::from(&user_data_dir);
+        modules::instance::delete_instance_directory(&dir_path)?;
+    }
+
+    store.instances.remove(index);
+    save_instance_store(&store)?;
+    Ok(())
+}
+
+
+pub fn update_instance_after_start(instance_id: &str, pid: u32) -> Result<InstanceProfile, String> {
+    let _lock = COPILOT_INSTANCE_STORE_LOCK.lock().map_err(|_| "无法获取实例锁")?;
+    let mut store = load_instance_store()?;
+    let mut updated = None;
+    for instance in &mut store.instances {
+        if instance.id == instance_id {
+            instance.last_launched_at = Some(Utc::now().timestamp_millis());
+            instance.last_pid = Some(pid);
+            updated = Some(instance.clone());
+            break;
+        }
+    }
+    let updated = updated.ok_or("实例不存在")?;
+    save_instance_store(&store)?;
+    Ok(updated)
+}
+
+pub fn update_instance_pid(instance_id: &str, pid: Option<u32>) -> Result<InstanceProfile, String> {
+    let _lock = COPILOT_INSTANCE_STORE_LOCK.lock().map_err(|_| "无法获取实例锁")?;
+    let mut store = load_instance_store()?;
+    let mut updated = None;
+    for instance in &mut store.instances {
+        if instance.id == instance_id {
+            instance.last_pid = pid;
+            updated = Some(instance.clone());
+            break;
+        }
+    }
+    let updated = updated.ok_or("实例不存在")?;
+    save_instance_store(&store)?;
+    Ok(updated)
+}
+
+pub fn update_default_pid(pid: Option<u32>) -> Result<DefaultInstanceSettings, String> {
+    let _lock = COPILOT_INSTANCE_STORE_LOCK.lock().map_err(|_| "无法获取实例锁")?;
+    let mut store = load_instance_store()?;
+    store.default_settings.last_pid = pid;
+    let updated = store.default_settings.clone();
+    save_instance_store(&store)?;
+    Ok(updated)
+}
+
+pub fn clear_all_pids() -> Result<(), String> {
+    let _lock = COPILOT_INSTANCE_STORE_LOCK.lock().map_err(|_| "无法获取实例锁")?;
+    let mut store = load_instance_store()?;
+    store.default_settings.last_pid = None;
+    for instance in &mut store.instances {
+        instance.last_pid = None;
+    }
+    save_instance_store(&store)?;
+    Ok(())
+}
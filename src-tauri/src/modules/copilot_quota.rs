@@ -1,43 +1,73 @@
-use crate::models::copilot::CopilotQuota;
-use crate::modules::copilot_account;
+use crate::models::copilot::{CopilotQuota, CopilotQuotaSnapshot};
+use crate::modules::{copilot_account, endpoints};
+use crate::utils::http::HttpError;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
 use serde_json::Value;
 
-fn parse_premium_interactions(raw: &Value) -> Result<(i64, i64, Option<String>, Option<String>), String> {
+/// 快照显示顺序
+const SNAPSHOT_ORDER: [&str; 3] = ["premium_interactions", "chat", "completions"];
+
+fn parse_snapshot(quota_id: &str, value: &Value) -> Option<CopilotQuotaSnapshot> {
+    let obj = value.as_object()?;
+    let number = |key: &str| obj.get(key).and_then(|v| v.as_f64());
+    Some(CopilotQuotaSnapshot {
+        quota_id: obj
+            .get("quota_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or(quota_id)
+            .to_string(),
+        entitlement: number("entitlement").map(|v| v.round() as i64),
+        remaining: number("remaining").map(|v| v.round() as i64),
+        percent_remaining: number("percent_remaining"),
+        unlimited: obj.get("unlimited").and_then(|v| v.as_bool()).unwrap_or(false),
+        overage_permitted: obj.get("overage_permitted").and_then(|v| v.as_bool()).unwrap_or(false),
+        overage_count: number("overage_count").map(|v| v.round() as i64).unwrap_or(0),
+    })
+}
+
+/// 解析 `quota_snapshots` 中的全部快照（premium_interactions 必须存在）
+fn parse_quota_snapshots(raw: &Value) -> Result<Vec<CopilotQuotaSnapshot>, String> {
     let snapshots = raw
         .get("quota_snapshots")
         .and_then(|v| v.as_object())
         .ok_or_else(|| "缺少 quota_snapshots".to_string())?;
-    let premium = snapshots
-        .get("premium_interactions")
-        .and_then(|v| v.as_object())
-        .ok_or_else(|| "缺少 premium_interactions".to_string())?;
-
-    let entitlement = premium
-        .get("entitlement")
-        .and_then(|v| v.as_i64())
-        .ok_or_else(|| "缺少 entitlement".to_string())?;
-    let remaining = premium
-        .get("remaining")
-        .and_then(|v| v.as_i64())
-        .ok_or_else(|| "缺少 remaining".to_string())?;
 
-    let plan = raw
-        .get("copilot_plan")
-        .and_then(|v| v.as_str())
-        .map(|v| v.to_string());
-    let reset_date = raw
-        .get("quota_reset_date")
-        .and_then(|v| v.as_str())
-        .map(|v| v.to_string());
+    let mut parsed: Vec<CopilotQuotaSnapshot> = snapshots
+        .iter()
+        .filter_map(|(id, value)| parse_snapshot(id, value))
+        .collect();
+    parsed.sort_by_key(|snapshot| {
+        let rank = SNAPSHOT_ORDER
+            .iter()
+            .position(|id| *id == snapshot.quota_id)
+            .unwrap_or(SNAPSHOT_ORDER.len());
+        (rank, snapshot.quota_id.clone())
+    });
+
+    let premium = parsed
+        .iter()
+        .find(|snapshot| snapshot.quota_id == "premium_interactions")
+        .ok_or_else(|| "缺少 premium_interactions".to_string())?;
+    if !premium.unlimited {
+        premium.entitlement.ok_or_else(|| "缺少 entitlement".to_string())?;
+        premium.remaining.ok_or_else(|| "缺少 remaining".to_string())?;
+    }
 
-    Ok((entitlement, remaining, plan, reset_date))
+    Ok(parsed)
 }
 
 pub async fn fetch_quota(
     token: &str,
     included_requests_override: Option<i64>,
 ) -> Result<CopilotQuota, String> {
+    request_quota(token, included_requests_override).await.map_err(|e| e.message)
+}
+
+/// 查询配额，失败时保留状态码
+pub async fn request_quota(
+    token: &str,
+    included_requests_override: Option<i64>,
+) -> Result<CopilotQuota, HttpError> {
     let client = reqwest::Client::new();
 
     let mut headers = HeaderMap::new();
@@ -50,38 +80,67 @@ pub async fn fetch_quota(
     headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github+json"));
     headers.insert("X-GitHub-Api-Version", HeaderValue::from_static("2022-11-28"));
 
-    let url = "https://api.github.com/copilot_internal/user";
+    let url = format!("{}/copilot_internal/user", endpoints::github_api_base());
 
     let response = client
-        .get(url)
+        .get(&url)
         .headers(headers)
         .send()
         .await
-        .map_err(|e| format!("请求 Copilot usage 失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("请求 Copilot usage 失败: {}", e)))?;
 
     let status = response.status();
-    let raw: Value = response
-        .json()
+    let body = response
+        .text()
         .await
-        .map_err(|e| format!("解析 Copilot usage 响应失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("读取 Copilot usage 响应失败: {}", e)))?;
 
     if !status.is_success() {
+        let raw: Value = serde_json::from_str(&body).unwrap_or(Value::String(body.clone()));
         if status.as_u16() == 403 {
             let message = raw
                 .get("message")
                 .and_then(|v| v.as_str())
                 .unwrap_or_default();
             if message == "Resource not accessible by integration" {
-                return Err("COPILOT_PERMISSION_INTEGRATION".to_string());
+                return Err(HttpError::response(status, &body, "COPILOT_PERMISSION_INTEGRATION".to_string()));
             }
         }
-        return Err(format!("Copilot usage API 返回错误 {} - {}", status, raw));
+        return Err(HttpError::response(
+            status,
+            &body,
+            format!("Copilot usage API 返回错误 {} - {}", status, raw),
+        ));
     }
 
-    let (entitlement, remaining, plan, reset_date) = parse_premium_interactions(&raw)?;
-    let included_requests = included_requests_override.or(Some(entitlement));
-    let used_requests = (entitlement - remaining).max(0);
-    let remaining_requests = Some(remaining.max(0));
+    let raw: Value =
+        serde_json::from_str(&body).map_err(|e| format!("解析 Copilot usage 响应失败: {}", e))?;
+
+    let snapshots = parse_quota_snapshots(&raw)?;
+    let premium = snapshots
+        .iter()
+        .find(|snapshot| snapshot.quota_id == "premium_interactions")
+        .expect("premium_interactions checked by parser");
+    let (included_requests, used_requests, remaining_requests) = if premium.unlimited {
+        (included_requests_override, 0, None)
+    } else {
+        let entitlement = premium.entitlement.unwrap_or_default();
+        let remaining = premium.remaining.unwrap_or_default();
+        (
+            included_requests_override.or(Some(entitlement)),
+            (entitlement - remaining).max(0),
+            Some(remaining.max(0)),
+        )
+    };
+
+    let plan = raw
+        .get("copilot_plan")
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string());
+    let reset_date = raw
+        .get("quota_reset_date")
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string());
 
     Ok(CopilotQuota {
         used_requests,
@@ -90,6 +149,7 @@ pub async fn fetch_quota(
         usage_items_count: 0,
         copilot_plan: plan,
         quota_reset_date: reset_date,
+        snapshots,
         raw_data: Some(raw),
     })
 }
@@ -102,6 +162,12 @@ pub async fn refresh_account_quota(account_id: &str) -> Result<CopilotQuota, Str
 
     account.quota = Some(quota.clone());
     copilot_account::save_account(&account)?;
+    crate::modules::websocket::broadcast_quota_updated(
+        crate::modules::websocket::AccountProvider::Copilot,
+        &account.id,
+        account.email.as_deref().unwrap_or(&account.username),
+        crate::modules::websocket::ProviderQuota::Copilot(quota.clone()),
+    );
     Ok(quota)
 }
 
@@ -0,0 +1,604 @@
+//! Codex 凭据同步目标（credential sink）
+//!
+//! 切换 Codex 账号后，将 token 写入其他读取 OpenAI OAuth 凭据的 CLI / 编辑器插件配置文件。
+//! 每个目标声明：
+//! - `path`：目标文件（支持 `~/` 开头）
+//! - `format`：`json` 或 `toml`
+//! - `key_path`：写入位置（`.` 分隔，如 `providers.openai`；为空表示合并到根对象）
+//! - `template`：写入内容模板，字符串中的 `{{变量}}` 会被替换
+//! - `restart`：写入后的重启钩子
+//!
+//! 模板变量：`access_token`、`refresh_token`、`id_token`、`account_id`、`email`、`user_id`、
+//! `plan_type`、`expires_ms`、`expires_at`（秒）。字符串恰好为 `{{变量}}` 时保留原类型（如数字），
+//! 写成 `{{变量?}}` 表示变量缺失时删除该字段。
+//!
+//! 所有目标先渲染并写入临时文件，全部成功后再依次替换：任一启用目标渲染或写入临时文件失败时
+//! 不更新任何目标；替换中途失败会回滚已替换的文件。
+//! OpenCode 为内置目标，可通过 `disabled_builtins` 关闭。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::codex::CodexAccount;
+use crate::modules::atomic_write::{self, StagedFile};
+use crate::modules::{account, codex_account, codex_oauth, config, logger, opencode_auth, process};
+
+/// 用户配置的同步目标文件名（位于数据目录）
+const CREDENTIAL_SINKS_FILE: &str = "credential_sinks.json";
+
+/// 文件格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+/// 写入后的重启钩子
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RestartHook {
+    #[default]
+    None,
+    /// 内置 OpenCode 重启（受「切换 Codex 时自动重启 OpenCode」设置控制）
+    Opencode,
+    /// 执行外部命令
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// 单个同步目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialSink {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 内置目标（不会写入配置文件）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub builtin: bool,
+    pub path: String,
+    #[serde(default)]
+    pub format: SinkFormat,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    pub template: Value,
+    #[serde(default)]
+    pub restart: RestartHook,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 同步目标配置文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialSinkConfig {
+    #[serde(default)]
+    pub sinks: Vec<CredentialSink>,
+    /// 已关闭的内置目标 ID
+    #[serde(default)]
+    pub disabled_builtins: Vec<String>,
+}
+
+/// 单个目标的同步结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SinkResult {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub updated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(account::get_data_dir()?.join(CREDENTIAL_SINKS_FILE))
+}
+
+/// 读取用户配置的同步目标
+pub fn load_config() -> Result<CredentialSinkConfig, String> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(CredentialSinkConfig::default());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("读取同步目标配置失败: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析同步目标配置失败: {}", e))
+}
+
+/// 保存用户配置的同步目标（内置目标不落盘）
+pub fn save_config(sink_config: &CredentialSinkConfig) -> Result<(), String> {
+    let mut ids = std::collections::HashSet::new();
+    for sink in &sink_config.sinks {
+        if sink.id.trim().is_empty() || !ids.insert(sink.id.as_str()) {
+            return Err(format!("同步目标 ID 为空或重复: {}", sink.id));
+        }
+        if builtin_sinks().iter().any(|builtin| builtin.id == sink.id) {
+            return Err(format!("同步目标 ID 与内置目标冲突: {}", sink.id));
+        }
+        if sink.path.trim().is_empty() {
+            return Err(format!("同步目标 {} 未设置文件路径", sink.name));
+        }
+    }
+
+    let stored = CredentialSinkConfig {
+        sinks: sink_config.sinks.iter().filter(|sink| !sink.builtin).cloned().collect(),
+        disabled_builtins: sink_config.disabled_builtins.clone(),
+    };
+    let content = serde_json::to_string_pretty(&stored).map_err(|e| format!("序列化失败: {}", e))?;
+    let path = config_path()?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("写入同步目标配置失败: {}", e))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("保存同步目标配置失败: {}", e))
+}
+
+fn builtin_sinks() -> Vec<CredentialSink> {
+    vec![opencode_auth::builtin_sink()]
+}
+
+/// 列出全部同步目标（内置 + 用户配置）
+pub fn list_sinks() -> Result<Vec<CredentialSink>, String> {
+    let sink_config = load_config()?;
+    let mut sinks = builtin_sinks();
+    for sink in sinks.iter_mut() {
+        sink.enabled = !sink_config.disabled_builtins.contains(&sink.id);
+    }
+    sinks.extend(sink_config.sinks);
+    Ok(sinks)
+}
+
+fn expand_path(path: &str) -> Result<PathBuf, String> {
+    let path = path.trim();
+    match path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        Some(rest) => Ok(dirs::home_dir().ok_or("无法获取用户主目录")?.join(rest)),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+/// 从 Codex 账号提取模板变量
+fn template_vars(account: &CodexAccount) -> HashMap<&'static str, Value> {
+    let mut vars = HashMap::new();
+    let mut put = |name: &'static str, value: Option<Value>| {
+        if let Some(value) = value {
+            vars.insert(name, value);
+        }
+    };
+    let non_empty = |value: &str| Some(value.to_string()).filter(|v| !v.trim().is_empty()).map(Value::String);
+
+    put("access_token", non_empty(&account.tokens.access_token));
+    put("id_token", non_empty(&account.tokens.id_token));
+    put("refresh_token", account.tokens.refresh_token.as_deref().and_then(non_empty));
+    put("email", non_empty(&account.email));
+    put("user_id", account.user_id.as_deref().and_then(non_empty));
+    put("plan_type", account.plan_type.as_deref().and_then(non_empty));
+    put(
+        "account_id",
+        account
+            .account_id
+            .clone()
+            .or_else(|| codex_account::extract_chatgpt_account_id_from_access_token(&account.tokens.access_token))
+            .as_deref()
+            .and_then(non_empty),
+    );
+    if let Some(exp) = codex_account::decode_jwt_payload(&account.tokens.access_token)
+        .ok()
+        .and_then(|payload| payload.exp)
+    {
+        put("expires_at", Some(Value::from(exp)));
+        put("expires_ms", Some(Value::from(exp * 1000)));
+    }
+    vars
+}
+
+/// 渲染模板；返回 `None` 表示该字段应被删除（可选变量缺失）
+fn render_template(template: &Value, vars: &HashMap<&'static str, Value>) -> Result<Option<Value>, String> {
+    match template {
+        Value::String(text) => {
+            let trimmed = text.trim();
+            if let Some(name) = trimmed.strip_prefix("{{").and_then(|rest| rest.strip_suffix("}}")) {
+                if !name.contains("{{") && !name.contains("}}") {
+                    let (name, optional) = match name.trim().strip_suffix('?') {
+                        Some(name) => (name.trim(), true),
+                        None => (name.trim(), false),
+                    };
+                    return match vars.get(name) {
+                        Some(value) => Ok(Some(value.clone())),
+                        None if optional => Ok(None),
+                        None => Err(format!("模板变量缺失: {}", name)),
+                    };
+                }
+            }
+
+            let mut rendered = String::new();
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                let end = rest[start..]
+                    .find("}}")
+                    .map(|offset| start + offset)
+                    .ok_or_else(|| format!("模板格式错误: {}", text))?;
+                rendered.push_str(&rest[..start]);
+                let name = rest[start + 2..end].trim();
+                let value = vars.get(name).ok_or_else(|| format!("模板变量缺失: {}", name))?;
+                match value {
+                    Value::String(s) => rendered.push_str(s),
+                    other => rendered.push_str(&other.to_string()),
+                }
+                rest = &rest[end + 2..];
+            }
+            rendered.push_str(rest);
+            Ok(Some(Value::String(rendered)))
+        }
+        Value::Array(items) => {
+            let mut rendered = Vec::with_capacity(items.len());
+            for item in items {
+                if let Some(value) = render_template(item, vars)? {
+                    rendered.push(value);
+                }
+            }
+            Ok(Some(Value::Array(rendered)))
+        }
+        Value::Object(map) => {
+            let mut rendered = serde_json::Map::new();
+            for (key, item) in map {
+                if let Some(value) = render_template(item, vars)? {
+                    rendered.insert(key.clone(), value);
+                }
+            }
+            Ok(Some(Value::Object(rendered)))
+        }
+        other => Ok(Some(other.clone())),
+    }
+}
+
+fn parse_document(content: &str, format: SinkFormat) -> Result<Value, String> {
+    if content.trim().is_empty() {
+        return Ok(Value::Object(serde_json::Map::new()));
+    }
+    match format {
+        SinkFormat::Json => serde_json::from_str(content).map_err(|e| format!("解析 JSON 失败: {}", e)),
+        SinkFormat::Toml => {
+            let value: toml::Value = toml::from_str(content).map_err(|e| format!("解析 TOML 失败: {}", e))?;
+            serde_json::to_value(value).map_err(|e| format!("转换 TOML 失败: {}", e))
+        }
+    }
+}
+
+fn serialize_document(document: &Value, format: SinkFormat) -> Result<String, String> {
+    match format {
+        SinkFormat::Json => serde_json::to_string_pretty(document).map_err(|e| format!("序列化 JSON 失败: {}", e)),
+        SinkFormat::Toml => {
+            let value = toml::Value::try_from(document).map_err(|e| format!("转换 TOML 失败: {}", e))?;
+            toml::to_string_pretty(&value).map_err(|e| format!("序列化 TOML 失败: {}", e))
+        }
+    }
+}
+
+/// 将渲染结果写入文档的指定位置
+pub(crate) fn merge_into_document(document: &mut Value, key_path: Option<&str>, rendered: Value) -> Result<(), String> {
+    if !document.is_object() {
+        *document = Value::Object(serde_json::Map::new());
+    }
+
+    let segments: Vec<&str> = key_path
+        .unwrap_or_default()
+        .split('.')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let Some((last, parents)) = segments.split_last() else {
+        let Value::Object(entries) = rendered else {
+            return Err("未设置 key_path 时模板必须是对象".to_string());
+        };
+        let root = document.as_object_mut().expect("document is object");
+        root.extend(entries);
+        return Ok(());
+    };
+
+    let mut current = document;
+    for segment in parents {
+        let map = current.as_object_mut().expect("intermediate value is object");
+        let entry = map
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+        if !entry.is_object() {
+            *entry = Value::Object(serde_json::Map::new());
+        }
+        current = entry;
+    }
+    current
+        .as_object_mut()
+        .expect("parent value is object")
+        .insert(last.to_string(), rendered);
+    Ok(())
+}
+
+/// 渲染单个目标，返回目标路径与新文件内容
+fn render_sink(sink: &CredentialSink, vars: &HashMap<&'static str, Value>) -> Result<(PathBuf, String), String> {
+    let path = expand_path(&sink.path)?;
+    let rendered = render_template(&sink.template, vars)?.ok_or("模板渲染结果为空")?;
+
+    let existing = if path.exists() {
+        fs::read_to_string(&path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?
+    } else {
+        String::new()
+    };
+    let mut document = parse_document(&existing, sink.format)?;
+    merge_into_document(&mut document, sink.key_path.as_deref(), rendered)?;
+    Ok((path.clone(), serialize_document(&document, sink.format)?))
+}
+
+pub(crate) fn run_restart_hook(sink: &CredentialSink) {
+    match &sink.restart {
+        RestartHook::None => {}
+        RestartHook::Opencode => {
+            let user_config = config::get_user_config();
+            if !user_config.opencode_sync_on_switch {
+                logger::log_info("已关闭 OpenCode 自动重启");
+                return;
+            }
+            if process::is_opencode_running() {
+                if let Err(e) = process::close_opencode(20) {
+                    logger::log_warn(&format!("OpenCode 关闭失败: {}", e));
+                }
+            } else {
+                logger::log_info("OpenCode 未在运行，准备启动");
+            }
+            if let Err(e) = process::start_opencode_with_path(Some(&user_config.opencode_app_path)) {
+                logger::log_warn(&format!("OpenCode 启动失败: {}", e));
+            }
+        }
+        RestartHook::Command { program, args } => {
+            match std::process::Command::new(program).args(args).spawn() {
+                Ok(_) => logger::log_info(&format!("[CredentialSink] 已执行 {} 的重启命令: {}", sink.name, program)),
+                Err(e) => logger::log_warn(&format!("[CredentialSink] {} 的重启命令执行失败: {}", sink.name, e)),
+            }
+        }
+    }
+}
+
+/// 将 `value` 写入 JSON 文件的 `key_path` 位置，保留其他条目
+pub(crate) fn merge_json_file(path: &Path, key_path: &str, value: Value) -> Result<(), String> {
+    edit_document_file(path, SinkFormat::Json, |document| {
+        merge_into_document(document, Some(key_path), value)?;
+        Ok(true)
+    })
+}
+
+/// 读取文件并修改文档，`edit` 返回 true 时原子写回（文件不存在时视为空对象）
+pub(crate) fn edit_document_file(
+    path: &Path,
+    format: SinkFormat,
+    edit: impl FnOnce(&mut Value) -> Result<bool, String>,
+) -> Result<(), String> {
+    let existing = if path.exists() {
+        fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?
+    } else {
+        String::new()
+    };
+    let mut document = parse_document(&existing, format)?;
+    if !document.is_object() {
+        document = Value::Object(serde_json::Map::new());
+    }
+    if !edit(&mut document)? {
+        return Ok(());
+    }
+    atomic_write::write_file_atomic(path, serialize_document(&document, format)?.as_bytes())
+}
+
+/// 删除文档中 `key_path` 位置的条目，并移除因此变空的上级对象；返回是否有删除
+pub(crate) fn remove_from_document(document: &mut Value, key_path: &str) -> bool {
+    fn remove(current: &mut Value, segments: &[&str]) -> bool {
+        let Some(map) = current.as_object_mut() else {
+            return false;
+        };
+        match segments {
+            [] => false,
+            [last] => map.remove(*last).is_some(),
+            [first, rest @ ..] => {
+                let Some(child) = map.get_mut(*first) else {
+                    return false;
+                };
+                let removed = remove(child, rest);
+                if removed && child.as_object().is_some_and(|child| child.is_empty()) {
+                    map.remove(*first);
+                }
+                removed
+            }
+        }
+    }
+    let segments: Vec<&str> = key_path.split('.').map(str::trim).filter(|s| !s.is_empty()).collect();
+    remove(document, &segments)
+}
+
+/// 获取已启用的内置目标
+pub(crate) fn enabled_builtin(id: &str) -> Option<CredentialSink> {
+    let disabled = load_config().map(|c| c.disabled_builtins).unwrap_or_default();
+    if disabled.iter().any(|item| item == id) {
+        return None;
+    }
+    builtin_sinks().into_iter().find(|sink| sink.id == id)
+}
+
+/// 将 Codex 账号同步到所有启用的目标，并执行各目标的重启钩子
+pub fn sync_codex_account(account: &CodexAccount) -> Vec<SinkResult> {
+    let sinks = match list_sinks() {
+        Ok(sinks) => sinks,
+        Err(e) => {
+            logger::log_warn(&format!("[CredentialSink] 读取同步目标失败，仅同步内置目标: {}", e));
+            builtin_sinks()
+        }
+    };
+    if codex_oauth::is_token_expired(&account.tokens.access_token) {
+        logger::log_warn("[CredentialSink] Codex access_token 已过期，跳过凭据同步");
+        return sinks
+            .iter()
+            .filter(|sink| sink.enabled)
+            .map(|sink| sink_result(sink, Some("Codex access_token 已过期，未同步".to_string())))
+            .collect();
+    }
+
+    let results = write_sinks(&sinks, &template_vars(account));
+    for sink in sinks.iter().filter(|sink| sink.enabled) {
+        if results.iter().any(|result| result.id == sink.id && result.updated) {
+            run_restart_hook(sink);
+        }
+    }
+    results
+}
+
+fn sink_result(sink: &CredentialSink, error: Option<String>) -> SinkResult {
+    SinkResult {
+        id: sink.id.clone(),
+        name: sink.name.clone(),
+        path: sink.path.clone(),
+        updated: false,
+        error,
+    }
+}
+
+/// 写入所有启用的目标：任一目标无法渲染或写入临时文件时全部放弃
+fn write_sinks(sinks: &[CredentialSink], vars: &HashMap<&'static str, Value>) -> Vec<SinkResult> {
+    let enabled: Vec<&CredentialSink> = sinks.iter().filter(|sink| sink.enabled).collect();
+    let mut results: Vec<SinkResult> = enabled.iter().map(|sink| sink_result(sink, None)).collect();
+
+    // 第一阶段：渲染并写入临时文件
+    let mut staged: Vec<StagedFile> = Vec::new();
+    let mut stage_failed = false;
+    for (sink, result) in enabled.iter().zip(results.iter_mut()) {
+        match render_sink(sink, vars).and_then(|(path, content)| atomic_write::stage(&path, content.as_bytes())) {
+            Ok(file) => staged.push(file),
+            Err(e) => {
+                logger::log_warn(&format!("[CredentialSink] {} 渲染失败: {}", sink.name, e));
+                result.error = Some(e);
+                stage_failed = true;
+            }
+        }
+    }
+    if stage_failed {
+        logger::log_error("[CredentialSink] 存在无法写入的目标，取消本次凭据同步");
+        for file in &staged {
+            file.discard();
+        }
+        for result in results.iter_mut().filter(|result| result.error.is_none()) {
+            result.error = Some("其他目标写入失败，未更新".to_string());
+        }
+        return results;
+    }
+
+    // 第二阶段：依次替换，失败时回滚已替换的文件
+    if let Err((failed, error)) = atomic_write::replace_all(&staged, |_| Ok(())) {
+        logger::log_error(&format!(
+            "[CredentialSink] {} 替换失败，回滚全部目标: {}",
+            enabled[failed].name, error
+        ));
+        for (index, result) in results.iter_mut().enumerate() {
+            result.error = Some(if index == failed {
+                error.clone()
+            } else {
+                "其他目标替换失败，已回滚".to_string()
+            });
+        }
+        return results;
+    }
+
+    for ((sink, result), file) in enabled.iter().zip(results.iter_mut()).zip(&staged) {
+        result.updated = true;
+        logger::log_info(&format!("[CredentialSink] 已更新 {}: {}", sink.name, file.path.display()));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> HashMap<&'static str, Value> {
+        HashMap::from([
+            ("access_token", Value::from("at")),
+            ("refresh_token", Value::from("rt")),
+            ("expires_ms", Value::from(1_000)),
+        ])
+    }
+
+    #[test]
+    fn render_keeps_types_and_drops_optional_fields() {
+        let template = serde_json::json!({
+            "access": "{{access_token}}",
+            "expires": "{{ expires_ms }}",
+            "header": "Bearer {{access_token}}",
+            "accountId": "{{account_id?}}",
+        });
+        let rendered = render_template(&template, &vars()).unwrap().unwrap();
+        assert_eq!(
+            rendered,
+            serde_json::json!({ "access": "at", "expires": 1000, "header": "Bearer at" })
+        );
+        assert!(render_template(&Value::from("{{account_id}}"), &vars()).is_err());
+    }
+
+    #[test]
+    fn merge_preserves_other_entries() {
+        let mut document = parse_document("model = \"o3\"\n[providers.other]\nkey = \"x\"\n", SinkFormat::Toml).unwrap();
+        merge_into_document(&mut document, Some("providers.openai"), serde_json::json!({ "token": "at" })).unwrap();
+        let output = serialize_document(&document, SinkFormat::Toml).unwrap();
+        let reparsed = parse_document(&output, SinkFormat::Toml).unwrap();
+        assert_eq!(reparsed["model"], "o3");
+        assert_eq!(reparsed["providers"]["other"]["key"], "x");
+        assert_eq!(reparsed["providers"]["openai"]["token"], "at");
+
+        let mut root = serde_json::json!({ "keep": 1, "access": "old" });
+        merge_into_document(&mut root, None, serde_json::json!({ "access": "new" })).unwrap();
+        assert_eq!(root, serde_json::json!({ "keep": 1, "access": "new" }));
+    }
+
+    #[test]
+    fn remove_prunes_empty_parents() {
+        let mut document = serde_json::json!({
+            "provider": { "openai": { "options": { "baseURL": "x" } }, "other": { "options": { "baseURL": "y" } } },
+        });
+        assert!(remove_from_document(&mut document, "provider.openai.options.baseURL"));
+        assert_eq!(document, serde_json::json!({ "provider": { "other": { "options": { "baseURL": "y" } } } }));
+        assert!(!remove_from_document(&mut document, "provider.openai.options.baseURL"));
+        assert!(!remove_from_document(&mut document, "provider.other.options.baseURL.deeper"));
+    }
+
+    #[test]
+    fn one_failing_sink_leaves_all_targets_untouched() {
+        let dir = std::env::temp_dir().join(format!("cockpit_sink_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let sink = |id: &str, file: &str| CredentialSink {
+            id: id.to_string(),
+            name: id.to_string(),
+            enabled: true,
+            builtin: false,
+            path: dir.join(file).to_string_lossy().to_string(),
+            format: SinkFormat::Json,
+            key_path: None,
+            template: serde_json::json!({ "access": "{{access_token}}" }),
+            restart: RestartHook::None,
+        };
+        fs::write(dir.join("good.json"), "{\"access\":\"old\"}").unwrap();
+        fs::write(dir.join("broken.json"), "not json").unwrap();
+
+        let results = write_sinks(&[sink("good", "good.json"), sink("broken", "broken.json")], &vars());
+        assert!(results.iter().all(|result| !result.updated && result.error.is_some()));
+        assert_eq!(fs::read_to_string(dir.join("good.json")).unwrap(), "{\"access\":\"old\"}");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2, "临时文件应被清理");
+
+        let results = write_sinks(&[sink("good", "good.json")], &vars());
+        assert!(results[0].updated);
+        let written: Value = serde_json::from_str(&fs::read_to_string(dir.join("good.json")).unwrap()).unwrap();
+        assert_eq!(written["access"], "at");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,128 @@
+//! 上游服务地址
+//!
+//! 优先级：环境变量 > config.json 中的 `endpoints` > 内置默认值。
+//! 便于接入代理；测试中通过 `set_test_base` 指向本地 mock 服务。
+
+use crate::modules::config;
+
+pub const ENV_CLOUD_CODE_BASE_URLS: &str = "COCKPIT_CLOUD_CODE_BASE_URLS";
+pub const ENV_QUOTA_API_URL: &str = "COCKPIT_QUOTA_API_URL";
+pub const ENV_CODEX_USAGE_URL: &str = "COCKPIT_CODEX_USAGE_URL";
+pub const ENV_GITHUB_API_BASE: &str = "COCKPIT_GITHUB_API_BASE";
+
+const DEFAULT_CLOUD_CODE_BASE_URLS: [&str; 3] = [
+    "https://daily-cloudcode-pa.googleapis.com",
+    "https://cloudcode-pa.googleapis.com",
+    "https://daily-cloudcode-pa.sandbox.googleapis.com",
+];
+const DEFAULT_QUOTA_API_URL: &str = "https://cloudcode-pa.googleapis.com/v1internal:fetchAvailableModels";
+// 使用 wham/usage 端点（Quotio 使用的）
+const DEFAULT_CODEX_USAGE_URL: &str = "https://chatgpt.com/backend-api/wham/usage";
+const DEFAULT_GITHUB_API_BASE: &str = "https://api.github.com";
+
+/// 测试中使用的 mock 服务地址
+#[cfg(test)]
+static TEST_BASE: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+/// 测试中将所有上游地址指向 mock 服务（优先于环境变量与配置）
+#[cfg(test)]
+pub(crate) fn set_test_base(base: &str) {
+    let _ = TEST_BASE.set(normalize_url(base));
+}
+
+fn test_url(_path: &str) -> Option<String> {
+    #[cfg(test)]
+    {
+        TEST_BASE.get().map(|base| format!("{}{}", base, _path))
+    }
+    #[cfg(not(test))]
+    {
+        None
+    }
+}
+
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn normalize_url(value: &str) -> String {
+    value.trim().trim_end_matches('/').to_string()
+}
+
+fn resolve(env_name: &str, configured: Option<String>, default: &str) -> String {
+    env_value(env_name)
+        .or(configured.filter(|value| !value.trim().is_empty()))
+        .map(|value| normalize_url(&value))
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Cloud Code 基础地址列表（按尝试顺序）
+pub fn cloud_code_base_urls() -> Vec<String> {
+    if let Some(url) = test_url("/cloudcode") {
+        return vec![url];
+    }
+    let from_env: Vec<String> = env_value(ENV_CLOUD_CODE_BASE_URLS)
+        .map(|value| {
+            value
+                .split(',')
+                .map(normalize_url)
+                .filter(|url| !url.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    if !from_env.is_empty() {
+        return from_env;
+    }
+
+    let configured: Vec<String> = config::get_user_config()
+        .endpoints
+        .cloud_code_base_urls
+        .iter()
+        .map(|url| normalize_url(url))
+        .filter(|url| !url.is_empty())
+        .collect();
+    if !configured.is_empty() {
+        return configured;
+    }
+
+    DEFAULT_CLOUD_CODE_BASE_URLS.iter().map(|url| url.to_string()).collect()
+}
+
+/// Antigravity 配额查询地址
+pub fn quota_api_url() -> String {
+    if let Some(url) = test_url("/cloudcode/v1internal:fetchAvailableModels") {
+        return url;
+    }
+    resolve(
+        ENV_QUOTA_API_URL,
+        config::get_user_config().endpoints.quota_api_url,
+        DEFAULT_QUOTA_API_URL,
+    )
+}
+
+/// Codex 使用率查询地址
+pub fn codex_usage_url() -> String {
+    if let Some(url) = test_url("/backend-api/wham/usage") {
+        return url;
+    }
+    resolve(
+        ENV_CODEX_USAGE_URL,
+        config::get_user_config().endpoints.codex_usage_url,
+        DEFAULT_CODEX_USAGE_URL,
+    )
+}
+
+/// GitHub API 基础地址（Copilot 用户信息与配额）
+pub fn github_api_base() -> String {
+    if let Some(url) = test_url("/github") {
+        return url;
+    }
+    resolve(
+        ENV_GITHUB_API_BASE,
+        config::get_user_config().endpoints.github_api_base,
+        DEFAULT_GITHUB_API_BASE,
+    )
+}
@@ -101,7 +101,9 @@ pub fn save_fingerprint_store(store: &FingerprintStore) -> Result<(), String> {
     let content = serde_json::to_string_pretty(store)
         .map_err(|e| format!("序列化指纹存储失败: {}", e))?;
     fs::write(&path, content)
-        .map_err(|e| format!("保存指纹存储失败: {}", e))
+        .map_err(|e| format!("保存指纹存储失败: {}", e))?;
+    crate::modules::account_sync::record_fingerprints_saved(&store.fingerprints);
+    Ok(())
 }
 
 /// 获取指纹详情
@@ -221,6 +223,7 @@ pub fn delete_fingerprint(fingerprint_id: &str) -> Result<(), String> {
     }
     
     save_fingerprint_store(&store)?;
+    crate::modules::account_sync::record_fingerprint_deleted(fingerprint_id);
     
     // 更新所有绑定此指纹的账号，改为绑定原始指纹
     update_accounts_fingerprint(fingerprint_id, "original")?;
@@ -242,6 +242,7 @@ pub fn save_group_settings(settings: &GroupSettings) -> Result<(), String> {
         .map_err(|e| format!("序列化失败: {}", e))?;
     
     fs::write(&path, content).map_err(|e| format!("写入文件失败: {}", e))?;
+    crate::modules::account_sync::record_group_settings_saved(settings);
     
     crate::modules::logger::log_info(&format!(
         "[GroupSettings] 保存配置成功: {} 个映射, {} 个分组",
@@ -0,0 +1,412 @@
+//! 账号健康检查
+//!
+//! 对所有 Antigravity / Codex / Copilot 账号依次检查三项：
+//! - `token`：刷新令牌是否可用（成功刷新的 Token 会保存）
+//! - `identity`：身份信息（Google userinfo / id_token 解码 / GitHub /user）是否与账号一致
+//! - `quota`：配额接口是否可访问
+//!
+//! 每项返回状态与耗时，账号的总体状态取各项中最严重的一项。可手动执行，也可按
+//! `health_check_interval_minutes` 定时执行，最近一次结果保存在内存中。
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt};
+use reqwest::StatusCode;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::codex::CodexAccount;
+use crate::models::copilot::CopilotAccount;
+use crate::error::AppError;
+use crate::models::Account;
+use crate::modules::websocket::AccountProvider;
+use crate::modules::{
+    account, codex_account, codex_oauth, codex_quota, config, copilot_account, copilot_quota, logger, oauth, quota,
+    token_refresher,
+};
+use crate::utils::http::HttpError;
+
+/// 同时检查的账号数
+const HEALTH_CHECK_CONCURRENCY: usize = 4;
+/// 定时检查的轮询间隔
+const SCHEDULE_POLL_SECS: u64 = 60;
+
+static STARTED: OnceLock<Mutex<bool>> = OnceLock::new();
+static RUN_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+static LAST_REPORT: Mutex<Option<HealthReport>> = Mutex::new(None);
+
+/// 检查状态（按严重程度递增排列）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    NetworkError,
+    Error,
+    Forbidden,
+    Expired,
+    Revoked,
+}
+
+/// 单项检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheckStep {
+    pub status: HealthStatus,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 单个账号的检查结果（前一项失败时后续项为空）
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountHealth {
+    pub provider: AccountProvider,
+    pub account_id: String,
+    pub email: String,
+    pub disabled: bool,
+    pub status: HealthStatus,
+    pub token: Option<HealthCheckStep>,
+    pub identity: Option<HealthCheckStep>,
+    pub quota: Option<HealthCheckStep>,
+}
+
+/// 健康检查报告
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub checked_at: i64,
+    pub duration_ms: u64,
+    /// 各状态的账号数
+    pub summary: BTreeMap<HealthStatus, usize>,
+    pub accounts: Vec<AccountHealth>,
+}
+
+/// 根据状态码与错误码判断状态
+fn classify_error(error: &HttpError) -> HealthStatus {
+    if token_refresher::is_revoked_error(error) {
+        return HealthStatus::Revoked;
+    }
+    match error.status {
+        Some(StatusCode::FORBIDDEN) => HealthStatus::Forbidden,
+        Some(StatusCode::UNAUTHORIZED) => HealthStatus::Expired,
+        None if error.network => HealthStatus::NetworkError,
+        _ => HealthStatus::Error,
+    }
+}
+
+/// 将 `reqwest` 错误转为带状态码的错误
+fn from_reqwest(error: &reqwest::Error) -> HttpError {
+    match error.status() {
+        Some(status) => HttpError { status: Some(status), message: error.to_string(), ..HttpError::default() },
+        None if error.is_connect() || error.is_timeout() || error.is_request() => HttpError::network(error.to_string()),
+        None => HttpError::from(error.to_string()),
+    }
+}
+
+/// 执行一项检查并计时
+async fn timed<T, E, F>(future: F) -> (Result<T, E>, u64)
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = future.await;
+    (result, started.elapsed().as_millis() as u64)
+}
+
+fn step_ok(duration_ms: u64, message: Option<String>) -> HealthCheckStep {
+    HealthCheckStep { status: HealthStatus::Ok, duration_ms, message }
+}
+
+fn step_err(duration_ms: u64, error: HttpError) -> HealthCheckStep {
+    HealthCheckStep { status: classify_error(&error), duration_ms, message: Some(error.message) }
+}
+
+fn finish(mut health: AccountHealth) -> AccountHealth {
+    health.status = [&health.token, &health.identity, &health.quota]
+        .into_iter()
+        .flatten()
+        .map(|step| step.status)
+        .max()
+        .unwrap_or(HealthStatus::Ok);
+    health
+}
+
+fn new_health(provider: AccountProvider, account_id: &str, email: &str, disabled: bool) -> AccountHealth {
+    AccountHealth {
+        provider,
+        account_id: account_id.to_string(),
+        email: email.to_string(),
+        disabled,
+        status: HealthStatus::Ok,
+        token: None,
+        identity: None,
+        quota: None,
+    }
+}
+
+async fn check_antigravity(acc: Account) -> AccountHealth {
+    let mut health = new_health(AccountProvider::Antigravity, &acc.id, &acc.email, acc.disabled);
+
+    let (token, duration) = timed(token_refresher::refresh_antigravity_account(&acc)).await;
+    let token = match token {
+        Ok(token) => {
+            health.token = Some(step_ok(duration, None));
+            token
+        }
+        Err(e) => {
+            health.token = Some(step_err(duration, e));
+            return finish(health);
+        }
+    };
+
+    let (identity, duration) = timed(oauth::request_user_info(&token.access_token)).await;
+    health.identity = Some(match identity {
+        Ok(info) if info.email.eq_ignore_ascii_case(&acc.email) => step_ok(duration, None),
+        Ok(info) => HealthCheckStep {
+            status: HealthStatus::Error,
+            duration_ms: duration,
+            message: Some(format!("身份不一致: {}", info.email)),
+        },
+        Err(e) => step_err(duration, e),
+    });
+
+    let (quota, duration) = timed(quota::fetch_quota(&token.access_token, &acc.email, true)).await;
+    health.quota = Some(match quota {
+        Ok(result) => match result.error {
+            Some(err) => step_err(
+                duration,
+                HttpError {
+                    status: err.code.and_then(|code| StatusCode::from_u16(code).ok()),
+                    message: err.message,
+                    ..HttpError::default()
+                },
+            ),
+            None => step_ok(duration, None),
+        },
+        Err(AppError::Network(e)) => step_err(duration, from_reqwest(&e)),
+        Err(e) => step_err(duration, e.to_string().into()),
+    });
+
+    finish(health)
+}
+
+/// `auth_dir` 为账号已写入的 auth.json 所在目录
+async fn check_codex(mut acc: CodexAccount, auth_dir: Option<PathBuf>) -> AccountHealth {
+    let mut health = new_health(AccountProvider::Codex, &acc.id, &acc.email, acc.disabled);
+
+    let expired = codex_oauth::is_token_expired(&acc.tokens.access_token);
+    match acc.tokens.refresh_token.clone() {
+        // 已写入 auth.json 的 Token 由 Codex 自身维护，未过期时不刷新，避免令牌轮换导致 auth.json 失效
+        _ if auth_dir.is_some() && !expired => {
+            health.token = Some(step_ok(0, Some("已写入 auth.json，未刷新".to_string())));
+        }
+        Some(refresh_token) => {
+            let (tokens, duration) = timed(token_refresher::refresh_codex_account(&acc.id, &refresh_token)).await;
+            match tokens {
+                Ok(tokens) => {
+                    acc.tokens = tokens;
+                    // 刷新后旧令牌失效，需同步写回 auth.json
+                    if let Some(dir) = &auth_dir {
+                        if let Err(e) = codex_account::write_auth_file_to_dir(dir, &acc) {
+                            health.token =
+                                Some(step_err(duration, format!("Token 已刷新，但写回 auth.json 失败: {}", e).into()));
+                            return finish(health);
+                        }
+                    }
+                    health.token = Some(step_ok(duration, None));
+                }
+                Err(e) => {
+                    health.token = Some(step_err(duration, e));
+                    return finish(health);
+                }
+            }
+        }
+        None if expired => {
+            health.token = Some(HealthCheckStep {
+                status: HealthStatus::Expired,
+                duration_ms: 0,
+                message: Some("Token 已过期且无 refresh_token".to_string()),
+            });
+            return finish(health);
+        }
+        None => {
+            health.token = Some(step_ok(0, Some("无 refresh_token".to_string())));
+        }
+    }
+
+    let started = Instant::now();
+    let identity = codex_account::extract_user_info(&acc.tokens.id_token);
+    let duration = started.elapsed().as_millis() as u64;
+    health.identity = Some(match identity {
+        Ok((email, ..)) if email.eq_ignore_ascii_case(&acc.email) => step_ok(duration, None),
+        Ok((email, ..)) => HealthCheckStep {
+            status: HealthStatus::Error,
+            duration_ms: duration,
+            message: Some(format!("身份不一致: {}", email)),
+        },
+        Err(e) => step_err(duration, e.into()),
+    });
+
+    let (quota, duration) = timed(codex_quota::request_quota(&acc)).await;
+    health.quota = Some(match quota {
+        Ok(_) => step_ok(duration, None),
+        Err(e) => step_err(duration, e),
+    });
+
+    finish(health)
+}
+
+async fn check_copilot(acc: CopilotAccount) -> AccountHealth {
+    let email = acc.email.clone().unwrap_or_else(|| acc.username.clone());
+    let mut health = new_health(AccountProvider::Copilot, &acc.id, &email, acc.disabled);
+
+    // GitHub Token 不会过期，/user 同时用于校验 Token 与身份
+    let (user, duration) = timed(copilot_account::request_github_user(&acc.token)).await;
+    match user {
+        Ok((login, _)) => {
+            health.token = Some(step_ok(duration, None));
+            health.identity = Some(if login.eq_ignore_ascii_case(&acc.username) {
+                step_ok(0, None)
+            } else {
+                HealthCheckStep {
+                    status: HealthStatus::Error,
+                    duration_ms: 0,
+                    message: Some(format!("身份不一致: {}", login)),
+                }
+            });
+        }
+        Err(e) => {
+            let mut step = step_err(duration, e);
+            // GitHub Token 不会过期，401 即已被撤销
+            if step.status == HealthStatus::Expired {
+                step.status = HealthStatus::Revoked;
+            }
+            health.token = Some(step);
+            return finish(health);
+        }
+    }
+
+    let (quota, duration) = timed(copilot_quota::request_quota(&acc.token, acc.monthly_included_requests)).await;
+    health.quota = Some(match quota {
+        Ok(_) => step_ok(duration, None),
+        Err(e) => step_err(duration, e),
+    });
+
+    finish(health)
+}
+
+/// 检查所有平台的账号
+pub async fn run_health_check(app: Option<&AppHandle>) -> Result<HealthReport, String> {
+    let _guard = RUN_LOCK.lock().await;
+    let started = Instant::now();
+
+    let mut checks: Vec<BoxFuture<'static, AccountHealth>> = Vec::new();
+    for acc in account::list_accounts()? {
+        checks.push(check_antigravity(acc).boxed());
+    }
+    let mut codex_auth_dirs = token_refresher::codex_auth_dirs();
+    for acc in codex_account::list_accounts() {
+        let auth_dir = codex_auth_dirs.remove(&acc.id);
+        checks.push(check_codex(acc, auth_dir).boxed());
+    }
+    for acc in copilot_account::list_accounts() {
+        checks.push(check_copilot(acc).boxed());
+    }
+
+    let mut accounts: Vec<AccountHealth> = futures::stream::iter(checks)
+        .buffer_unordered(HEALTH_CHECK_CONCURRENCY)
+        .collect()
+        .await;
+    accounts.sort_by(|a, b| {
+        (a.provider as u8, a.email.to_lowercase()).cmp(&(b.provider as u8, b.email.to_lowercase()))
+    });
+
+    let mut summary = BTreeMap::new();
+    for health in &accounts {
+        *summary.entry(health.status).or_insert(0) += 1;
+    }
+
+    let report = HealthReport {
+        checked_at: chrono::Utc::now().timestamp(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        summary,
+        accounts,
+    };
+
+    logger::log_info(&format!(
+        "[HealthCheck] 检查完成: {} 个账号, 耗时 {}ms, 结果 {:?}",
+        report.accounts.len(),
+        report.duration_ms,
+        report.summary
+    ));
+
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some(report.clone());
+    }
+    if let Some(app) = app {
+        let _ = app.emit("health:report", &report);
+    }
+    Ok(report)
+}
+
+/// 获取最近一次检查结果
+pub fn get_last_report() -> Option<HealthReport> {
+    LAST_REPORT.lock().ok().and_then(|last| last.clone())
+}
+
+/// 启动定时检查（间隔由 `health_check_interval_minutes` 控制，重复调用无副作用）
+pub fn ensure_started(app: AppHandle) {
+    let mut started = STARTED
+        .get_or_init(|| Mutex::new(false))
+        .lock()
+        .expect("health check started lock");
+    if *started {
+        return;
+    }
+    *started = true;
+
+    tauri::async_runtime::spawn(async move {
+        let mut last_run: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(Duration::from_secs(SCHEDULE_POLL_SECS)).await;
+            let interval = config::get_user_config().health_check_interval_minutes;
+            if interval <= 0 {
+                continue;
+            }
+            let due = last_run.is_none_or(|last| last.elapsed() >= Duration::from_secs(interval as u64 * 60));
+            if !due {
+                continue;
+            }
+            last_run = Some(Instant::now());
+            if let Err(e) = run_health_check(Some(&app)).await {
+                logger::log_warn(&format!("[HealthCheck] 定时检查失败: {}", e));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_by_status_and_error_code() {
+        let response = |status: StatusCode, body: &str| HttpError::response(status, body, "请求失败".to_string());
+        assert_eq!(
+            classify_error(&response(StatusCode::BAD_REQUEST, r#"{"error": "invalid_grant"}"#)),
+            HealthStatus::Revoked
+        );
+        assert_eq!(classify_error(&response(StatusCode::UNAUTHORIZED, "Bad credentials")), HealthStatus::Expired);
+        assert_eq!(classify_error(&response(StatusCode::FORBIDDEN, "")), HealthStatus::Forbidden);
+        assert_eq!(classify_error(&response(StatusCode::BAD_GATEWAY, "")), HealthStatus::Error);
+        assert_eq!(
+            classify_error(&HttpError::network("error sending request".to_string())),
+            HealthStatus::NetworkError
+        );
+        // 不再按文字判断：消息中的 401 / 过期等字样不影响状态
+        assert_eq!(classify_error(&HttpError::from("API 返回错误 401，Token 已过期")), HealthStatus::Error);
+    }
+}
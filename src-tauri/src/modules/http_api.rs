@@ -0,0 +1,296 @@
+//! 本地 HTTP API
+//!
+//! 为无法保持 WebSocket 长连接的工具（脚本、启动器等）提供与 WebSocket 相同的操作，
+//! 鉴权方式一致：`Authorization: Bearer <token>` 或 `?token=`，令牌及端口见 server.json。
+//!
+//! 接口（均返回 `{"ok": bool, "data"?: ..., "error"?: string}`，`provider` 默认为 antigravity）：
+//! - `GET  /api/v1/ping`
+//! - `GET  /api/v1/accounts?provider=codex`
+//! - `GET  /api/v1/accounts/current?provider=codex`
+//! - `GET  /api/v1/accounts/tokens`（需要 tokens 权限）
+//! - `GET  /api/v1/accounts/{id}/quota?provider=codex`
+//! - `POST /api/v1/accounts/{id}/quota/refresh?provider=codex`
+//! - `POST /api/v1/accounts/{id}/switch?provider=codex`（需要 write 权限）
+//!
+//! 参数无效返回 400，账号不存在返回 404，其余处理失败返回 500。
+
+use std::collections::HashSet;
+
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::modules::logger;
+use crate::modules::websocket::{self, AccountProvider, WsScope};
+
+const API_PREFIX: &str = "/api/v1";
+
+/// 处理失败时的 HTTP 状态码与错误信息
+type ApiError = (u16, String);
+
+fn internal_error(message: String) -> ApiError {
+    (500, message)
+}
+
+/// 在 [start_port, end_port) 范围内绑定 HTTP API 并在后台线程处理请求
+///
+/// 调用方应传入与 WebSocket 不重叠的端口范围，避免两者争抢端口。
+pub fn start_server(start_port: u16, end_port: u16) {
+    let mut bound = None;
+    for port in start_port..end_port {
+        if let Ok(server) = Server::http(format!("127.0.0.1:{}", port)) {
+            bound = Some((server, port));
+            break;
+        }
+    }
+
+    let Some((server, port)) = bound else {
+        logger::log_error(&format!(
+            "[HTTP] 无法绑定端口 ({}-{})，本地 HTTP API 未启动",
+            start_port,
+            end_port.saturating_sub(1)
+        ));
+        return;
+    };
+
+    if let Err(e) = crate::modules::config::set_http_api_port(port) {
+        logger::log_error(&format!("[HTTP] 保存服务状态失败: {}", e));
+    }
+    logger::log_info(&format!("[HTTP] 本地 HTTP API 已启动: http://127.0.0.1:{}{}", port, API_PREFIX));
+
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            tauri::async_runtime::spawn(handle_request(request));
+        }
+    });
+}
+
+/// 路由结果
+enum Route {
+    Ping,
+    Accounts,
+    CurrentAccount,
+    AccountsWithTokens,
+    Quota { account_id: String, refresh: bool },
+    Switch { account_id: String },
+}
+
+impl Route {
+    fn parse(method: &Method, path: &str) -> Option<Self> {
+        let rest = path.strip_prefix(API_PREFIX)?.trim_end_matches('/');
+        let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+        let account_id = |raw: &str| {
+            urlencoding::decode(raw)
+                .map(|id| id.into_owned())
+                .unwrap_or_else(|_| raw.to_string())
+        };
+
+        match (method, segments.as_slice()) {
+            (Method::Get, ["ping"]) => Some(Route::Ping),
+            (Method::Get, ["accounts"]) => Some(Route::Accounts),
+            (Method::Get, ["accounts", "current"]) => Some(Route::CurrentAccount),
+            (Method::Get, ["accounts", "tokens"]) => Some(Route::AccountsWithTokens),
+            (Method::Get, ["accounts", id, "quota"]) => Some(Route::Quota {
+                account_id: account_id(id),
+                refresh: false,
+            }),
+            (Method::Post, ["accounts", id, "quota", "refresh"]) => Some(Route::Quota {
+                account_id: account_id(id),
+                refresh: true,
+            }),
+            (Method::Post, ["accounts", id, "switch"]) => Some(Route::Switch {
+                account_id: account_id(id),
+            }),
+            _ => None,
+        }
+    }
+
+    /// 与 WebSocket 消息保持一致的权限要求
+    fn required_scope(&self) -> Option<WsScope> {
+        match self {
+            Route::Ping => None,
+            Route::Accounts | Route::CurrentAccount | Route::Quota { .. } => Some(WsScope::Read),
+            Route::AccountsWithTokens => Some(WsScope::Tokens),
+            Route::Switch { .. } => Some(WsScope::Write),
+        }
+    }
+}
+
+fn header_value<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+fn parse_provider(query: Option<&str>) -> Result<AccountProvider, String> {
+    match websocket::query_value(query, "provider") {
+        Some(value) => serde_json::from_value(serde_json::Value::String(value.clone()))
+            .map_err(|_| format!("不支持的平台: {}", value)),
+        None => Ok(AccountProvider::Antigravity),
+    }
+}
+
+fn respond(request: Request, status: u16, body: serde_json::Value) {
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(
+            Header::from_bytes(&b"Content-Type"[..], &b"application/json; charset=utf-8"[..])
+                .expect("content type header"),
+        );
+    let _ = request.respond(response);
+}
+
+fn respond_error(request: Request, status: u16, error: &str) {
+    respond(request, status, json!({ "ok": false, "error": error }));
+}
+
+async fn handle_request(request: Request) {
+    let url = request.url().to_string();
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (url.clone(), None),
+    };
+    let query = query.as_deref();
+
+    let scopes: HashSet<WsScope> = match websocket::authorize_client(
+        header_value(&request, "Origin"),
+        websocket::query_value(query, "token"),
+        header_value(&request, "Authorization"),
+        websocket::query_value(query, "scopes"),
+    ) {
+        Ok(scopes) => scopes,
+        Err((status, reason)) => {
+            logger::log_warn(&format!("[HTTP] 拒绝请求 {} {}: {}", request.method(), path, reason));
+            respond_error(request, status, reason);
+            return;
+        }
+    };
+
+    let Some(route) = Route::parse(request.method(), &path) else {
+        respond_error(request, 404, "not found");
+        return;
+    };
+
+    if let Some(scope) = route.required_scope() {
+        if !scopes.contains(&scope) {
+            let error = format!("权限不足: 需要 {} 权限", scope.as_str());
+            respond_error(request, 403, &error);
+            return;
+        }
+    }
+
+    let provider = match parse_provider(query) {
+        Ok(provider) => provider,
+        Err(e) => {
+            respond_error(request, 400, &e);
+            return;
+        }
+    };
+
+    let result = dispatch(route, provider).await;
+    match result {
+        Ok(data) => respond(request, 200, json!({ "ok": true, "data": data })),
+        Err((status, e)) => respond_error(request, status, &e),
+    }
+}
+
+/// 确认账号存在，不存在时返回 404
+fn ensure_account_exists(provider: AccountProvider, account_id: &str) -> Result<(), ApiError> {
+    if account_id.trim().is_empty() {
+        return Err((400, "缺少账号 ID".to_string()));
+    }
+    let (accounts, _) = websocket::get_provider_accounts_info(provider).map_err(internal_error)?;
+    if accounts.iter().any(|account| account.id == account_id) {
+        Ok(())
+    } else {
+        Err((404, format!("账号不存在: {}", account_id)))
+    }
+}
+
+async fn dispatch(route: Route, provider: AccountProvider) -> Result<serde_json::Value, ApiError> {
+    let to_value = |value: serde_json::Result<serde_json::Value>| {
+        value.map_err(|e| internal_error(format!("序列化响应失败: {}", e)))
+    };
+
+    match route {
+        Route::Ping => Ok(json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "protocol_version": websocket::PROTOCOL_VERSION,
+        })),
+        Route::Accounts => {
+            let (accounts, current_account_id) =
+                websocket::get_provider_accounts_info(provider).map_err(internal_error)?;
+            Ok(json!({
+                "provider": provider,
+                "accounts": to_value(serde_json::to_value(accounts))?,
+                "current_account_id": current_account_id,
+            }))
+        }
+        Route::CurrentAccount => {
+            let (accounts, _) = websocket::get_provider_accounts_info(provider).map_err(internal_error)?;
+            let current = accounts.into_iter().find(|account| account.is_current);
+            to_value(serde_json::to_value(current))
+        }
+        Route::AccountsWithTokens => {
+            if provider != AccountProvider::Antigravity {
+                return Err((400, "仅支持 Antigravity 账号".to_string()));
+            }
+            let (accounts, current_account_id) = websocket::get_accounts_with_tokens_info().map_err(internal_error)?;
+            Ok(json!({
+                "accounts": to_value(serde_json::to_value(accounts))?,
+                "current_account_id": current_account_id,
+            }))
+        }
+        Route::Quota { account_id, refresh } => {
+            ensure_account_exists(provider, &account_id)?;
+            let quota = websocket::get_provider_quota(provider, &account_id, refresh)
+                .await
+                .map_err(internal_error)?;
+            to_value(serde_json::to_value(quota))
+        }
+        Route::Switch { account_id } => {
+            logger::log_info(&format!("[HTTP] 收到 {:?} 切换请求", provider));
+            ensure_account_exists(provider, &account_id)?;
+            let email = websocket::switch_provider_account(provider, &account_id)
+                .await
+                .map_err(internal_error)?;
+            Ok(json!({ "account_id": account_id, "email": email }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_parse() {
+        assert!(matches!(Route::parse(&Method::Get, "/api/v1/accounts"), Some(Route::Accounts)));
+        assert!(matches!(
+            Route::parse(&Method::Get, "/api/v1/accounts/current/"),
+            Some(Route::CurrentAccount)
+        ));
+        match Route::parse(&Method::Post, "/api/v1/accounts/a%2Fb/quota/refresh") {
+            Some(Route::Quota { account_id, refresh }) => {
+                assert_eq!(account_id, "a/b");
+                assert!(refresh);
+            }
+            _ => panic!("quota route"),
+        }
+        assert!(matches!(
+            Route::parse(&Method::Post, "/api/v1/accounts/x/switch"),
+            Some(Route::Switch { .. })
+        ));
+        // 切换必须使用 POST
+        assert!(Route::parse(&Method::Get, "/api/v1/accounts/x/switch").is_none());
+        assert!(Route::parse(&Method::Get, "/other").is_none());
+    }
+
+    #[test]
+    fn provider_defaults_to_antigravity() {
+        assert_eq!(parse_provider(None).unwrap(), AccountProvider::Antigravity);
+        assert_eq!(parse_provider(Some("provider=codex")).unwrap(), AccountProvider::Codex);
+        assert!(parse_provider(Some("provider=unknown")).is_err());
+    }
+}
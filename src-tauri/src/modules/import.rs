@@ -1,5 +1,5 @@
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use uuid::Uuid;
 use crate::models;
@@ -707,3 +707,474 @@ pub async fn import_from_extension_credentials() -> Result<usize, String> {
 
     Ok(imported_count)
 }
+
+// ==================== 表格/文本批量导入 ====================
+
+/// 表格导入格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabularFormat {
+    Csv,
+    Tsv,
+    /// 每行 `email:refresh_token`
+    Lines,
+}
+
+/// 表格导入选项
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TabularImportOptions {
+    /// 格式，缺省时根据内容自动识别
+    #[serde(default)]
+    pub format: Option<TabularFormat>,
+    /// 首行是否为表头，缺省时自动识别
+    #[serde(default)]
+    pub has_header: Option<bool>,
+    /// 邮箱列：表头名称或从 0 开始的列号
+    #[serde(default)]
+    pub email_column: Option<String>,
+    /// refresh_token 列：表头名称或从 0 开始的列号
+    #[serde(default)]
+    pub refresh_token_column: Option<String>,
+    /// 名称列（可选）
+    #[serde(default)]
+    pub name_column: Option<String>,
+    /// 是否先通过 refresh_token 换取 access_token 以校验有效性
+    #[serde(default)]
+    pub validate: bool,
+    /// 应用到所有导入账号的标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 单行导入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportRowResult {
+    /// 原始内容中的行号（从 1 开始）
+    pub row: usize,
+    pub email: Option<String>,
+    /// imported / updated / invalid / duplicate / failed
+    pub status: String,
+    pub message: Option<String>,
+    pub account_id: Option<String>,
+}
+
+/// 表格导入报告
+#[derive(Debug, Clone, Serialize)]
+pub struct TabularImportReport {
+    pub format: String,
+    pub total: usize,
+    pub imported: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportRowResult>,
+}
+
+/// 解析后的待导入行
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedRow {
+    row: usize,
+    email: String,
+    refresh_token: String,
+    name: Option<String>,
+}
+
+/// 解析带引号的分隔文本（RFC 4180），返回 (行号, 字段)
+fn parse_delimited(content: &str, delimiter: char) -> Vec<(usize, Vec<String>)> {
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut row_start = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|f| !f.trim().is_empty()) {
+                    rows.push((row_start, std::mem::take(&mut fields)));
+                } else {
+                    fields.clear();
+                }
+                line += 1;
+                row_start = line;
+            }
+            _ if c == delimiter => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    if fields.iter().any(|f| !f.trim().is_empty()) {
+        rows.push((row_start, fields));
+    }
+    rows
+}
+
+fn detect_format(content: &str) -> TabularFormat {
+    let first = content.lines().find(|l| !l.trim().is_empty()).unwrap_or_default();
+    if first.contains('\t') {
+        TabularFormat::Tsv
+    } else if first.contains(',') {
+        TabularFormat::Csv
+    } else {
+        TabularFormat::Lines
+    }
+}
+
+fn normalize_header(value: &str) -> String {
+    value.trim().trim_start_matches('\u{feff}').to_lowercase().replace([' ', '-'], "_")
+}
+
+/// 解析列映射：支持列号或表头名称；未指定时按候选表头名自动匹配
+fn resolve_column(
+    mapping: Option<&str>,
+    header: Option<&[String]>,
+    candidates: &[&str],
+    label: &str,
+) -> Result<Option<usize>, String> {
+    if let Some(mapping) = mapping.map(str::trim).filter(|m| !m.is_empty()) {
+        if let Ok(index) = mapping.parse::<usize>() {
+            return Ok(Some(index));
+        }
+        let wanted = normalize_header(mapping);
+        return header
+            .and_then(|cols| cols.iter().position(|c| normalize_header(c) == wanted))
+            .map(Some)
+            .ok_or_else(|| format!("未找到{}列: {}", label, mapping));
+    }
+    Ok(header.and_then(|cols| {
+        cols.iter()
+            .position(|c| candidates.contains(&normalize_header(c).as_str()))
+    }))
+}
+
+fn parse_tabular_rows(
+    content: &str,
+    format: TabularFormat,
+    options: &TabularImportOptions,
+) -> Result<Vec<Result<ParsedRow, ImportRowResult>>, String> {
+    if format == TabularFormat::Lines {
+        return Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| {
+                let row = index + 1;
+                match line.trim().split_once(':') {
+                    Some((email, token)) => Ok(ParsedRow {
+                        row,
+                        email: email.trim().to_string(),
+                        refresh_token: token.trim().to_string(),
+                        name: None,
+                    }),
+                    None => Err(invalid_row(row, None, "格式应为 email:refresh_token")),
+                }
+            })
+            .collect());
+    }
+
+    let delimiter = if format == TabularFormat::Tsv { '\t' } else { ',' };
+    let mut rows = parse_delimited(content, delimiter);
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let has_header = options.has_header.unwrap_or_else(|| {
+        !rows[0].1.iter().any(|field| field.contains('@'))
+    });
+    let header = if has_header { Some(rows.remove(0).1) } else { None };
+    let header = header.as_deref();
+
+    let email_col = resolve_column(
+        options.email_column.as_deref(),
+        header,
+        &["email", "mail", "e_mail", "account", "邮箱"],
+        "邮箱",
+    )?
+    .unwrap_or(0);
+    let token_col = resolve_column(
+        options.refresh_token_column.as_deref(),
+        header,
+        &["refresh_token", "refreshtoken", "refresh", "token"],
+        "refresh_token",
+    )?
+    .unwrap_or(1);
+    let name_col = resolve_column(options.name_column.as_deref(), header, &["name", "名称"], "名称")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(row, fields)| {
+            let get = |col: usize| fields.get(col).map(|v| v.trim().to_string()).unwrap_or_default();
+            let email = get(email_col);
+            let refresh_token = get(token_col);
+            if refresh_token.is_empty() && fields.len() <= token_col {
+                return Err(invalid_row(row, Some(email), "缺少 refresh_token 列"));
+            }
+            Ok(ParsedRow {
+                row,
+                email,
+                refresh_token,
+                name: name_col.map(get).filter(|name| !name.is_empty()),
+            })
+        })
+        .collect())
+}
+
+fn invalid_row(row: usize, email: Option<String>, message: &str) -> ImportRowResult {
+    ImportRowResult {
+        row,
+        email: email.filter(|e| !e.is_empty()),
+        status: "invalid".to_string(),
+        message: Some(message.to_string()),
+        account_id: None,
+    }
+}
+
+fn validate_row(row: &ParsedRow) -> Result<(), String> {
+    let email = row.email.as_str();
+    let valid_email = email
+        .split_once('@')
+        .map(|(local, domain)| !local.is_empty() && domain.contains('.') && !email.contains(char::is_whitespace))
+        .unwrap_or(false);
+    if !valid_email {
+        return Err("邮箱格式无效".to_string());
+    }
+    if row.refresh_token.is_empty() {
+        return Err("refresh_token 为空".to_string());
+    }
+    if row.refresh_token.contains(char::is_whitespace) {
+        return Err("refresh_token 包含空白字符".to_string());
+    }
+    Ok(())
+}
+
+/// 从 CSV/TSV 或 `email:refresh_token` 文本导入账号
+pub async fn import_from_tabular_logic(
+    content: String,
+    options: TabularImportOptions,
+) -> Result<TabularImportReport, String> {
+    let format = options.format.unwrap_or_else(|| detect_format(&content));
+    modules::logger::log_info(&format!("开始表格导入账号 (format={:?}, validate={})", format, options.validate));
+
+    let parsed = parse_tabular_rows(&content, format, &options)?;
+    // 小写邮箱 -> (账号 ID, 已保存的邮箱)；upsert 按邮箱精确匹配，需沿用已保存的写法
+    let existing_accounts: HashMap<String, (String, String)> = modules::account::load_account_index()?
+        .accounts
+        .iter()
+        .map(|summary| (summary.email.to_lowercase(), (summary.id.clone(), summary.email.clone())))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut rows = Vec::with_capacity(parsed.len());
+    for item in parsed {
+        let parsed_row = match item {
+            Ok(row) => row,
+            Err(result) => {
+                rows.push(result);
+                continue;
+            }
+        };
+        let existing = existing_accounts.get(&parsed_row.email.to_lowercase());
+        let email = existing
+            .map(|(_, stored_email)| stored_email.clone())
+            .unwrap_or_else(|| parsed_row.email.clone());
+        let existing_account = existing.and_then(|(account_id, _)| modules::load_account(account_id).ok());
+
+        if let Err(message) = validate_row(&parsed_row) {
+            rows.push(invalid_row(parsed_row.row, Some(email), &message));
+            continue;
+        }
+        if !seen.insert(email.to_lowercase()) {
+            rows.push(ImportRowResult {
+                row: parsed_row.row,
+                email: Some(email),
+                status: "duplicate".to_string(),
+                message: Some("与前面的行重复，已跳过".to_string()),
+                account_id: None,
+            });
+            continue;
+        }
+
+        let token = if options.validate {
+            match modules::oauth::refresh_access_token(&parsed_row.refresh_token).await {
+                Ok(response) => models::TokenData::new(
+                    response.access_token,
+                    response.refresh_token.unwrap_or(parsed_row.refresh_token.clone()),
+                    response.expires_in,
+                    Some(email.clone()),
+                    None,
+                    None,
+                ),
+                Err(e) => {
+                    rows.push(ImportRowResult {
+                        row: parsed_row.row,
+                        email: Some(email),
+                        status: "failed".to_string(),
+                        message: Some(format!("refresh_token 校验失败: {}", e)),
+                        account_id: None,
+                    });
+                    continue;
+                }
+            }
+        } else if let Some(existing_account) = &existing_account {
+            // 未校验时保留已有账号可用的 access_token，只更新 refresh_token
+            let mut token = existing_account.token.clone();
+            token.refresh_token = parsed_row.refresh_token.clone();
+            token
+        } else {
+            // 未校验时 access_token 置空并标记为已过期，首次使用时自动刷新
+            models::TokenData::new(
+                String::new(),
+                parsed_row.refresh_token.clone(),
+                0,
+                Some(email.clone()),
+                None,
+                None,
+            )
+        };
+
+        // 文件中没有名称时保留已有名称
+        let name = parsed_row
+            .name
+            .clone()
+            .filter(|name| !name.trim().is_empty())
+            .or_else(|| existing_account.as_ref().and_then(|account| account.name.clone()));
+        let result = modules::upsert_account(email.clone(), name, token).and_then(|account| {
+            if options.tags.is_empty() {
+                return Ok(account);
+            }
+            let mut tags = account.tags.clone();
+            tags.extend(options.tags.iter().cloned());
+            modules::account::update_account_tags(&account.id, tags)
+        });
+
+        rows.push(match result {
+            Ok(account) => ImportRowResult {
+                row: parsed_row.row,
+                status: if existing.is_some() {
+                    "updated".to_string()
+                } else {
+                    "imported".to_string()
+                },
+                email: Some(email),
+                message: None,
+                account_id: Some(account.id),
+            },
+            Err(e) => ImportRowResult {
+                row: parsed_row.row,
+                email: Some(email),
+                status: "failed".to_string(),
+                message: Some(e),
+                account_id: None,
+            },
+        });
+    }
+
+    let count = |status: &str| rows.iter().filter(|r| r.status == status).count();
+    let report = TabularImportReport {
+        format: format!("{:?}", format).to_lowercase(),
+        total: rows.len(),
+        imported: count("imported"),
+        updated: count("updated"),
+        failed: rows.len() - count("imported") - count("updated"),
+        rows,
+    };
+    modules::logger::log_info(&format!(
+        "表格导入完成: 新增 {}, 更新 {}, 失败/跳过 {}",
+        report.imported, report.updated, report.failed
+    ));
+
+    if report.imported + report.updated > 0 {
+        modules::websocket::broadcast_data_changed("import_from_tabular");
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str, options: TabularImportOptions) -> Vec<Result<ParsedRow, ImportRowResult>> {
+        let format = options.format.unwrap_or_else(|| detect_format(content));
+        parse_tabular_rows(content, format, &options).unwrap()
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(detect_format("a@b.com\t1//x"), TabularFormat::Tsv);
+        assert_eq!(detect_format("email,refresh_token\n"), TabularFormat::Csv);
+        assert_eq!(detect_format("a@b.com:1//x"), TabularFormat::Lines);
+    }
+
+    #[test]
+    fn csv_with_header_and_quotes() {
+        let content = "Name,Refresh Token,Email\n\"Doe, Jane\",1//abc,jane@example.com\n\n,1//def,bob@example.com\n";
+        let rows = parse(content, TabularImportOptions::default());
+        assert_eq!(rows.len(), 2);
+        let first = rows[0].as_ref().unwrap();
+        assert_eq!(first.row, 2);
+        assert_eq!(first.email, "jane@example.com");
+        assert_eq!(first.refresh_token, "1//abc");
+        assert_eq!(first.name.as_deref(), Some("Doe, Jane"));
+        assert_eq!(rows[1].as_ref().unwrap().row, 4);
+    }
+
+    #[test]
+    fn tsv_with_explicit_columns_and_no_header() {
+        let content = "x\t1//abc\tjane@example.com\n";
+        let options = TabularImportOptions {
+            email_column: Some("2".to_string()),
+            refresh_token_column: Some("1".to_string()),
+            ..Default::default()
+        };
+        let rows = parse(content, options);
+        let row = rows[0].as_ref().unwrap();
+        assert_eq!(row.email, "jane@example.com");
+        assert_eq!(row.refresh_token, "1//abc");
+    }
+
+    #[test]
+    fn lines_format_and_validation() {
+        let rows = parse("# comment\njane@example.com:1//abc\nbroken line\n", TabularImportOptions::default());
+        assert_eq!(rows.len(), 2);
+        assert!(validate_row(rows[0].as_ref().unwrap()).is_ok());
+        assert_eq!(rows[1].as_ref().unwrap_err().row, 3);
+
+        let bad = ParsedRow {
+            row: 1,
+            email: "not-an-email".to_string(),
+            refresh_token: "1//abc".to_string(),
+            name: None,
+        };
+        assert!(validate_row(&bad).is_err());
+    }
+
+    #[test]
+    fn unknown_column_name_is_an_error() {
+        let options = TabularImportOptions {
+            format: Some(TabularFormat::Csv),
+            email_column: Some("address".to_string()),
+            ..Default::default()
+        };
+        assert!(parse_tabular_rows("email,token\n", TabularFormat::Csv, &options).is_err());
+    }
+}
@@ -10,10 +10,20 @@ pub mod fingerprint;
 pub mod import;
 pub mod process;
 pub mod websocket;
+pub mod http_api;
+pub mod backup;
+pub mod account_export;
+pub mod account_sync;
 pub mod config;
+pub mod endpoints;
+#[cfg(test)]
+pub mod upstream_mock;
 pub mod wakeup;
 pub mod wakeup_scheduler;
+pub mod wakeup_cron;
 pub mod wakeup_history;
+pub mod token_refresher;
+pub mod health_check;
 pub mod sync_settings;
 pub mod update_checker;
 pub mod group_settings;
@@ -23,11 +33,17 @@ pub mod codex_oauth;
 pub mod copilot_account;
 pub mod copilot_quota;
 pub mod copilot_oauth;
+pub mod copilot_auth;
 pub mod opencode_auth;
+pub mod atomic_write;
+pub mod credential_sink;
+pub mod api_key_account;
 pub mod tray;
+pub mod tray_icon;
 pub mod instance_store;
 pub mod instance;
 pub mod codex_instance;
+pub mod copilot_instance;
 
 // 重新导出常用函数
 pub use account::*;
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::http::HttpError;
+
 const CLIENT_ID: &str = "1071006060591-tmhssin2h21lcre235vtolojh4g403ep.apps.googleusercontent.com";
 const CLIENT_SECRET: &str = "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";
 const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
@@ -121,6 +123,11 @@ pub async fn exchange_code(code: &str, redirect_uri: &str) -> Result<TokenRespon
 
 /// 使用 refresh_token 刷新 access_token
 pub async fn refresh_access_token(refresh_token: &str) -> Result<TokenResponse, String> {
+    request_token_refresh(refresh_token).await.map_err(|e| e.message)
+}
+
+/// 刷新 Token，失败时保留状态码与 OAuth 错误码
+pub async fn request_token_refresh(refresh_token: &str) -> Result<TokenResponse, HttpError> {
     let client = crate::utils::http::create_client(15);
     
     let params = [
@@ -135,9 +142,10 @@ pub async fn refresh_access_token(refresh_token: &str) -> Result<TokenResponse,
         .form(&params)
         .send()
         .await
-        .map_err(|e| format!("刷新请求失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("刷新请求失败: {}", e)))?;
 
-    if response.status().is_success() {
+    let status = response.status();
+    if status.is_success() {
         let token_data = response
             .json::<TokenResponse>()
             .await
@@ -146,12 +154,17 @@ pub async fn refresh_access_token(refresh_token: &str) -> Result<TokenResponse,
         Ok(token_data)
     } else {
         let error_text = response.text().await.unwrap_or_default();
-        Err(format!("刷新失败: {}", error_text))
+        Err(HttpError::response(status, &error_text, format!("刷新失败: {}", error_text)))
     }
 }
 
 /// 获取用户信息
 pub async fn get_user_info(access_token: &str) -> Result<UserInfo, String> {
+    request_user_info(access_token).await.map_err(|e| e.message)
+}
+
+/// 获取用户信息，失败时保留状态码
+pub async fn request_user_info(access_token: &str) -> Result<UserInfo, HttpError> {
     let client = crate::utils::http::create_client(15);
     
     let response = client
@@ -159,15 +172,16 @@ pub async fn get_user_info(access_token: &str) -> Result<UserInfo, String> {
         .bearer_auth(access_token)
         .send()
         .await
-        .map_err(|e| format!("用户信息请求失败: {}", e))?;
+        .map_err(|e| HttpError::network(format!("用户信息请求失败: {}", e)))?;
 
-    if response.status().is_success() {
+    let status = response.status();
+    if status.is_success() {
         response.json::<UserInfo>()
             .await
-            .map_err(|e| format!("用户信息解析失败: {}", e))
+            .map_err(|e| format!("用户信息解析失败: {}", e).into())
     } else {
         let error_text = response.text().await.unwrap_or_default();
-        Err(format!("获取用户信息失败: {}", error_text))
+        Err(HttpError::response(status, &error_text, format!("获取用户信息失败: {}", error_text)))
     }
 }
 
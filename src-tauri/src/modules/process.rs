@@ -11,6 +11,8 @@ const OPENCODE_APP_NAME: &str = "OpenCode";
 const CODEX_APP_PATH: &str = "/Applications/Codex.app/Contents/MacOS/Codex";
 #[cfg(target_os = "macos")]
 const ANTIGRAVITY_APP_PATH: &str = "/Applications/Antigravity.app/Contents/MacOS/Electron";
+#[cfg(target_os = "macos")]
+const VSCODE_APP_PATH: &str = "/Applications/Visual Studio Code.app/Contents/MacOS/Electron";
 
 #[cfg(target_os = "windows")]
 const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
@@ -139,6 +141,12 @@ fn update_app_path_in_config(app: &str, path: &Path) {
                 return;
             }
         }
+        "vscode" => {
+            if current.vscode_app_path == normalized {
+                return;
+            }
+            current.vscode_app_path = normalized;
+        }
         _ => return,
     }
     let _ = config::save_user_config(&current);
@@ -150,6 +158,7 @@ fn resolve_macos_app_root_from_config(app: &str) -> Option<String> {
     let raw = match app {
         "antigravity" => current.antigravity_app_path,
         "codex" => current.codex_app_path,
+        "vscode" => current.vscode_app_path,
         _ => String::new(),
     };
     let trimmed = raw.trim();
@@ -420,6 +429,58 @@ fn detect_opencode_exec_path() -> Option<std::path::PathBuf> {
     None
 }
 
+fn detect_vscode_exec_path() -> Option<std::path::PathBuf> {
+    #[cfg(target_os = "macos")]
+    {
+        let path = std::path::PathBuf::from(VSCODE_APP_PATH);
+        if path.exists() {
+            return Some(path);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let mut candidates: Vec<std::path::PathBuf> = Vec::new();
+        if let Ok(local_appdata) = std::env::var("LOCALAPPDATA") {
+            candidates.push(
+                std::path::PathBuf::from(local_appdata)
+                    .join("Programs")
+                    .join("Microsoft VS Code")
+                    .join("Code.exe"),
+            );
+        }
+        if let Ok(program_files) = std::env::var("PROGRAMFILES") {
+            candidates.push(
+                std::path::PathBuf::from(program_files)
+                    .join("Microsoft VS Code")
+                    .join("Code.exe"),
+            );
+        }
+        for candidate in candidates {
+            if candidate.exists() {
+                return Some(candidate);
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let candidates = [
+            "/usr/share/code/code",
+            "/usr/bin/code",
+            "/snap/bin/code",
+        ];
+        for candidate in candidates {
+            let path = std::path::PathBuf::from(candidate);
+            if path.exists() {
+                return Some(path);
+            }
+        }
+    }
+
+    None
+}
+
 fn resolve_antigravity_launch_path() -> Result<std::path::PathBuf, String> {
     if let Some(custom) = normalize_custom_path(Some(&config::get_user_config().antigravity_app_path)) {
         if let Some(exec) = resolve_macos_exec_path(&custom, "Electron") {
@@ -450,6 +511,21 @@ fn resolve_codex_launch_path() -> Result<std::path::PathBuf, String> {
     Err(app_path_missing_error("codex"))
 }
 
+fn resolve_vscode_launch_path() -> Result<std::path::PathBuf, String> {
+    if let Some(custom) = normalize_custom_path(Some(&config::get_user_config().vscode_app_path)) {
+        if let Some(exec) = resolve_macos_exec_path(&custom, "Electron") {
+            return Ok(exec);
+        }
+    }
+
+    if let Some(detected) = detect_vscode_exec_path() {
+        update_app_path_in_config("vscode", &detected);
+        return Ok(detected);
+    }
+
+    Err(app_path_missing_error("vscode"))
+}
+
 pub fn detect_and_save_app_path(app: &str) -> Option<String> {
     let current = config::get_user_config();
     match app {
@@ -480,6 +556,15 @@ pub fn detect_and_save_app_path(app: &str) -> Option<String> {
                 return Some(config::get_user_config().opencode_app_path);
             }
         }
+        "vscode" => {
+            if !current.vscode_app_path.trim().is_empty() {
+                return Some(current.vscode_app_path);
+            }
+            if let Some(detected) = detect_vscode_exec_path() {
+                update_app_path_in_config("vscode", &detected);
+                return Some(config::get_user_config().vscode_app_path);
+            }
+        }
         _ => {}
     }
     None
@@ -2459,6 +2544,137 @@ pub fn start_opencode_with_path(custom_path: Option<&str>) -> Result<(), String>
     Err("不支持的操作系统".to_string())
 }
 
+/// 收集 VS Code 主进程（PID 与 --user-data-dir）
+fn collect_vscode_process_entries() -> Vec<(u32, Option<String>)> {
+    let mut system = System::new();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+    let mut entries = Vec::new();
+    let current_pid = std::process::id();
+
+    for (pid, process) in system.processes() {
+        let pid_u32 = pid.as_u32();
+        if pid_u32 == current_pid {
+            continue;
+        }
+
+        let name = process.name().to_string_lossy().to_lowercase();
+        let exe_path = process
+            .exe()
+            .and_then(|p| p.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let args = process.cmd();
+        let args_str = args
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_lowercase())
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let is_helper = args_str.contains("--type=")
+            || name.contains("helper")
+            || name.contains("crashpad")
+            || exe_path.contains("crashpad");
+        if is_helper {
+            continue;
+        }
+
+        #[cfg(target_os = "macos")]
+        let matched = exe_path.contains("visual studio code.app/contents/macos/");
+        #[cfg(target_os = "windows")]
+        let matched = name == "code.exe";
+        #[cfg(target_os = "linux")]
+        let matched = name == "code" || exe_path.ends_with("/code/code");
+        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+        let matched = false;
+
+        if matched {
+            entries.push((pid_u32, extract_user_data_dir(args)));
+        }
+    }
+
+    entries
+}
+
+/// 关闭 VS Code（全部窗口）
+pub fn close_vscode(timeout_secs: u64) -> Result<(), String> {
+    crate::modules::logger::log_info("正在关闭 VS Code...");
+    let pids: Vec<u32> = collect_vscode_process_entries()
+        .into_iter()
+        .map(|(pid, _)| pid)
+        .collect();
+    if pids.is_empty() {
+        crate::modules::logger::log_info("VS Code 未在运行，无需关闭");
+        return Ok(());
+    }
+    for pid in pids {
+        close_pid(pid, timeout_secs)?;
+    }
+    crate::modules::logger::log_info("VS Code 已成功关闭");
+    Ok(())
+}
+
+/// 关闭指定 --user-data-dir 的 VS Code 实例
+pub fn close_vscode_instance(user_data_dir: &str, timeout_secs: u64) -> Result<(), String> {
+    let target = normalize_path_for_compare(user_data_dir);
+    let pids: Vec<u32> = collect_vscode_process_entries()
+        .into_iter()
+        .filter(|(_, dir)| {
+            dir.as_deref()
+                .is_some_and(|dir| normalize_path_for_compare(dir) == target)
+        })
+        .map(|(pid, _)| pid)
+        .collect();
+    for pid in pids {
+        close_pid(pid, timeout_secs)?;
+    }
+    Ok(())
+}
+
+/// 启动 VS Code
+///
+/// `user_data_dir` 为空时使用默认目录
+pub fn start_vscode_with_args(user_data_dir: &str, extra_args: &[String]) -> Result<u32, String> {
+    crate::modules::logger::log_info("正在启动 VS Code...");
+    let launch_path = resolve_vscode_launch_path()?;
+
+    let mut cmd = Command::new(&launch_path);
+    if !user_data_dir.trim().is_empty() {
+        cmd.arg("--user-data-dir");
+        cmd.arg(user_data_dir.trim());
+    }
+    for arg in extra_args {
+        if !arg.trim().is_empty() {
+            cmd.arg(arg);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    let child = {
+        use std::os::windows::process::CommandExt;
+        if should_detach_child() {
+            cmd.creation_flags(0x08000000 | CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS);
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+        } else {
+            cmd.creation_flags(0x08000000);
+        }
+        cmd.spawn().map_err(|e| format!("启动 VS Code 失败: {}", e))?
+    };
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    let child = spawn_detached_unix(&mut cmd).map_err(|e| format!("启动 VS Code 失败: {}", e))?;
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    let child = cmd.spawn().map_err(|e| format!("启动 VS Code 失败: {}", e))?;
+
+    crate::modules::logger::log_info(&format!(
+        "VS Code 已启动: {}",
+        launch_path.to_string_lossy()
+    ));
+    Ok(child.id())
+}
+
 pub fn find_pids_by_port(port: u16) -> Result<Vec<u32>, String> {
     let current_pid = std::process::id();
     let mut pids = HashSet::new();
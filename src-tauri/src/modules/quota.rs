@@ -7,12 +7,6 @@ use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::PathBuf;
 
-const QUOTA_API_URL: &str = "https://cloudcode-pa.googleapis.com/v1internal:fetchAvailableModels";
-const CLOUD_CODE_BASE_URLS: [&str; 3] = [
-    "https://daily-cloudcode-pa.googleapis.com",
-    "https://cloudcode-pa.googleapis.com",
-    "https://daily-cloudcode-pa.sandbox.googleapis.com",
-];
 const USER_AGENT: &str = "antigravity";
 const DEFAULT_ATTEMPTS: usize = 2;
 const BACKOFF_BASE_MS: u64 = 500;
@@ -297,7 +291,8 @@ pub async fn fetch_project_id(access_token: &str, email: &str) -> (Option<String
     let mut last_error: Option<String> = None;
     let meta = build_metadata_payload();
 
-    for base in CLOUD_CODE_BASE_URLS {
+    for base in modules::endpoints::cloud_code_base_urls() {
+        let base = base.as_str();
         for attempt in 1..=DEFAULT_ATTEMPTS {
             let response = client
                 .post(format!("{}/v1internal:loadCodeAssist", base))
@@ -484,10 +479,11 @@ pub async fn fetch_quota(access_token: &str, email: &str, skip_cache: bool) -> c
         .unwrap_or_else(|| json!({}));
     
     let max_retries = 3;
+    let quota_api_url = modules::endpoints::quota_api_url();
 
     for attempt in 1..=max_retries {
         match client
-            .post(QUOTA_API_URL)
+            .post(&quota_api_url)
             .bearer_auth(access_token)
             .header("User-Agent", USER_AGENT)
             .header(reqwest::header::ACCEPT_ENCODING, "gzip")
@@ -0,0 +1,354 @@
+//! 后台 Token 刷新
+//!
+//! 定期检查所有平台账号，在 access_token 过期前提前刷新，避免切换或查询配额时才发现刷新令牌已失效：
+//! - Antigravity：按 `expiry_timestamp` 刷新
+//! - Codex：按 access_token 的 `exp` 刷新（跳过已写入 auth.json 的账号，其 Token 由 Codex 自身轮换）
+//! - Copilot：GitHub Token 不会过期，定期校验是否已被撤销
+//!
+//! 刷新令牌被撤销（invalid_grant 等）或 GitHub Token 校验返回 401 时将账号标记为禁用并通知前端与扩展。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::codex::CodexTokens;
+use crate::models::{Account, TokenData};
+use crate::modules::websocket::AccountProvider;
+use crate::modules::{account, codex_account, codex_instance, codex_oauth, config, copilot_account, logger, oauth};
+use crate::utils::http::HttpError;
+
+/// 检查间隔
+const CHECK_INTERVAL_SECS: u64 = 5 * 60;
+/// 启动后首次检查前的等待时间
+const STARTUP_DELAY_SECS: u64 = 30;
+/// 距过期不足该时长时刷新（需大于检查间隔）
+const REFRESH_AHEAD_SECS: i64 = 15 * 60;
+/// Copilot Token 校验间隔
+const COPILOT_VALIDATE_INTERVAL_SECS: i64 = 6 * 60 * 60;
+
+/// 表示刷新令牌已失效的 OAuth 错误码
+///
+/// 不含 `refresh_token_reused`：令牌被其他客户端轮换后旧值会报该错误，账号本身仍然有效。
+const REVOKED_MARKERS: [&str; 3] = [
+    "invalid_grant",
+    "refresh_token_expired",
+    "refresh_token_invalidated",
+];
+
+static STARTED: OnceLock<Mutex<bool>> = OnceLock::new();
+static RUN_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+/// 各账号的刷新锁（后台刷新与健康检查共用），避免同一刷新令牌被并发轮换
+static ACCOUNT_REFRESH_LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+/// Copilot 账号上次校验时间
+static COPILOT_VALIDATED_AT: OnceLock<Mutex<HashMap<String, i64>>> = OnceLock::new();
+
+/// 被禁用的账号
+#[derive(Debug, Clone, Serialize)]
+pub struct DisabledAccount {
+    pub provider: AccountProvider,
+    pub account_id: String,
+    pub email: String,
+    pub reason: String,
+}
+
+/// 单次刷新结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenRefreshSummary {
+    pub checked: usize,
+    pub refreshed: usize,
+    pub failed: usize,
+    pub disabled: Vec<DisabledAccount>,
+}
+
+pub(crate) fn is_revoked_error(error: &HttpError) -> bool {
+    error
+        .code
+        .as_deref()
+        .is_some_and(|code| REVOKED_MARKERS.contains(&code))
+}
+
+/// 启动后台刷新循环（重复调用无副作用）
+pub fn ensure_started(app: AppHandle) {
+    let mut started = STARTED
+        .get_or_init(|| Mutex::new(false))
+        .lock()
+        .expect("token refresher started lock");
+    if *started {
+        return;
+    }
+    *started = true;
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(STARTUP_DELAY_SECS)).await;
+        loop {
+            if config::get_user_config().background_token_refresh {
+                run_once(Some(&app), false).await;
+            }
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+/// 执行一次检查；`force` 为 true 时忽略过期时间与校验间隔，刷新全部账号
+pub async fn run_once(app: Option<&AppHandle>, force: bool) -> TokenRefreshSummary {
+    let _guard = RUN_LOCK.lock().await;
+    let mut summary = TokenRefreshSummary::default();
+
+    refresh_antigravity(&mut summary, force).await;
+    refresh_codex(&mut summary, force).await;
+    validate_copilot(&mut summary, force).await;
+
+    if summary.refreshed > 0 || summary.failed > 0 || !summary.disabled.is_empty() {
+        logger::log_info(&format!(
+            "[TokenRefresher] 检查 {} 个账号: 刷新 {} 个, 失败 {} 个, 禁用 {} 个",
+            summary.checked,
+            summary.refreshed,
+            summary.failed,
+            summary.disabled.len()
+        ));
+    }
+    if !summary.disabled.is_empty() {
+        notify_disabled(app, &summary.disabled);
+    }
+    summary
+}
+
+fn account_refresh_lock(provider: AccountProvider, account_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+    let locks = ACCOUNT_REFRESH_LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut locks = locks.lock().unwrap_or_else(|e| e.into_inner());
+    locks
+        .entry(format!("{:?}:{}", provider, account_id))
+        .or_default()
+        .clone()
+}
+
+/// 刷新 Antigravity 账号的 Token 并保存
+pub(crate) async fn refresh_antigravity_account(acc: &Account) -> Result<TokenData, HttpError> {
+    let lock = account_refresh_lock(AccountProvider::Antigravity, &acc.id);
+    let _guard = lock.lock().await;
+    let response = oauth::request_token_refresh(&acc.token.refresh_token).await?;
+    let token = TokenData::new(
+        response.access_token,
+        acc.token.refresh_token.clone(),
+        response.expires_in,
+        acc.token.email.clone(),
+        acc.token.project_id.clone(),
+        None,
+    );
+    // 重新读取，避免覆盖刷新期间的其他修改
+    let mut latest = account::load_account(&acc.id)?;
+    latest.token = token.clone();
+    account::save_account(&latest).map_err(|e| format!("保存 Token 失败: {}", e))?;
+    Ok(token)
+}
+
+/// 刷新 Codex 账号的 Token 并保存
+///
+/// 等待锁期间刷新令牌已被其他任务轮换时直接返回已保存的 Token。
+pub(crate) async fn refresh_codex_account(account_id: &str, refresh_token: &str) -> Result<CodexTokens, HttpError> {
+    let lock = account_refresh_lock(AccountProvider::Codex, account_id);
+    let _guard = lock.lock().await;
+    let stored = codex_account::load_account(account_id).ok_or_else(|| format!("账号不存在: {}", account_id))?;
+    if stored.tokens.refresh_token.as_deref() != Some(refresh_token) {
+        return Ok(stored.tokens);
+    }
+    let tokens = codex_oauth::request_token_refresh(refresh_token).await?;
+    let mut latest = codex_account::load_account(account_id).ok_or_else(|| format!("账号不存在: {}", account_id))?;
+    latest.tokens = tokens.clone();
+    codex_account::save_account(&latest).map_err(|e| format!("保存 Token 失败: {}", e))?;
+    Ok(tokens)
+}
+
+async fn refresh_antigravity(summary: &mut TokenRefreshSummary, force: bool) {
+    let accounts = match account::list_accounts() {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            logger::log_warn(&format!("[TokenRefresher] 读取账号列表失败: {}", e));
+            return;
+        }
+    };
+    let now = chrono::Utc::now().timestamp();
+
+    for acc in accounts.into_iter().filter(|acc| !acc.disabled) {
+        summary.checked += 1;
+        if !force && acc.token.expiry_timestamp > now + REFRESH_AHEAD_SECS {
+            continue;
+        }
+
+        match refresh_antigravity_account(&acc).await {
+            Ok(_) => summary.refreshed += 1,
+            Err(e) if is_revoked_error(&e) => {
+                let reason = format!("invalid_grant: {}", e);
+                if let Ok(mut latest) = account::load_account(&acc.id) {
+                    latest.disabled = true;
+                    latest.disabled_reason = Some(reason.clone());
+                    latest.disabled_at = Some(chrono::Utc::now().timestamp());
+                    if let Err(e) = account::save_account(&latest) {
+                        logger::log_warn(&format!("[TokenRefresher] 禁用 {} 失败: {}", acc.email, e));
+                    }
+                }
+                summary.disabled.push(DisabledAccount {
+                    provider: AccountProvider::Antigravity,
+                    account_id: acc.id,
+                    email: acc.email,
+                    reason,
+                });
+            }
+            Err(e) => {
+                summary.failed += 1;
+                logger::log_warn(&format!("[TokenRefresher] 刷新 {} 的 Token 失败: {}", acc.email, e));
+            }
+        }
+    }
+}
+
+/// 已写入 auth.json 的 Codex 账号及其所在目录（默认目录及各实例目录）
+///
+/// 这些账号的 Token 由 Codex 自身轮换，此处会先回收最新 Token。
+pub(crate) fn codex_auth_dirs() -> HashMap<String, PathBuf> {
+    let mut dirs = vec![codex_account::get_codex_home()];
+    match codex_instance::load_instance_store() {
+        Ok(store) => dirs.extend(store.instances.iter().map(|instance| PathBuf::from(&instance.user_data_dir))),
+        Err(e) => logger::log_warn(&format!("[TokenRefresher] 读取 Codex 实例失败: {}", e)),
+    }
+    dirs.into_iter()
+        .filter_map(|dir| Some((codex_account::sync_tokens_from_dir(&dir)?, dir)))
+        .collect()
+}
+
+async fn refresh_codex(summary: &mut TokenRefreshSummary, force: bool) {
+    // 跳过已写入 auth.json 的账号，以免刷新后 Codex 持有的令牌失效
+    let in_auth_files = codex_auth_dirs();
+    let now = chrono::Utc::now().timestamp();
+
+    for acc in codex_account::list_accounts().into_iter().filter(|acc| !acc.disabled) {
+        if in_auth_files.contains_key(&acc.id) {
+            continue;
+        }
+        let Some(refresh_token) = acc.tokens.refresh_token.clone() else {
+            continue;
+        };
+        summary.checked += 1;
+
+        let expires_at = codex_account::decode_jwt_payload(&acc.tokens.access_token)
+            .ok()
+            .and_then(|payload| payload.exp);
+        let due = match expires_at {
+            Some(exp) => exp <= now + REFRESH_AHEAD_SECS,
+            None => codex_oauth::is_token_expired(&acc.tokens.access_token),
+        };
+        if !force && !due {
+            continue;
+        }
+
+        match refresh_codex_account(&acc.id, &refresh_token).await {
+            Ok(_) => summary.refreshed += 1,
+            Err(e) if is_revoked_error(&e) => {
+                let e = e.message;
+                if let Some(mut latest) = codex_account::load_account(&acc.id) {
+                    latest.disabled = true;
+                    latest.disabled_reason = Some(e.clone());
+                    latest.disabled_at = Some(chrono::Utc::now().timestamp());
+                    if let Err(e) = codex_account::save_account(&latest) {
+                        logger::log_warn(&format!("[TokenRefresher] 禁用 Codex {} 失败: {}", acc.email, e));
+                    }
+                }
+                summary.disabled.push(DisabledAccount {
+                    provider: AccountProvider::Codex,
+                    account_id: acc.id,
+                    email: acc.email,
+                    reason: e,
+                });
+            }
+            Err(e) => {
+                summary.failed += 1;
+                logger::log_warn(&format!("[TokenRefresher] 刷新 Codex {} 的 Token 失败: {}", acc.email, e));
+            }
+        }
+    }
+}
+
+async fn validate_copilot(summary: &mut TokenRefreshSummary, force: bool) {
+    let now = chrono::Utc::now().timestamp();
+    let validated_at = COPILOT_VALIDATED_AT.get_or_init(|| Mutex::new(HashMap::new()));
+
+    for acc in copilot_account::list_accounts().into_iter().filter(|acc| !acc.disabled) {
+        summary.checked += 1;
+        let last = validated_at
+            .lock()
+            .ok()
+            .and_then(|map| map.get(&acc.id).copied());
+        if !force && last.is_some_and(|last| now - last < COPILOT_VALIDATE_INTERVAL_SECS) {
+            continue;
+        }
+
+        match copilot_account::request_github_user(&acc.token).await {
+            Ok(_) => {
+                if let Ok(mut map) = validated_at.lock() {
+                    map.insert(acc.id.clone(), now);
+                }
+            }
+            Err(e) if e.status == Some(reqwest::StatusCode::UNAUTHORIZED) => {
+                let e = e.message;
+                if let Some(mut latest) = copilot_account::load_account(&acc.id) {
+                    latest.disabled = true;
+                    latest.disabled_reason = Some(e.clone());
+                    latest.disabled_at = Some(chrono::Utc::now().timestamp());
+                    if let Err(e) = copilot_account::save_account(&latest) {
+                        logger::log_warn(&format!("[TokenRefresher] 禁用 Copilot {} 失败: {}", acc.username, e));
+                    }
+                }
+                summary.disabled.push(DisabledAccount {
+                    provider: AccountProvider::Copilot,
+                    account_id: acc.id,
+                    email: acc.email.unwrap_or(acc.username),
+                    reason: e,
+                });
+            }
+            Err(e) => {
+                summary.failed += 1;
+                logger::log_warn(&format!("[TokenRefresher] 校验 Copilot {} 失败: {}", acc.username, e.message));
+            }
+        }
+    }
+}
+
+/// 通知前端与扩展：账号因刷新令牌失效被禁用
+fn notify_disabled(app: Option<&AppHandle>, disabled: &[DisabledAccount]) {
+    for item in disabled {
+        logger::log_warn(&format!(
+            "[TokenRefresher] {:?} 账号 {} 的刷新令牌已失效，已禁用: {}",
+            item.provider, item.email, item.reason
+        ));
+    }
+    if let Some(app) = app {
+        let _ = app.emit("token:accounts_disabled", disabled);
+        let _ = crate::modules::tray::update_tray_menu(app);
+    }
+    crate::modules::websocket::broadcast_data_changed("token_refresher");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_revoked_refresh_tokens() {
+        let response = |status: u16, body: &str| {
+            HttpError::response(reqwest::StatusCode::from_u16(status).unwrap(), body, body.to_string())
+        };
+        assert!(is_revoked_error(&response(
+            400,
+            r#"{"error": "invalid_grant", "error_description": "Token has been expired or revoked."}"#
+        )));
+        assert!(is_revoked_error(&response(401, r#"{"error": {"code": "refresh_token_expired"}}"#)));
+        assert!(!is_revoked_error(&response(401, r#"{"error": {"code": "refresh_token_reused"}}"#)));
+        // 错误码只取自响应体结构，描述文字中出现的标识不算
+        assert!(!is_revoked_error(&response(400, r#"{"error": "invalid_request", "error_description": "invalid_grant"}"#)));
+        assert!(!is_revoked_error(&HttpError::network("刷新请求失败: error sending request".to_string())));
+    }
+}
@@ -2,6 +2,7 @@
 //! 管理系统托盘图标和菜单
 
 use tauri::{
+    image::Image,
     menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
     Emitter, Manager, Runtime,
@@ -13,12 +14,61 @@ use crate::modules::logger;
 /// 托盘菜单 ID
 pub const TRAY_ID: &str = "main-tray";
 
+const DEFAULT_TOOLTIP: &str = "Cockpit Tools";
+
 /// 菜单项 ID
 pub mod menu_ids {
     pub const SHOW_WINDOW: &str = "show_window";
     pub const REFRESH_QUOTA: &str = "refresh_quota";
     pub const SETTINGS: &str = "settings";
     pub const QUIT: &str = "quit";
+    /// 快速切换菜单项前缀：`switch:<平台>:<账号 ID>`
+    pub const SWITCH_PREFIX: &str = "switch:";
+}
+
+/// 每个「切换到…」子菜单最多列出的账号数
+const MAX_SWITCH_ITEMS: usize = 30;
+
+/// 快速切换的目标平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SwitchTarget {
+    Antigravity,
+    Codex,
+    Copilot,
+    ApiKey,
+}
+
+impl SwitchTarget {
+    fn key(self) -> &'static str {
+        match self {
+            SwitchTarget::Antigravity => "ag",
+            SwitchTarget::Codex => "codex",
+            SwitchTarget::Copilot => "copilot",
+            SwitchTarget::ApiKey => "apikey",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "ag" => Some(SwitchTarget::Antigravity),
+            "codex" => Some(SwitchTarget::Codex),
+            "copilot" => Some(SwitchTarget::Copilot),
+            "apikey" => Some(SwitchTarget::ApiKey),
+            _ => None,
+        }
+    }
+}
+
+/// 「切换到…」子菜单中的一个账号
+#[derive(Debug, Clone)]
+struct SwitchEntry {
+    id: String,
+    label: String,
+    /// 剩余配额百分比（取各项中最低的一项），无数据时为 None
+    remaining: Option<i32>,
+    current: bool,
+    disabled: bool,
+    forbidden: bool,
 }
 
 /// 创建系统托盘
@@ -26,12 +76,16 @@ pub fn create_tray<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<TrayIcon<R>,
     info!("[Tray] 正在创建系统托盘...");
     
     let menu = build_tray_menu(app)?;
+    let (icon, tooltip) = match build_quota_icon() {
+        Some((icon, tooltip)) => (icon, tooltip),
+        None => (app.default_window_icon().unwrap().clone(), DEFAULT_TOOLTIP.to_string()),
+    };
     
     let tray = TrayIconBuilder::with_id(TRAY_ID)
-        .icon(app.default_window_icon().unwrap().clone())
+        .icon(icon)
         .menu(&menu)
         .show_menu_on_left_click(false)
-        .tooltip("Cockpit Tools")
+        .tooltip(tooltip)
         .on_menu_event(handle_menu_event)
         .on_tray_icon_event(handle_tray_event)
         .build(app)?;
@@ -47,7 +101,7 @@ fn build_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<Menu<R>, tau
     let lang = &config.language;
     
     // 获取账号信息（暂时使用占位符，后续动态更新）
-    let (ag_info, codex_info) = get_account_display_info();
+    let (ag_info, codex_info, copilot_info) = get_account_display_info();
     
     // 创建菜单项
     let show_window = MenuItem::with_id(
@@ -100,10 +154,14 @@ fn build_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<Menu<R>, tau
             None::<&str>,
         )?);
     }
-    let ag_refs: Vec<&dyn IsMenuItem<R>> = ag_items
+    let ag_separator = PredefinedMenuItem::separator(app)?;
+    let ag_switch = build_switch_submenu(app, SwitchTarget::Antigravity, lang)?;
+    let mut ag_refs: Vec<&dyn IsMenuItem<R>> = ag_items
         .iter()
         .map(|item| item as &dyn IsMenuItem<R>)
         .collect();
+    ag_refs.push(&ag_separator);
+    ag_refs.push(&ag_switch);
     let ag_submenu = Submenu::with_id_and_items(
         app,
         "antigravity_submenu",
@@ -130,10 +188,14 @@ fn build_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<Menu<R>, tau
             None::<&str>,
         )?);
     }
-    let codex_refs: Vec<&dyn IsMenuItem<R>> = codex_items
+    let codex_separator = PredefinedMenuItem::separator(app)?;
+    let codex_switch = build_switch_submenu(app, SwitchTarget::Codex, lang)?;
+    let mut codex_refs: Vec<&dyn IsMenuItem<R>> = codex_items
         .iter()
         .map(|item| item as &dyn IsMenuItem<R>)
         .collect();
+    codex_refs.push(&codex_separator);
+    codex_refs.push(&codex_switch);
     let codex_submenu = Submenu::with_id_and_items(
         app,
         "codex_submenu",
@@ -142,6 +204,67 @@ fn build_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<Menu<R>, tau
         &codex_refs,
     )?;
     
+    // Copilot 子菜单
+    let mut copilot_items: Vec<MenuItem<R>> = Vec::new();
+    copilot_items.push(MenuItem::with_id(
+        app,
+        "copilot_account",
+        copilot_info.account,
+        true,
+        None::<&str>,
+    )?);
+    for (idx, line) in copilot_info.quota_lines.iter().enumerate() {
+        copilot_items.push(MenuItem::with_id(
+            app,
+            format!("copilot_quota_{}", idx),
+            line,
+            true,
+            None::<&str>,
+        )?);
+    }
+    let copilot_separator = PredefinedMenuItem::separator(app)?;
+    let copilot_switch = build_switch_submenu(app, SwitchTarget::Copilot, lang)?;
+    let mut copilot_refs: Vec<&dyn IsMenuItem<R>> = copilot_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<R>)
+        .collect();
+    copilot_refs.push(&copilot_separator);
+    copilot_refs.push(&copilot_switch);
+    let copilot_submenu = Submenu::with_id_and_items(
+        app,
+        "copilot_submenu",
+        "Copilot",
+        true,
+        &copilot_refs,
+    )?;
+    
+    // API Key 子菜单
+    let mut api_key_items: Vec<MenuItem<R>> = Vec::new();
+    for (idx, line) in get_api_key_display_lines(lang).iter().enumerate() {
+        api_key_items.push(MenuItem::with_id(
+            app,
+            format!("apikey_info_{}", idx),
+            line,
+            false,
+            None::<&str>,
+        )?);
+    }
+    let api_key_separator = PredefinedMenuItem::separator(app)?;
+    let api_key_switch = build_switch_submenu(app, SwitchTarget::ApiKey, lang)?;
+    let mut api_key_refs: Vec<&dyn IsMenuItem<R>> = api_key_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<R>)
+        .collect();
+    api_key_refs.push(&api_key_separator);
+    api_key_refs.push(&api_key_switch);
+    let api_key_submenu = Submenu::with_id_and_items(
+        app,
+        "apikey_submenu",
+        "API Key",
+        true,
+        &api_key_refs,
+    )?;
+    
     // 构建完整菜单
     let menu = Menu::with_id_and_items(
         app,
@@ -151,6 +274,8 @@ fn build_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<Menu<R>, tau
             &PredefinedMenuItem::separator(app)?,
             &ag_submenu,
             &codex_submenu,
+            &copilot_submenu,
+            &api_key_submenu,
             &PredefinedMenuItem::separator(app)?,
             &refresh_quota,
             &settings,
@@ -169,7 +294,7 @@ struct AccountDisplayInfo {
 }
 
 /// 获取账号显示信息
-fn get_account_display_info() -> (AccountDisplayInfo, AccountDisplayInfo) {
+fn get_account_display_info() -> (AccountDisplayInfo, AccountDisplayInfo, AccountDisplayInfo) {
     let config = crate::modules::config::get_user_config();
     let lang = &config.language;
     
@@ -196,20 +321,7 @@ fn get_account_display_info() -> (AccountDisplayInfo, AccountDisplayInfo) {
     // 获取 Codex 当前账号
     let codex_info = if let Some(account) = crate::modules::codex_account::get_current_account() {
         let mut quota_lines = if let Some(quota) = &account.quota {
-            vec![
-                format!(
-                    "5h: {}% · {} {}",
-                    quota.hourly_percentage,
-                    get_text("reset", lang),
-                    format_reset_time_from_ts(lang, quota.hourly_reset_time)
-                ),
-                format!(
-                    "Week: {}% · {} {}",
-                    quota.weekly_percentage,
-                    get_text("reset", lang),
-                    format_reset_time_from_ts(lang, quota.weekly_reset_time)
-                ),
-            ]
+            build_codex_quota_lines(lang, quota)
         } else {
             vec![get_text("loading", lang)]
         };
@@ -228,7 +340,339 @@ fn get_account_display_info() -> (AccountDisplayInfo, AccountDisplayInfo) {
         }
     };
     
-    (ag_info, codex_info)
+    // 获取 Copilot 当前账号
+    let copilot_info = if let Some(account) = crate::modules::copilot_account::get_current_account() {
+        let quota_lines = match &account.quota {
+            Some(quota) => build_copilot_quota_lines(lang, quota),
+            None => vec![get_text("loading", lang)],
+        };
+        AccountDisplayInfo {
+            account: format!("📧 {}", account.email.as_deref().unwrap_or(&account.username)),
+            quota_lines,
+        }
+    } else {
+        AccountDisplayInfo {
+            account: format!("📧 {}", get_text("not_logged_in", lang)),
+            quota_lines: vec!["—".to_string()],
+        }
+    };
+    
+    (ag_info, codex_info, copilot_info)
+}
+
+/// 获取当前 API Key 的显示信息
+fn get_api_key_display_lines(lang: &str) -> Vec<String> {
+    let Some(account) = crate::modules::api_key_account::get_current_account() else {
+        return vec![format!("🔑 {}", get_text("not_selected", lang))];
+    };
+    let mut lines = vec![format!("🔑 {} · {}", account.label, account.masked_key())];
+    if let Some(usage) = &account.usage {
+        match (usage.remaining, usage.total_granted) {
+            (Some(remaining), Some(granted)) => lines.push(format!("${:.2} / ${:.2}", remaining, granted)),
+            (Some(remaining), None) => lines.push(format!("${:.2}", remaining)),
+            _ => {}
+        }
+    }
+    lines
+}
+
+/// 构建某个平台的「切换到…」子菜单
+fn build_switch_submenu<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    target: SwitchTarget,
+    lang: &str,
+) -> Result<Submenu<R>, tauri::Error> {
+    let entries = collect_switch_entries(target);
+    let mut items: Vec<MenuItem<R>> = Vec::new();
+    if entries.is_empty() {
+        items.push(MenuItem::with_id(
+            app,
+            format!("{}_switch_empty", target.key()),
+            get_text("no_accounts", lang),
+            false,
+            None::<&str>,
+        )?);
+    }
+    for entry in entries.iter().take(MAX_SWITCH_ITEMS) {
+        items.push(MenuItem::with_id(
+            app,
+            format!("{}{}:{}", menu_ids::SWITCH_PREFIX, target.key(), entry.id),
+            format_switch_entry(entry, lang),
+            !entry.current && !entry.disabled,
+            None::<&str>,
+        )?);
+    }
+    let refs: Vec<&dyn IsMenuItem<R>> = items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<R>)
+        .collect();
+    Submenu::with_id_and_items(
+        app,
+        format!("{}_switch_submenu", target.key()),
+        get_text("switch_to", lang),
+        true,
+        &refs,
+    )
+}
+
+/// 收集某个平台的账号并按剩余配额排序
+fn collect_switch_entries(target: SwitchTarget) -> Vec<SwitchEntry> {
+    use crate::modules::{account, api_key_account, codex_account, copilot_account};
+
+    let mut entries: Vec<SwitchEntry> = match target {
+        SwitchTarget::Antigravity => {
+            let current_id = account::get_current_account_id().ok().flatten();
+            account::list_accounts()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|acc| SwitchEntry {
+                    current: current_id.as_deref() == Some(acc.id.as_str()),
+                    remaining: acc
+                        .quota
+                        .as_ref()
+                        .and_then(|quota| quota.models.iter().map(|model| model.percentage).min()),
+                    disabled: acc.disabled,
+                    forbidden: acc.quota.as_ref().is_some_and(|quota| quota.is_forbidden),
+                    label: acc.email,
+                    id: acc.id,
+                })
+                .collect()
+        }
+        SwitchTarget::Codex => {
+            let current_id = codex_account::get_current_account().map(|acc| acc.id);
+            codex_account::list_accounts()
+                .into_iter()
+                .map(|acc| SwitchEntry {
+                    current: current_id.as_deref() == Some(acc.id.as_str()),
+                    remaining: acc.quota.as_ref().map(codex_remaining_percentage),
+                    disabled: acc.disabled,
+                    forbidden: false,
+                    label: acc.email,
+                    id: acc.id,
+                })
+                .collect()
+        }
+        SwitchTarget::Copilot => {
+            let current_id = copilot_account::get_current_account().map(|acc| acc.id);
+            copilot_account::list_accounts()
+                .into_iter()
+                .map(|acc| SwitchEntry {
+                    current: current_id.as_deref() == Some(acc.id.as_str()),
+                    remaining: acc.quota.as_ref().and_then(copilot_remaining_percentage),
+                    disabled: acc.disabled,
+                    forbidden: false,
+                    label: acc.email.unwrap_or(acc.username),
+                    id: acc.id,
+                })
+                .collect()
+        }
+        SwitchTarget::ApiKey => {
+            let current_id = api_key_account::get_current_account().map(|acc| acc.id);
+            api_key_account::list_accounts()
+                .into_iter()
+                .map(|acc| SwitchEntry {
+                    current: current_id.as_deref() == Some(acc.id.as_str()),
+                    remaining: acc.usage.as_ref().and_then(|usage| match (usage.remaining, usage.total_granted) {
+                        (Some(remaining), Some(granted)) if granted > 0.0 => {
+                            Some((remaining / granted * 100.0).round() as i32)
+                        }
+                        _ => None,
+                    }),
+                    disabled: false,
+                    forbidden: false,
+                    label: acc.label,
+                    id: acc.id,
+                })
+                .collect()
+        }
+    };
+    sort_switch_entries(&mut entries);
+    entries
+}
+
+/// 可用账号在前（剩余配额高的在前，无配额数据的其次），受限账号、禁用账号依次在后
+fn sort_switch_entries(entries: &mut [SwitchEntry]) {
+    entries.sort_by(|a, b| {
+        a.disabled
+            .cmp(&b.disabled)
+            .then(a.forbidden.cmp(&b.forbidden))
+            .then_with(|| match (a.remaining, b.remaining) {
+                (Some(x), Some(y)) => y.cmp(&x),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+            .then_with(|| a.label.cmp(&b.label))
+    });
+}
+
+fn codex_remaining_percentage(quota: &crate::models::codex::CodexQuota) -> i32 {
+    if quota.limit_reached || !quota.allowed {
+        return 0;
+    }
+    quota.hourly_percentage.min(quota.weekly_percentage)
+}
+
+/// Copilot 以 Premium 配额为准，不限量时视为 100%
+fn copilot_remaining_percentage(quota: &crate::models::copilot::CopilotQuota) -> Option<i32> {
+    if let Some(snapshot) = quota
+        .snapshots
+        .iter()
+        .find(|snapshot| snapshot.quota_id == "premium_interactions")
+    {
+        if snapshot.unlimited {
+            return Some(100);
+        }
+        if let Some(percent) = snapshot.percent_remaining {
+            return Some(percent.round() as i32);
+        }
+    }
+    match (quota.remaining_requests, quota.included_requests) {
+        (Some(remaining), Some(included)) if included > 0 => {
+            Some((remaining as f64 / included as f64 * 100.0).round() as i32)
+        }
+        _ => None,
+    }
+}
+
+fn format_switch_entry(entry: &SwitchEntry, lang: &str) -> String {
+    let mut text = if entry.current {
+        format!("✓ {}", entry.label)
+    } else {
+        entry.label.clone()
+    };
+    if let Some(remaining) = entry.remaining {
+        text.push_str(&format!(" · {}%", remaining));
+    }
+    if entry.disabled {
+        text.push_str(&format!(" · ⛔ {}", get_text("disabled", lang)));
+    } else if entry.forbidden {
+        text.push_str(&format!(" · 🚫 {}", get_text("forbidden", lang)));
+    }
+    text
+}
+
+/// 执行托盘中的快速切换（与对应命令走相同的切换流程），完成后刷新托盘菜单
+fn spawn_switch<R: Runtime>(app: &tauri::AppHandle<R>, target: SwitchTarget, account_id: String) {
+    use crate::modules::websocket::{self, AccountProvider};
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match target {
+            SwitchTarget::Antigravity => crate::commands::account::switch_account_with_app(&app, &account_id)
+                .await
+                .map(|account| account.email),
+            SwitchTarget::Codex => websocket::switch_provider_account(AccountProvider::Codex, &account_id).await,
+            SwitchTarget::Copilot => {
+                websocket::switch_provider_account(AccountProvider::Copilot, &account_id).await
+            }
+            SwitchTarget::ApiKey => {
+                let account_id = account_id.clone();
+                tokio::task::spawn_blocking(move || crate::modules::api_key_account::switch_account(&account_id))
+                    .await
+                    .map_err(|e| format!("切换任务失败: {}", e))
+                    .and_then(|result| result)
+                    .map(|result| result.account.label)
+            }
+        };
+        match result {
+            Ok(name) => {
+                logger::log_info(&format!("[Tray] 已切换 {} 账号: {}", target.key(), name));
+                let _ = app.emit("accounts:refresh", "tray");
+            }
+            Err(e) => {
+                logger::log_error(&format!("[Tray] 切换 {} 账号失败: {}", target.key(), e));
+            }
+        }
+        if let Err(e) = update_tray_menu(&app) {
+            logger::log_warn(&format!("[Tray] 刷新托盘菜单失败: {}", e));
+        }
+    });
+}
+
+fn build_codex_quota_lines(lang: &str, quota: &crate::models::codex::CodexQuota) -> Vec<String> {
+    use crate::models::codex::CodexQuotaLevel;
+
+    let mut lines = Vec::new();
+    match quota.level() {
+        CodexQuotaLevel::Exhausted => lines.push(format!("⛔ {}", get_text("quota_exhausted", lang))),
+        CodexQuotaLevel::Low => lines.push(format!("⚠️ {}", get_text("quota_low", lang))),
+        CodexQuotaLevel::Normal => {}
+    }
+    lines.push(format!(
+        "5h: {}% · {} {}",
+        quota.hourly_percentage,
+        get_text("reset", lang),
+        format_reset_time_from_ts(lang, quota.hourly_reset_time)
+    ));
+    lines.push(format!(
+        "Week: {}% · {} {}",
+        quota.weekly_percentage,
+        get_text("reset", lang),
+        format_reset_time_from_ts(lang, quota.weekly_reset_time)
+    ));
+    if let Some(code_review) = &quota.code_review {
+        let mut line = format!(
+            "{}: {}% · {} {}",
+            get_text("code_review", lang),
+            code_review.percentage,
+            get_text("reset", lang),
+            format_reset_time_from_ts(lang, code_review.reset_time)
+        );
+        if code_review.limit_reached || !code_review.allowed {
+            line.push_str(&format!(" ⛔ {}", get_text("quota_exhausted", lang)));
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn copilot_snapshot_label(quota_id: &str) -> String {
+    match quota_id {
+        "premium_interactions" => "Premium".to_string(),
+        "chat" => "Chat".to_string(),
+        "completions" => "Completions".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn build_copilot_quota_lines(
+    lang: &str,
+    quota: &crate::models::copilot::CopilotQuota,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for snapshot in &quota.snapshots {
+        let label = copilot_snapshot_label(&snapshot.quota_id);
+        if snapshot.unlimited {
+            lines.push(format!("{}: ∞ {}", label, get_text("unlimited", lang)));
+            continue;
+        }
+        let mut line = match (snapshot.remaining, snapshot.entitlement) {
+            (Some(remaining), Some(entitlement)) => format!("{}: {}/{}", label, remaining, entitlement),
+            _ => format!("{}: —", label),
+        };
+        if let Some(percent) = snapshot.percent_remaining {
+            line.push_str(&format!(" ({}%)", percent.round() as i64));
+        }
+        if snapshot.overage_count > 0 {
+            line.push_str(&format!(" · {} {}", get_text("overage", lang), snapshot.overage_count));
+        } else if snapshot.overage_permitted {
+            line.push_str(&format!(" · {}", get_text("overage_permitted", lang)));
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        if let (Some(remaining), Some(included)) = (quota.remaining_requests, quota.included_requests) {
+            lines.push(format!("Premium: {}/{}", remaining, included));
+        }
+    }
+    if let Some(reset_date) = &quota.quota_reset_date {
+        lines.push(format!("{} {}", get_text("reset", lang), reset_date));
+    }
+    if lines.is_empty() {
+        lines.push("—".to_string());
+    }
+    lines
 }
 
 fn build_model_quota_lines(
@@ -340,7 +784,14 @@ fn handle_menu_event<R: Runtime>(app: &tauri::AppHandle<R>, event: tauri::menu::
             app.exit(0);
         }
         _ => {
-            if id.starts_with("ag_") {
+            if let Some(rest) = id.strip_prefix(menu_ids::SWITCH_PREFIX) {
+                if let Some((target, account_id)) = rest
+                    .split_once(':')
+                    .and_then(|(key, account_id)| Some((SwitchTarget::from_key(key)?, account_id)))
+                {
+                    spawn_switch(app, target, account_id.to_string());
+                }
+            } else if id.starts_with("ag_") {
                 if let Some(window) = app.get_webview_window("main") {
                     let _ = window.show();
                     let _ = window.unminimize();
@@ -354,6 +805,13 @@ fn handle_menu_event<R: Runtime>(app: &tauri::AppHandle<R>, event: tauri::menu::
                     let _ = window.set_focus();
                     let _ = app.emit("tray:navigate", "codex");
                 }
+            } else if id.starts_with("copilot_") {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.unminimize();
+                    let _ = window.set_focus();
+                    let _ = app.emit("tray:navigate", "copilot");
+                }
             }
         }
     }
@@ -392,11 +850,72 @@ pub fn update_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), Str
     if let Some(tray) = app.tray_by_id(TRAY_ID) {
         let menu = build_tray_menu(app).map_err(|e| e.to_string())?;
         tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
+        if let Err(e) = update_tray_icon(app) {
+            logger::log_warn(&format!("[Tray] 更新托盘图标失败: {}", e));
+        }
         logger::log_info("[Tray] 托盘菜单已更新");
     }
     Ok(())
 }
 
+/// 按当前 Antigravity 账号的最低剩余配额绘制托盘图标
+///
+/// 未开启、未登录或没有配额数据时返回 None，使用默认图标。
+fn build_quota_icon() -> Option<(Image<'static>, String)> {
+    use crate::modules::{group_settings, tray_icon};
+
+    let config = crate::modules::config::get_user_config();
+    if !config.tray_quota_icon {
+        return None;
+    }
+    let account = crate::modules::account::get_current_account().ok().flatten()?;
+    let quota = account.quota.as_ref()?;
+
+    let group_id = config.tray_quota_group.trim();
+    let (group_models, scope) = if group_id.is_empty() {
+        (None, None)
+    } else {
+        let settings = group_settings::load_group_settings();
+        (Some(settings.get_models_in_group(group_id)), Some(settings.get_group_name(group_id)))
+    };
+    let lowest = tray_icon::lowest_quota(&quota.models, group_models.as_deref())?;
+
+    let lang = &config.language;
+    let title = match scope {
+        Some(scope) => format!("{} ({})", get_text("lowest_quota", lang), scope),
+        None => get_text("lowest_quota", lang),
+    };
+    let tooltip = format!(
+        "{}\n{}\n{}: {} {}%",
+        DEFAULT_TOOLTIP, account.email, title, lowest.name, lowest.percentage
+    );
+    let icon = Image::new_owned(
+        tray_icon::render_gauge(lowest.percentage),
+        tray_icon::ICON_SIZE,
+        tray_icon::ICON_SIZE,
+    );
+    Some((icon, tooltip))
+}
+
+/// 更新托盘图标与提示（配额或配置变化时调用）
+pub fn update_tray_icon<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<(), String> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    match build_quota_icon() {
+        Some((icon, tooltip)) => {
+            tray.set_icon(Some(icon)).map_err(|e| e.to_string())?;
+            tray.set_tooltip(Some(tooltip)).map_err(|e| e.to_string())?;
+        }
+        None => {
+            tray.set_icon(app.default_window_icon().cloned())
+                .map_err(|e| e.to_string())?;
+            tray.set_tooltip(Some(DEFAULT_TOOLTIP)).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
 /// 获取本地化文本
 fn get_text(key: &str, lang: &str) -> String {
     match (key, lang) {
@@ -409,6 +928,18 @@ fn get_text(key: &str, lang: &str) -> String {
         ("loading", "zh-cn") => "加载中...".to_string(),
         ("reset", "zh-cn") => "重置".to_string(),
         ("reset_done", "zh-cn") => "已重置".to_string(),
+        ("unlimited", "zh-cn") => "不限".to_string(),
+        ("overage", "zh-cn") => "超额".to_string(),
+        ("overage_permitted", "zh-cn") => "允许超额".to_string(),
+        ("quota_exhausted", "zh-cn") => "已耗尽".to_string(),
+        ("quota_low", "zh-cn") => "不足".to_string(),
+        ("code_review", "zh-cn") => "代码审查".to_string(),
+        ("switch_to", "zh-cn") => "切换到…".to_string(),
+        ("no_accounts", "zh-cn") => "暂无账号".to_string(),
+        ("not_selected", "zh-cn") => "未选择".to_string(),
+        ("disabled", "zh-cn") => "已禁用".to_string(),
+        ("forbidden", "zh-cn") => "无权限".to_string(),
+        ("lowest_quota", "zh-cn") => "最低配额".to_string(),
         
         // 繁体中文
         ("show_window", "zh-tw") => "顯示主視窗".to_string(),
@@ -419,6 +950,18 @@ fn get_text(key: &str, lang: &str) -> String {
         ("loading", "zh-tw") => "載入中...".to_string(),
         ("reset", "zh-tw") => "重置".to_string(),
         ("reset_done", "zh-tw") => "已重置".to_string(),
+        ("unlimited", "zh-tw") => "不限".to_string(),
+        ("overage", "zh-tw") => "超額".to_string(),
+        ("overage_permitted", "zh-tw") => "允許超額".to_string(),
+        ("quota_exhausted", "zh-tw") => "已耗盡".to_string(),
+        ("quota_low", "zh-tw") => "不足".to_string(),
+        ("code_review", "zh-tw") => "程式碼審查".to_string(),
+        ("switch_to", "zh-tw") => "切換到…".to_string(),
+        ("no_accounts", "zh-tw") => "暫無帳號".to_string(),
+        ("not_selected", "zh-tw") => "未選擇".to_string(),
+        ("disabled", "zh-tw") => "已停用".to_string(),
+        ("forbidden", "zh-tw") => "無權限".to_string(),
+        ("lowest_quota", "zh-tw") => "最低配額".to_string(),
         
         // 英文
         ("show_window", "en") => "Show Window".to_string(),
@@ -429,6 +972,18 @@ fn get_text(key: &str, lang: &str) -> String {
         ("loading", "en") => "Loading...".to_string(),
         ("reset", "en") => "Reset".to_string(),
         ("reset_done", "en") => "Reset done".to_string(),
+        ("unlimited", "en") => "Unlimited".to_string(),
+        ("overage", "en") => "Overage".to_string(),
+        ("overage_permitted", "en") => "Overage allowed".to_string(),
+        ("quota_exhausted", "en") => "Exhausted".to_string(),
+        ("quota_low", "en") => "Low".to_string(),
+        ("code_review", "en") => "Code review".to_string(),
+        ("switch_to", "en") => "Switch to…".to_string(),
+        ("no_accounts", "en") => "No accounts".to_string(),
+        ("not_selected", "en") => "Not selected".to_string(),
+        ("disabled", "en") => "Disabled".to_string(),
+        ("forbidden", "en") => "Forbidden".to_string(),
+        ("lowest_quota", "en") => "Lowest quota".to_string(),
         
         // 日语
         ("show_window", "ja") => "ウィンドウを表示".to_string(),
@@ -439,6 +994,18 @@ fn get_text(key: &str, lang: &str) -> String {
         ("loading", "ja") => "読み込み中...".to_string(),
         ("reset", "ja") => "リセット".to_string(),
         ("reset_done", "ja") => "リセット済み".to_string(),
+        ("unlimited", "ja") => "無制限".to_string(),
+        ("overage", "ja") => "超過".to_string(),
+        ("overage_permitted", "ja") => "超過利用可".to_string(),
+        ("quota_exhausted", "ja") => "上限到達".to_string(),
+        ("quota_low", "ja") => "残りわずか".to_string(),
+        ("code_review", "ja") => "コードレビュー".to_string(),
+        ("switch_to", "ja") => "切り替え…".to_string(),
+        ("no_accounts", "ja") => "アカウントなし".to_string(),
+        ("not_selected", "ja") => "未選択".to_string(),
+        ("disabled", "ja") => "無効".to_string(),
+        ("forbidden", "ja") => "アクセス拒否".to_string(),
+        ("lowest_quota", "ja") => "最低残量".to_string(),
         
         // 俄语
         ("show_window", "ru") => "Показать окно".to_string(),
@@ -449,6 +1016,18 @@ fn get_text(key: &str, lang: &str) -> String {
         ("loading", "ru") => "Загрузка...".to_string(),
         ("reset", "ru") => "Сброс".to_string(),
         ("reset_done", "ru") => "Сброс выполнен".to_string(),
+        ("unlimited", "ru") => "Без лимита".to_string(),
+        ("overage", "ru") => "Перерасход".to_string(),
+        ("overage_permitted", "ru") => "Перерасход разрешён".to_string(),
+        ("quota_exhausted", "ru") => "Исчерпано".to_string(),
+        ("quota_low", "ru") => "Мало".to_string(),
+        ("code_review", "ru") => "Код-ревью".to_string(),
+        ("switch_to", "ru") => "Переключить на…".to_string(),
+        ("no_accounts", "ru") => "Нет аккаунтов".to_string(),
+        ("not_selected", "ru") => "Не выбран".to_string(),
+        ("disabled", "ru") => "Отключён".to_string(),
+        ("forbidden", "ru") => "Нет доступа".to_string(),
+        ("lowest_quota", "ru") => "Минимальная квота".to_string(),
         
         // 默认英文
         ("show_window", _) => "Show Window".to_string(),
@@ -459,7 +1038,49 @@ fn get_text(key: &str, lang: &str) -> String {
         ("loading", _) => "Loading...".to_string(),
         ("reset", _) => "Reset".to_string(),
         ("reset_done", _) => "Reset done".to_string(),
+        ("unlimited", _) => "Unlimited".to_string(),
+        ("overage", _) => "Overage".to_string(),
+        ("overage_permitted", _) => "Overage allowed".to_string(),
+        ("quota_exhausted", _) => "Exhausted".to_string(),
+        ("quota_low", _) => "Low".to_string(),
+        ("code_review", _) => "Code review".to_string(),
+        ("switch_to", _) => "Switch to…".to_string(),
+        ("no_accounts", _) => "No accounts".to_string(),
+        ("not_selected", _) => "Not selected".to_string(),
+        ("disabled", _) => "Disabled".to_string(),
+        ("forbidden", _) => "Forbidden".to_string(),
+        ("lowest_quota", _) => "Lowest quota".to_string(),
         
         _ => key.to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, remaining: Option<i32>, disabled: bool, forbidden: bool) -> SwitchEntry {
+        SwitchEntry {
+            id: id.to_string(),
+            label: id.to_string(),
+            remaining,
+            current: false,
+            disabled,
+            forbidden,
+        }
+    }
+
+    #[test]
+    fn switch_entries_sorted_by_remaining_quota() {
+        let mut entries = vec![
+            entry("disabled", Some(100), true, false),
+            entry("low", Some(5), false, false),
+            entry("unknown", None, false, false),
+            entry("forbidden", Some(90), false, true),
+            entry("high", Some(80), false, false),
+        ];
+        sort_switch_entries(&mut entries);
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["high", "low", "unknown", "forbidden", "disabled"]);
+    }
+}
@@ -0,0 +1,146 @@
+//! 托盘配额图标
+//!
+//! 按当前账号的最低剩余配额在进程内绘制 RGBA 图标：
+//! - 灰色底环 + 从 12 点方向顺时针的彩色弧，弧长对应剩余百分比
+//! - 中间绘制百分比数字
+//! - 颜色：≥50% 绿色，≥20% 黄色，其余红色
+
+use crate::models::quota::ModelQuota;
+
+/// 图标边长（像素）
+pub const ICON_SIZE: u32 = 64;
+
+const RING_RADIUS: f32 = 27.0;
+const RING_THICKNESS: f32 = 8.0;
+const TRACK_COLOR: [u8; 4] = [128, 128, 128, 110];
+
+/// 3x5 点阵数字
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// 找出剩余配额最低的模型
+///
+/// `group_models` 为 `Some` 时只统计分组内的模型。
+pub fn lowest_quota<'a>(models: &'a [ModelQuota], group_models: Option<&[String]>) -> Option<&'a ModelQuota> {
+    models
+        .iter()
+        .filter(|model| group_models.is_none_or(|ids| ids.iter().any(|id| id == &model.name)))
+        .min_by_key(|model| model.percentage)
+}
+
+/// 按剩余百分比选择颜色
+pub fn gauge_color(percentage: i32) -> [u8; 4] {
+    if percentage >= 50 {
+        [46, 204, 113, 255]
+    } else if percentage >= 20 {
+        [241, 196, 15, 255]
+    } else {
+        [231, 76, 60, 255]
+    }
+}
+
+/// 绘制配额图标，返回 `ICON_SIZE x ICON_SIZE` 的 RGBA 数据
+pub fn render_gauge(percentage: i32) -> Vec<u8> {
+    let percentage = percentage.clamp(0, 100);
+    let size = ICON_SIZE as usize;
+    let mut rgba = vec![0u8; size * size * 4];
+    let color = gauge_color(percentage);
+    let center = ICON_SIZE as f32 / 2.0;
+    let filled = percentage as f32 / 100.0;
+
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f32 + 0.5 - center;
+            let dy = y as f32 + 0.5 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+            // 边缘按覆盖率做简单抗锯齿
+            let coverage = (RING_THICKNESS / 2.0 + 0.5 - (distance - RING_RADIUS).abs()).clamp(0.0, 1.0);
+            if coverage <= 0.0 {
+                continue;
+            }
+            // 从 12 点方向顺时针的角度，归一化到 [0, 1)
+            let angle = dx.atan2(-dy).rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU;
+            let base = if angle < filled { color } else { TRACK_COLOR };
+            let offset = (y * size + x) * 4;
+            rgba[offset..offset + 3].copy_from_slice(&base[..3]);
+            rgba[offset + 3] = (base[3] as f32 * coverage).round() as u8;
+        }
+    }
+
+    draw_number(&mut rgba, percentage as u32, color);
+    rgba
+}
+
+/// 在图标中心绘制数字（三位数时缩小字号以放入环内）
+fn draw_number(rgba: &mut [u8], value: u32, color: [u8; 4]) {
+    let text = value.to_string();
+    let scale: usize = if text.len() >= 3 { 3 } else { 4 };
+    let gap = scale;
+    let glyph_width = 3 * scale;
+    let glyph_height = 5 * scale;
+    let size = ICON_SIZE as usize;
+    let total_width = text.len() * glyph_width + (text.len() - 1) * gap;
+    let left = (size - total_width) / 2;
+    let top = (size - glyph_height) / 2;
+
+    for (index, ch) in text.chars().enumerate() {
+        let Some(digit) = ch.to_digit(10) else {
+            continue;
+        };
+        let origin_x = left + index * (glyph_width + gap);
+        for (row, bits) in DIGITS[digit as usize].iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for py in 0..scale {
+                    for px in 0..scale {
+                        let x = origin_x + col * scale + px;
+                        let y = top + row * scale + py;
+                        let offset = (y * size + x) * 4;
+                        rgba[offset..offset + 4].copy_from_slice(&color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(name: &str, percentage: i32) -> ModelQuota {
+        ModelQuota {
+            name: name.to_string(),
+            percentage,
+            reset_time: String::new(),
+        }
+    }
+
+    #[test]
+    fn lowest_quota_respects_group_and_gauge_renders() {
+        let models = vec![model("gemini-3-flash", 80), model("claude-sonnet-4-5", 12), model("gemini-3-pro-high", 40)];
+        assert_eq!(lowest_quota(&models, None).unwrap().name, "claude-sonnet-4-5");
+
+        let group = vec!["gemini-3-pro-high".to_string(), "gemini-3-flash".to_string()];
+        assert_eq!(lowest_quota(&models, Some(&group)).unwrap().percentage, 40);
+        assert!(lowest_quota(&models, Some(&[])).is_none());
+
+        let rgba = render_gauge(100);
+        assert_eq!(rgba.len(), (ICON_SIZE * ICON_SIZE * 4) as usize);
+        // 12 点方向的环上为彩色弧
+        let top = ((ICON_SIZE / 2 - RING_RADIUS as u32) * ICON_SIZE + ICON_SIZE / 2) as usize * 4;
+        assert_eq!(&rgba[top..top + 4], &gauge_color(100));
+    }
+}
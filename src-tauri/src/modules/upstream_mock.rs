@@ -0,0 +1,276 @@
+//! 测试用上游 mock 服务
+//!
+//! 基于 tiny_http 在本地随机端口回放 `tests/fixtures/upstream` 中录制的响应，
+//! 并通过测试覆盖（不修改进程环境变量）把所有上游地址与数据目录指向它。响应场景由 Bearer token 决定：
+//! - `mock-ok`：返回录制的成功响应
+//! - `mock-forbidden`：403
+//! - `mock-rate-limited`：429
+//! - `mock-malformed`：200 + 无法解析的响应体
+
+use std::sync::{Mutex, OnceLock};
+
+use crate::modules::{config, endpoints};
+
+pub const TOKEN_OK: &str = "mock-ok";
+pub const TOKEN_FORBIDDEN: &str = "mock-forbidden";
+pub const TOKEN_RATE_LIMITED: &str = "mock-rate-limited";
+pub const TOKEN_MALFORMED: &str = "mock-malformed";
+
+const LOAD_CODE_ASSIST: &str = include_str!("../../tests/fixtures/upstream/load_code_assist.json");
+const FETCH_AVAILABLE_MODELS: &str = include_str!("../../tests/fixtures/upstream/fetch_available_models.json");
+const WHAM_USAGE: &str = include_str!("../../tests/fixtures/upstream/wham_usage.json");
+const COPILOT_USER: &str = include_str!("../../tests/fixtures/upstream/copilot_user.json");
+const COPILOT_FORBIDDEN: &str = include_str!("../../tests/fixtures/upstream/copilot_forbidden.json");
+const BILLING_SUBSCRIPTION: &str = include_str!("../../tests/fixtures/upstream/billing_subscription.json");
+const BILLING_USAGE: &str = include_str!("../../tests/fixtures/upstream/billing_usage.json");
+const FORBIDDEN: &str = include_str!("../../tests/fixtures/upstream/forbidden.json");
+const RATE_LIMITED: &str = include_str!("../../tests/fixtures/upstream/rate_limited.json");
+const MALFORMED: &str = "{\"models\": [not valid json";
+
+/// mock 收到的请求
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+static BASE_URL: OnceLock<String> = OnceLock::new();
+static REQUESTS: Mutex<Vec<RecordedRequest>> = Mutex::new(Vec::new());
+
+/// 启动 mock 服务（进程内只启动一次），返回基础地址
+pub fn ensure_started() -> &'static str {
+    BASE_URL.get_or_init(|| {
+        // 隔离数据目录，避免测试写入真实的 ~/.antigravity_cockpit
+        let data_dir = std::env::temp_dir().join(format!("cockpit-tools-test-{}", std::process::id()));
+        std::fs::create_dir_all(&data_dir).expect("create test data dir");
+        config::set_test_data_dir(data_dir);
+
+        let server = tiny_http::Server::http("127.0.0.1:0").expect("start mock upstream");
+        let port = server.server_addr().to_ip().expect("mock upstream addr").port();
+        let base = format!("http://127.0.0.1:{}", port);
+
+        endpoints::set_test_base(&base);
+
+        std::thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                let _ = request.as_reader().read_to_string(&mut body);
+                let recorded = RecordedRequest {
+                    path: request.url().to_string(),
+                    headers: request
+                        .headers()
+                        .iter()
+                        .map(|header| (header.field.to_string(), header.value.to_string()))
+                        .collect(),
+                };
+                let token = recorded
+                    .header("Authorization")
+                    .and_then(|value| value.strip_prefix("Bearer "))
+                    .unwrap_or_default()
+                    .to_string();
+                let (status, payload) = respond(&recorded.path, &token);
+                REQUESTS.lock().expect("mock requests lock").push(recorded);
+
+                let response = tiny_http::Response::from_string(payload)
+                    .with_status_code(status)
+                    .with_header(
+                        tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                            .expect("content type header"),
+                    );
+                let _ = request.respond(response);
+            }
+        });
+
+        base
+    })
+}
+
+/// 按路径筛选 mock 已收到的请求
+pub fn requests_to(path_suffix: &str) -> Vec<RecordedRequest> {
+    REQUESTS
+        .lock()
+        .expect("mock requests lock")
+        .iter()
+        .filter(|request| request.path.ends_with(path_suffix))
+        .cloned()
+        .collect()
+}
+
+fn respond(path: &str, token: &str) -> (u16, &'static str) {
+    let is_copilot = path.ends_with("/copilot_internal/user");
+    match token {
+        TOKEN_FORBIDDEN if is_copilot => (403, COPILOT_FORBIDDEN),
+        TOKEN_FORBIDDEN => (403, FORBIDDEN),
+        TOKEN_RATE_LIMITED => (429, RATE_LIMITED),
+        TOKEN_MALFORMED => (200, MALFORMED),
+        _ if path.ends_with(":loadCodeAssist") => (200, LOAD_CODE_ASSIST),
+        _ if path.ends_with(":fetchAvailableModels") => (200, FETCH_AVAILABLE_MODELS),
+        _ if path.ends_with("/backend-api/wham/usage") => (200, WHAM_USAGE),
+        _ if is_copilot => (200, COPILOT_USER),
+        _ if path.ends_with("/v1/dashboard/billing/subscription") => (200, BILLING_SUBSCRIPTION),
+        _ if path.contains("/v1/dashboard/billing/usage?") => (200, BILLING_USAGE),
+        _ => (404, "{\"error\":\"not found\"}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::codex::{CodexAccount, CodexTokens};
+    use crate::models::api_key::ApiKeyAccount;
+    use crate::modules::{api_key_account, codex_quota, copilot_quota, quota, wakeup};
+
+    fn codex_account(token: &str) -> CodexAccount {
+        let mut account = CodexAccount::new(
+            "codex_mock".to_string(),
+            "mock@example.com".to_string(),
+            CodexTokens {
+                id_token: String::new(),
+                access_token: token.to_string(),
+                refresh_token: None,
+            },
+        );
+        account.account_id = Some("acct-mock".to_string());
+        account
+    }
+
+    #[tokio::test]
+    async fn antigravity_quota_success() {
+        ensure_started();
+        let result = quota::fetch_quota(TOKEN_OK, "ok@example.com", true).await.unwrap();
+        assert!(result.error.is_none());
+        assert_eq!(result.project_id.as_deref(), Some("mock-project-123"));
+        assert_eq!(result.quota.subscription_tier.as_deref(), Some("g1-pro-tier"));
+        assert!(!result.quota.is_forbidden);
+
+        let flash = result.quota.models.iter().find(|m| m.name == "gemini-3-flash").unwrap();
+        assert_eq!(flash.percentage, 80);
+        assert_eq!(flash.reset_time, "2026-01-05T12:00:00Z");
+        // 非 gemini/claude 模型会被过滤
+        assert!(result.quota.models.iter().all(|m| m.name != "chat_20706"));
+    }
+
+    #[tokio::test]
+    async fn antigravity_quota_forbidden() {
+        ensure_started();
+        let result = quota::fetch_quota(TOKEN_FORBIDDEN, "forbidden@example.com", true).await.unwrap();
+        assert!(result.quota.is_forbidden);
+        assert_eq!(result.error.as_ref().and_then(|e| e.code), Some(403));
+    }
+
+    #[tokio::test]
+    async fn antigravity_quota_rate_limited() {
+        ensure_started();
+        let err = quota::fetch_quota(TOKEN_RATE_LIMITED, "limited@example.com", true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("429"));
+    }
+
+    #[tokio::test]
+    async fn antigravity_quota_malformed() {
+        ensure_started();
+        let err = quota::fetch_quota(TOKEN_MALFORMED, "malformed@example.com", true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("API 响应解析失败"));
+    }
+
+    #[tokio::test]
+    async fn available_models_scenarios() {
+        ensure_started();
+        let models = wakeup::fetch_available_models_with_token(TOKEN_OK).await.unwrap();
+        let names: Vec<&str> = models.iter().map(|m| m.display_name.as_str()).collect();
+        assert_eq!(names, vec!["Claude Sonnet 4.5", "Gemini 3 Flash", "Internal Chat"]);
+
+        let err = wakeup::fetch_available_models_with_token(TOKEN_FORBIDDEN).await.unwrap_err();
+        assert_eq!(err, "Cloud Code access forbidden");
+
+        let err = wakeup::fetch_available_models_with_token(TOKEN_RATE_LIMITED).await.unwrap_err();
+        assert!(err.contains("429"));
+
+        let err = wakeup::fetch_available_models_with_token(TOKEN_MALFORMED).await.unwrap_err();
+        assert!(err.contains("解析模型列表失败"));
+    }
+
+    #[tokio::test]
+    async fn codex_usage_scenarios() {
+        ensure_started();
+        let quota = codex_quota::fetch_quota(&codex_account(TOKEN_OK)).await.unwrap();
+        assert_eq!(quota.hourly_percentage, 65);
+        assert_eq!(quota.weekly_percentage, 88);
+        assert_eq!(quota.hourly_reset_time, Some(1767614400));
+        assert_eq!(quota.hourly_window_seconds, Some(18000));
+        assert_eq!(quota.plan_type.as_deref(), Some("plus"));
+        assert!(quota.allowed && !quota.limit_reached);
+        let code_review = quota.code_review.as_ref().unwrap();
+        assert_eq!(code_review.percentage, 100);
+        assert_eq!(code_review.window_seconds, Some(604800));
+        assert!(requests_to("/backend-api/wham/usage")
+            .iter()
+            .any(|req| req.header("ChatGPT-Account-Id") == Some("acct-mock")));
+
+        let err = codex_quota::fetch_quota(&codex_account(TOKEN_FORBIDDEN)).await.unwrap_err();
+        assert!(err.contains("403"));
+        let err = codex_quota::request_quota(&codex_account(TOKEN_FORBIDDEN)).await.unwrap_err();
+        assert_eq!(err.status, Some(reqwest::StatusCode::FORBIDDEN));
+
+        let err = codex_quota::fetch_quota(&codex_account(TOKEN_RATE_LIMITED)).await.unwrap_err();
+        assert!(err.contains("429"));
+
+        let err = codex_quota::fetch_quota(&codex_account(TOKEN_MALFORMED)).await.unwrap_err();
+        assert!(err.contains("解析 JSON 失败"));
+    }
+
+    #[tokio::test]
+    async fn api_key_billing_scenarios() {
+        let base = ensure_started();
+        let account = |key: &str| {
+            ApiKeyAccount::new("apikey_mock".to_string(), "mock".to_string(), format!("{}/v1", base), key.to_string(), None)
+        };
+        let usage = api_key_account::fetch_usage(&account(TOKEN_OK)).await.unwrap();
+        assert_eq!(usage.total_granted, Some(120.0));
+        assert_eq!(usage.total_used, Some(45.5));
+        assert_eq!(usage.remaining, Some(74.5));
+        assert!(requests_to("/v1/dashboard/billing/subscription")
+            .iter()
+            .any(|req| req.header("Authorization") == Some("Bearer mock-ok")));
+
+        let err = api_key_account::fetch_usage(&account(TOKEN_FORBIDDEN)).await.unwrap_err();
+        assert!(err.contains("403"));
+    }
+
+    #[tokio::test]
+    async fn copilot_usage_scenarios() {
+        ensure_started();
+        let quota = copilot_quota::fetch_quota(TOKEN_OK, None).await.unwrap();
+        assert_eq!(quota.included_requests, Some(300));
+        assert_eq!(quota.remaining_requests, Some(120));
+        assert_eq!(quota.used_requests, 180);
+        assert_eq!(quota.copilot_plan.as_deref(), Some("individual"));
+        let ids: Vec<&str> = quota.snapshots.iter().map(|s| s.quota_id.as_str()).collect();
+        assert_eq!(ids, ["premium_interactions", "chat", "completions"]);
+        assert!(quota.snapshots[1].unlimited);
+        assert!(quota.snapshots[0].overage_permitted);
+
+        let err = copilot_quota::fetch_quota(TOKEN_FORBIDDEN, None).await.unwrap_err();
+        assert_eq!(err, "COPILOT_PERMISSION_INTEGRATION");
+        let err = copilot_quota::request_quota(TOKEN_FORBIDDEN, None).await.unwrap_err();
+        assert_eq!(err.status, Some(reqwest::StatusCode::FORBIDDEN));
+
+        let err = copilot_quota::fetch_quota(TOKEN_RATE_LIMITED, None).await.unwrap_err();
+        assert!(err.contains("429"));
+
+        let err = copilot_quota::fetch_quota(TOKEN_MALFORMED, None).await.unwrap_err();
+        assert!(err.contains("解析 Copilot usage 响应失败"));
+    }
+}
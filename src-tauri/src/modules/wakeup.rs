@@ -6,11 +6,6 @@ use std::sync::{Mutex, OnceLock};
 
 use crate::modules;
 
-const CLOUD_CODE_BASE_URLS: [&str; 3] = [
-    "https://daily-cloudcode-pa.googleapis.com",
-    "https://cloudcode-pa.googleapis.com",
-    "https://daily-cloudcode-pa.sandbox.googleapis.com",
-];
 const STREAM_PATH: &str = "/v1internal:streamGenerateContent?alt=sse";
 const FETCH_MODELS_PATH: &str = "/v1internal:fetchAvailableModels";
 const USER_AGENT: &str = "antigravity";
@@ -18,7 +13,8 @@ const ANTIGRAVITY_SYSTEM_PROMPT: &str = "You are Antigravity, a powerful agentic
 const DEFAULT_ATTEMPTS: usize = 2;
 const BACKOFF_BASE_MS: u64 = 500;
 const BACKOFF_MAX_MS: u64 = 4000;
-static BASE_URL_ORDER: OnceLock<Mutex<Vec<&'static str>>> = OnceLock::new();
+/// 最近一次请求成功的基础地址，下次优先尝试
+static PREFERRED_BASE_URL: OnceLock<Mutex<Option<String>>> = OnceLock::new();
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -32,6 +28,34 @@ pub struct WakeupResponse {
     pub duration_ms: u64,
 }
 
+/// 唤醒请求失败
+#[derive(Debug)]
+pub struct WakeupError {
+    /// 最后一次请求的 HTTP 状态码（网络错误、解析失败等情况为空）
+    pub status: Option<reqwest::StatusCode>,
+    pub message: String,
+}
+
+impl WakeupError {
+    /// 是否由 429/5xx 引起（可重试）
+    pub fn is_retryable(&self) -> bool {
+        self.status
+            .is_some_and(|status| status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
+    }
+}
+
+impl From<String> for WakeupError {
+    fn from(message: String) -> Self {
+        Self { status: None, message }
+    }
+}
+
+impl std::fmt::Display for WakeupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
 #[derive(Debug)]
 struct StreamParseResult {
     reply: String,
@@ -120,21 +144,25 @@ fn get_backoff_delay_ms(attempt: usize) -> u64 {
     std::cmp::min(raw + jitter, BACKOFF_MAX_MS)
 }
 
-fn get_base_url_order() -> Vec<&'static str> {
-    let lock = BASE_URL_ORDER.get_or_init(|| Mutex::new(CLOUD_CODE_BASE_URLS.to_vec()));
-    match lock.lock() {
-        Ok(list) => list.clone(),
-        Err(_) => CLOUD_CODE_BASE_URLS.to_vec(),
+fn get_base_url_order() -> Vec<String> {
+    let mut list = modules::endpoints::cloud_code_base_urls();
+    let preferred = PREFERRED_BASE_URL
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone());
+    if let Some(preferred) = preferred {
+        if let Some(pos) = list.iter().position(|item| *item == preferred) {
+            let base = list.remove(pos);
+            list.insert(0, base);
+        }
     }
+    list
 }
 
-fn promote_base_url(base: &'static str) {
-    let lock = BASE_URL_ORDER.get_or_init(|| Mutex::new(CLOUD_CODE_BASE_URLS.to_vec()));
-    if let Ok(mut list) = lock.lock() {
-        if let Some(pos) = list.iter().position(|item| *item == base) {
-            list.remove(pos);
-            list.insert(0, base);
-        }
+fn promote_base_url(base: &str) {
+    if let Ok(mut guard) = PREFERRED_BASE_URL.get_or_init(|| Mutex::new(None)).lock() {
+        *guard = Some(base.to_string());
     }
 }
 
@@ -322,9 +350,10 @@ async fn send_stream_request(
     client: &reqwest::Client,
     access_token: &str,
     body: &serde_json::Value,
-) -> Result<StreamParseResult, String> {
-    let mut last_error: Option<String> = None;
+) -> Result<StreamParseResult, WakeupError> {
+    let mut last_error: Option<WakeupError> = None;
     for base in get_base_url_order() {
+        let base = base.as_str();
         for attempt in 1..=DEFAULT_ATTEMPTS {
             let url = format!("{}{}", base, STREAM_PATH);
             crate::modules::logger::log_info(&format!(
@@ -361,7 +390,7 @@ async fn send_stream_request(
                                 return Ok(parsed);
                             }
                             Err(err) => {
-                                last_error = Some(err.clone());
+                                last_error = Some(WakeupError::from(err.clone()));
                                 crate::modules::logger::log_warn(&format!(
                                     "[Wakeup] 解析响应失败: url={}, error={}",
                                     url, err
@@ -383,17 +412,25 @@ async fn send_stream_request(
                     } else {
                         if status == reqwest::StatusCode::UNAUTHORIZED {
                             crate::modules::logger::log_error("[Wakeup] 授权失效 (401)");
-                            return Err("Authorization expired".to_string());
+                            return Err(WakeupError {
+                                status: Some(status),
+                                message: "Authorization expired".to_string(),
+                            });
                         }
                         if status == reqwest::StatusCode::FORBIDDEN {
                             crate::modules::logger::log_error("[Wakeup] 无权限 (403)");
-                            return Err("Cloud Code access forbidden".to_string());
+                            return Err(WakeupError {
+                                status: Some(status),
+                                message: "Cloud Code access forbidden".to_string(),
+                            });
                         }
                         let text = res.text().await.unwrap_or_default();
-                        let retryable = status == reqwest::StatusCode::TOO_MANY_REQUESTS
-                            || status.as_u16() >= 500;
-                        let message = format!("唤醒请求失败: {} - {}", status, text);
-                        last_error = Some(message.clone());
+                        let error = WakeupError {
+                            status: Some(status),
+                            message: format!("唤醒请求失败: {} - {}", status, text),
+                        };
+                        let retryable = error.is_retryable();
+                        last_error = Some(error);
                         crate::modules::logger::log_warn(&format!(
                             "[Wakeup] 请求失败: url={}, status={}, retryable={}",
                             url, status, retryable
@@ -412,7 +449,7 @@ async fn send_stream_request(
                     }
                 }
                 Err(err) => {
-                    last_error = Some(format!("唤醒请求失败: {}", err));
+                    last_error = Some(WakeupError::from(format!("唤醒请求失败: {}", err)));
                     crate::modules::logger::log_warn(&format!(
                         "[Wakeup] 网络错误: url={}, error={}",
                         url, err
@@ -432,7 +469,7 @@ async fn send_stream_request(
             }
         }
     }
-    Err(last_error.unwrap_or_else(|| "唤醒请求失败".to_string()))
+    Err(last_error.unwrap_or_else(|| WakeupError::from("唤醒请求失败".to_string())))
 }
 
 /// 触发单个账号的唤醒请求
@@ -441,7 +478,7 @@ pub async fn trigger_wakeup(
     model: &str,
     prompt: &str,
     max_output_tokens: u32,
-) -> Result<WakeupResponse, String> {
+) -> Result<WakeupResponse, WakeupError> {
     let mut account = modules::load_account(account_id)?;
     crate::modules::logger::log_info(&format!(
         "[Wakeup] 开始唤醒: email={}, model={}, max_tokens={}, prompt={}",
@@ -544,17 +581,22 @@ pub async fn fetch_available_models() -> Result<Vec<AvailableModel>, String> {
         let _ = modules::save_account(&updated);
     }
 
+    fetch_available_models_with_token(&token.access_token).await
+}
+
+/// 使用指定 access token 获取可用模型列表
+pub async fn fetch_available_models_with_token(access_token: &str) -> Result<Vec<AvailableModel>, String> {
     let payload = json!({});
 
     let client = crate::utils::http::create_client(15);
     let mut last_error: Option<String> = None;
     let mut data: Option<AvailableModelsResponse> = None;
-    'outer: for base in CLOUD_CODE_BASE_URLS {
+    'outer: for base in modules::endpoints::cloud_code_base_urls() {
         for attempt in 1..=DEFAULT_ATTEMPTS {
             let url = format!("{}{}", base, FETCH_MODELS_PATH);
             let response = client
                 .post(url)
-                .bearer_auth(&token.access_token)
+                .bearer_auth(access_token)
                 .header(reqwest::header::USER_AGENT, USER_AGENT)
                 .header(reqwest::header::CONTENT_TYPE, "application/json")
                 .header(reqwest::header::ACCEPT_ENCODING, "gzip")
@@ -0,0 +1,369 @@
+//! Crontab 表达式解析
+//!
+//! 支持标准 5 段格式（分 时 日 月 周）：
+//! - `*`、`?`、列表 `1,2,3`、范围 `1-5`、步长 `*/15`、`10-50/10`、`5/20`
+//! - 月份/星期名称（`JAN`-`DEC`、`SUN`-`SAT`），星期 `7` 等同于周日
+//! - 日期字段：`L`（月末）、`L-3`（月末前 3 天）、`15W`（最近工作日）、`LW`（月末最后工作日）
+//! - 星期字段：`5L`（当月最后一个周五）、`1#2`（当月第二个周一）
+//! - 宏：`@yearly`、`@annually`、`@monthly`、`@weekly`、`@daily`、`@midnight`、`@hourly`
+//!
+//! 日期与星期同时受限时按标准 cron 语义取并集。
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+/// 向后搜索的最大天数（覆盖 2 月 29 日这类低频表达式）
+const SEARCH_DAYS: i64 = 366 * 8;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DayOfMonthItem {
+    Day(u32),
+    /// 月末前 N 天（`L` 为 0）
+    Last(u32),
+    NearestWeekday(u32),
+    LastWeekday,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DayOfWeekItem {
+    Day(u32),
+    /// 当月最后一个星期 N
+    LastOf(u32),
+    /// 当月第 K 个星期 N
+    Nth(u32, u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    months: Vec<u32>,
+    days_of_month: Option<Vec<DayOfMonthItem>>,
+    days_of_week: Option<Vec<DayOfWeekItem>>,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        let expanded = expand_macro(expr)?;
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Crontab 表达式需要 5 个字段（分 时 日 月 周），实际为 {} 个",
+                fields.len()
+            ));
+        }
+
+        let minutes = parse_numeric_field(fields[0], 0, 59, &[]).map_err(|e| field_error("分钟", e))?;
+        let hours = parse_numeric_field(fields[1], 0, 23, &[]).map_err(|e| field_error("小时", e))?;
+        let days_of_month = parse_day_of_month(fields[2]).map_err(|e| field_error("日期", e))?;
+        let months = parse_numeric_field(fields[3], 1, 12, &MONTH_NAMES).map_err(|e| field_error("月份", e))?;
+        let days_of_week = parse_day_of_week(fields[4]).map_err(|e| field_error("星期", e))?;
+
+        Ok(Self {
+            minutes,
+            hours,
+            months,
+            days_of_month,
+            days_of_week,
+        })
+    }
+
+    /// 返回严格晚于 `after` 的下一个触发时间（按分钟对齐）
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let start_date = start.date();
+
+        for day_offset in 0..SEARCH_DAYS {
+            let date = start_date + Duration::days(day_offset);
+            if !self.matches_date(date) {
+                continue;
+            }
+            for hour in &self.hours {
+                for minute in &self.minutes {
+                    let candidate = date.and_hms_opt(*hour, *minute, 0)?;
+                    if candidate >= start {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+
+        let dom_match = self
+            .days_of_month
+            .as_ref()
+            .map(|items| items.iter().any(|item| matches_day_of_month(item, date)));
+        let dow_match = self
+            .days_of_week
+            .as_ref()
+            .map(|items| items.iter().any(|item| matches_day_of_week(item, date)));
+
+        match (dom_match, dow_match) {
+            (None, None) => true,
+            (Some(dom), None) => dom,
+            (None, Some(dow)) => dow,
+            (Some(dom), Some(dow)) => dom || dow,
+        }
+    }
+}
+
+fn field_error(name: &str, error: String) -> String {
+    format!("Crontab {}字段无效: {}", name, error)
+}
+
+fn expand_macro(expr: &str) -> Result<String, String> {
+    if !expr.starts_with('@') {
+        return Ok(expr.to_string());
+    }
+    let expanded = match expr.to_ascii_lowercase().as_str() {
+        "@yearly" | "@annually" => "0 0 1 1 *",
+        "@monthly" => "0 0 1 * *",
+        "@weekly" => "0 0 * * 0",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@hourly" => "0 * * * *",
+        _ => return Err(format!("不支持的 Crontab 宏: {}", expr)),
+    };
+    Ok(expanded.to_string())
+}
+
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    let upper = value.to_ascii_uppercase();
+    if let Some(index) = names.iter().position(|name| *name == upper) {
+        return Ok(index as u32 + min);
+    }
+    let parsed: u32 = value.parse().map_err(|_| format!("无法解析 \"{}\"", value))?;
+    if parsed < min || parsed > max {
+        return Err(format!("{} 超出范围 {}-{}", parsed, min, max));
+    }
+    Ok(parsed)
+}
+
+fn parse_numeric_part(part: &str, min: u32, max: u32, names: &[&str]) -> Result<Vec<u32>, String> {
+    let (range, step) = match part.split_once('/') {
+        Some((range, step)) => {
+            let step: u32 = step.parse().map_err(|_| format!("步长无效 \"{}\"", step))?;
+            if step == 0 {
+                return Err("步长不能为 0".to_string());
+            }
+            (range, Some(step))
+        }
+        None => (part, None),
+    };
+
+    let (start, end) = if range == "*" {
+        (min, max)
+    } else if let Some((start, end)) = range.split_once('-') {
+        let start = parse_value(start, min, max, names)?;
+        let end = parse_value(end, min, max, names)?;
+        if end < start {
+            return Err(format!("范围 \"{}\" 起始值大于结束值", range));
+        }
+        (start, end)
+    } else {
+        let start = parse_value(range, min, max, names)?;
+        // `5/20` 表示从 5 开始每 20 个单位
+        (start, if step.is_some() { max } else { start })
+    };
+
+    Ok((start..=end).step_by(step.unwrap_or(1) as usize).collect())
+}
+
+fn parse_numeric_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<Vec<u32>, String> {
+    if field.is_empty() {
+        return Err("字段为空".to_string());
+    }
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        values.extend(parse_numeric_part(part, min, max, names)?);
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+fn parse_day_of_month(field: &str) -> Result<Option<Vec<DayOfMonthItem>>, String> {
+    if field == "*" || field == "?" {
+        return Ok(None);
+    }
+    let mut items = Vec::new();
+    for part in field.split(',') {
+        let upper = part.to_ascii_uppercase();
+        if upper == "L" {
+            items.push(DayOfMonthItem::Last(0));
+        } else if upper == "LW" {
+            items.push(DayOfMonthItem::LastWeekday);
+        } else if let Some(offset) = upper.strip_prefix("L-") {
+            let offset = parse_value(offset, 1, 30, &[])?;
+            items.push(DayOfMonthItem::Last(offset));
+        } else if let Some(day) = upper.strip_suffix('W') {
+            let day = parse_value(day, 1, 31, &[])?;
+            items.push(DayOfMonthItem::NearestWeekday(day));
+        } else {
+            items.extend(
+                parse_numeric_part(part, 1, 31, &[])?
+                    .into_iter()
+                    .map(DayOfMonthItem::Day),
+            );
+        }
+    }
+    Ok(Some(items))
+}
+
+fn parse_weekday(value: &str) -> Result<u32, String> {
+    // 7 与 0 均表示周日
+    parse_value(value, 0, 7, &WEEKDAY_NAMES).map(|day| day % 7)
+}
+
+fn parse_day_of_week(field: &str) -> Result<Option<Vec<DayOfWeekItem>>, String> {
+    if field == "*" || field == "?" {
+        return Ok(None);
+    }
+    let mut items = Vec::new();
+    for part in field.split(',') {
+        let upper = part.to_ascii_uppercase();
+        if let Some((day, nth)) = upper.split_once('#') {
+            let day = parse_weekday(day)?;
+            let nth = parse_value(nth, 1, 5, &[])?;
+            items.push(DayOfWeekItem::Nth(day, nth));
+        } else if upper.len() > 1 && upper.ends_with('L') {
+            let day = parse_weekday(&upper[..upper.len() - 1])?;
+            items.push(DayOfWeekItem::LastOf(day));
+        } else {
+            items.extend(
+                parse_numeric_part(part, 0, 7, &WEEKDAY_NAMES)?
+                    .into_iter()
+                    .map(|day| DayOfWeekItem::Day(day % 7)),
+            );
+        }
+    }
+    Ok(Some(items))
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|date| date.pred_opt())
+        .map(|date| date.day())
+        .unwrap_or(28)
+}
+
+fn weekday_of(year: i32, month: u32, day: u32) -> Option<u32> {
+    NaiveDate::from_ymd_opt(year, month, day).map(|date| date.weekday().num_days_from_sunday())
+}
+
+/// 距离指定日期最近的工作日（不跨月）
+fn nearest_weekday(year: i32, month: u32, day: u32) -> Option<u32> {
+    let last_day = days_in_month(year, month);
+    if day > last_day {
+        return None;
+    }
+    match weekday_of(year, month, day)? {
+        6 if day == 1 => Some(3),
+        6 => Some(day - 1),
+        0 if day == last_day => Some(day - 2),
+        0 => Some(day + 1),
+        _ => Some(day),
+    }
+}
+
+fn matches_day_of_month(item: &DayOfMonthItem, date: NaiveDate) -> bool {
+    let (year, month, day) = (date.year(), date.month(), date.day());
+    let last_day = days_in_month(year, month);
+    match item {
+        DayOfMonthItem::Day(value) => day == *value,
+        DayOfMonthItem::Last(offset) => last_day.checked_sub(*offset) == Some(day),
+        DayOfMonthItem::NearestWeekday(value) => nearest_weekday(year, month, *value) == Some(day),
+        DayOfMonthItem::LastWeekday => {
+            let target = match weekday_of(year, month, last_day) {
+                Some(6) => last_day - 1,
+                Some(0) => last_day - 2,
+                _ => last_day,
+            };
+            day == target
+        }
+    }
+}
+
+fn matches_day_of_week(item: &DayOfWeekItem, date: NaiveDate) -> bool {
+    let weekday = date.weekday().num_days_from_sunday();
+    match item {
+        DayOfWeekItem::Day(value) => weekday == *value,
+        DayOfWeekItem::LastOf(value) => {
+            weekday == *value && date.day() + 7 > days_in_month(date.year(), date.month())
+        }
+        DayOfWeekItem::Nth(value, nth) => weekday == *value && (date.day() - 1) / 7 + 1 == *nth,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    fn next(expr: &str, after: NaiveDateTime) -> NaiveDateTime {
+        CronSchedule::parse(expr).unwrap().next_after(after).unwrap()
+    }
+
+    #[test]
+    fn supports_steps_ranges_and_lists() {
+        let after = at(2026, 1, 5, 9, 7);
+        assert_eq!(next("*/15 * * * *", after), at(2026, 1, 5, 9, 15));
+        assert_eq!(next("10-50/20 9 * * *", after), at(2026, 1, 5, 9, 10));
+        assert_eq!(next("5/20 * * * *", at(2026, 1, 5, 9, 45)), at(2026, 1, 5, 10, 5));
+        assert_eq!(next("0 8,20 * * *", after), at(2026, 1, 5, 20, 0));
+    }
+
+    #[test]
+    fn supports_names_and_macros() {
+        // 2026-01-05 为周一
+        let after = at(2026, 1, 5, 9, 0);
+        assert_eq!(next("0 9 * * MON-FRI", after), at(2026, 1, 6, 9, 0));
+        assert_eq!(next("0 9 * * 7", after), at(2026, 1, 11, 9, 0));
+        assert_eq!(next("0 0 1 mar *", after), at(2026, 3, 1, 0, 0));
+        assert_eq!(next("@daily", after), at(2026, 1, 6, 0, 0));
+        assert_eq!(next("@hourly", after), at(2026, 1, 5, 10, 0));
+    }
+
+    #[test]
+    fn supports_last_and_nearest_weekday() {
+        let after = at(2026, 1, 5, 0, 0);
+        assert_eq!(next("0 0 L * *", after), at(2026, 1, 31, 0, 0));
+        assert_eq!(next("0 0 L 2 *", after), at(2026, 2, 28, 0, 0));
+        assert_eq!(next("0 0 L-1 1 *", after), at(2026, 1, 30, 0, 0));
+        // 2026-02-28 为周六 → 最近工作日 2 月 27 日
+        assert_eq!(next("0 0 LW 2 *", after), at(2026, 2, 27, 0, 0));
+        // 2026-03-01 为周日 → 1W 落在 3 月 2 日
+        assert_eq!(next("0 0 1W 3 *", after), at(2026, 3, 2, 0, 0));
+        assert_eq!(next("0 0 * * 5L", after), at(2026, 1, 30, 0, 0));
+        assert_eq!(next("0 0 * * MON#2", after), at(2026, 1, 12, 0, 0));
+    }
+
+    #[test]
+    fn day_of_month_and_week_are_unioned() {
+        let after = at(2026, 1, 5, 0, 0);
+        assert_eq!(next("0 0 15 * SUN", after), at(2026, 1, 11, 0, 0));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 * FOO *").is_err());
+        assert!(CronSchedule::parse("@reboot").is_err());
+    }
+}
@@ -1,11 +1,18 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use crate::modules;
 
-const HISTORY_FILE: &str = "wakeup_history.json";
+pub const HISTORY_DB_FILE: &str = "wakeup_history.db";
+/// 旧版 JSON 历史文件，首次打开数据库时迁移
+const LEGACY_HISTORY_FILE: &str = "wakeup_history.json";
+/// 前端历史列表默认返回的数量
 const MAX_HISTORY_ITEMS: usize = 100;
+/// 数据库最多保留的记录数，超出后删除最旧的记录
+const MAX_STORED_ITEMS: i64 = 50_000;
 
 static HISTORY_LOCK: std::sync::LazyLock<Mutex<()>> = std::sync::LazyLock::new(|| Mutex::new(()));
 
@@ -16,6 +23,8 @@ pub struct WakeupHistoryItem {
     pub timestamp: i64,
     pub trigger_type: String,
     pub trigger_source: String,
+    #[serde(default)]
+    pub task_id: Option<String>,
     pub task_name: Option<String>,
     pub account_email: String,
     pub model_id: String,
@@ -23,86 +32,567 @@ pub struct WakeupHistoryItem {
     pub success: bool,
     pub message: Option<String>,
     pub duration: Option<u64>,
+    #[serde(default)]
+    pub prompt_tokens: Option<u32>,
+    #[serde(default)]
+    pub completion_tokens: Option<u32>,
+    #[serde(default)]
+    pub total_tokens: Option<u32>,
 }
 
-fn history_path() -> Result<PathBuf, String> {
+/// 历史查询条件（字段均为可选，时间范围为毫秒时间戳，闭区间）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WakeupHistoryFilter {
+    pub account_email: Option<String>,
+    pub model_id: Option<String>,
+    pub task_id: Option<String>,
+    pub task_name: Option<String>,
+    pub trigger_source: Option<String>,
+    pub success: Option<bool>,
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// 单个维度的统计结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeupStatsBucket {
+    pub key: String,
+    pub label: String,
+    pub total: u64,
+    pub success_count: u64,
+    pub failure_count: u64,
+    pub success_rate: f64,
+    pub median_duration: Option<u64>,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeupHistoryStats {
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub overall: WakeupStatsBucket,
+    pub by_task: Vec<WakeupStatsBucket>,
+    pub by_account: Vec<WakeupStatsBucket>,
+}
+
+fn history_db_path() -> Result<PathBuf, String> {
     let data_dir = modules::account::get_data_dir()?;
-    Ok(data_dir.join(HISTORY_FILE))
+    Ok(data_dir.join(HISTORY_DB_FILE))
+}
+
+/// 打开历史数据库（调用方需持有 HISTORY_LOCK）
+fn open_db() -> Result<Connection, String> {
+    open_db_in(&modules::account::get_data_dir()?)
+}
+
+/// 打开指定数据目录下的历史数据库，并迁移该目录中的旧版 JSON 历史
+fn open_db_in(data_dir: &Path) -> Result<Connection, String> {
+    let path = data_dir.join(HISTORY_DB_FILE);
+    let conn = Connection::open(&path).map_err(|e| format!("打开唤醒历史数据库失败: {}", e))?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS wakeup_history (
+            id TEXT PRIMARY KEY,
+            timestamp INTEGER NOT NULL,
+            trigger_type TEXT NOT NULL,
+            trigger_source TEXT NOT NULL,
+            task_id TEXT,
+            task_name TEXT,
+            account_email TEXT NOT NULL,
+            model_id TEXT NOT NULL,
+            prompt TEXT,
+            success INTEGER NOT NULL,
+            message TEXT,
+            duration INTEGER,
+            prompt_tokens INTEGER,
+            completion_tokens INTEGER,
+            total_tokens INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS idx_wakeup_history_timestamp ON wakeup_history(timestamp);
+        CREATE INDEX IF NOT EXISTS idx_wakeup_history_account ON wakeup_history(account_email);
+        CREATE INDEX IF NOT EXISTS idx_wakeup_history_task ON wakeup_history(task_id);",
+    )
+    .map_err(|e| format!("初始化唤醒历史数据库失败: {}", e))?;
+    migrate_legacy_history(&conn, data_dir);
+    Ok(conn)
+}
+
+/// 将旧版 JSON 历史导入数据库，成功后重命名旧文件
+fn migrate_legacy_history(conn: &Connection, data_dir: &Path) {
+    let legacy_path = data_dir.join(LEGACY_HISTORY_FILE);
+    if !legacy_path.exists() {
+        return;
+    }
+
+    let items: Vec<WakeupHistoryItem> = match fs::read_to_string(&legacy_path) {
+        Ok(content) if content.trim().is_empty() => Vec::new(),
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(items) => items,
+            Err(e) => {
+                modules::logger::log_warn(&format!("[Wakeup] 解析旧版唤醒历史失败，跳过迁移: {}", e));
+                return;
+            }
+        },
+        Err(e) => {
+            modules::logger::log_warn(&format!("[Wakeup] 读取旧版唤醒历史失败: {}", e));
+            return;
+        }
+    };
+
+    if let Err(e) = insert_items(conn, &items) {
+        modules::logger::log_warn(&format!("[Wakeup] 迁移旧版唤醒历史失败: {}", e));
+        return;
+    }
+    let migrated_path = data_dir.join(format!("{}.migrated", LEGACY_HISTORY_FILE));
+    if let Err(e) = fs::rename(&legacy_path, migrated_path) {
+        modules::logger::log_warn(&format!("[Wakeup] 重命名旧版唤醒历史失败: {}", e));
+        return;
+    }
+    modules::logger::log_info(&format!("[Wakeup] 已迁移 {} 条唤醒历史到数据库", items.len()));
+}
+
+fn insert_items(conn: &Connection, items: &[WakeupHistoryItem]) -> Result<usize, String> {
+    let mut inserted = 0;
+    let mut stmt = conn
+        .prepare(
+            "INSERT OR IGNORE INTO wakeup_history (
+                id, timestamp, trigger_type, trigger_source, task_id, task_name, account_email,
+                model_id, prompt, success, message, duration, prompt_tokens, completion_tokens, total_tokens
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        )
+        .map_err(|e| format!("准备写入唤醒历史失败: {}", e))?;
+    for item in items {
+        inserted += stmt
+            .execute(params![
+                item.id,
+                item.timestamp,
+                item.trigger_type,
+                item.trigger_source,
+                item.task_id,
+                item.task_name,
+                item.account_email,
+                item.model_id,
+                item.prompt,
+                item.success,
+                item.message,
+                item.duration.map(|value| value as i64),
+                item.prompt_tokens,
+                item.completion_tokens,
+                item.total_tokens,
+            ])
+            .map_err(|e| format!("写入唤醒历史失败: {}", e))?;
+    }
+    Ok(inserted)
+}
+
+fn row_to_item(row: &rusqlite::Row) -> rusqlite::Result<WakeupHistoryItem> {
+    Ok(WakeupHistoryItem {
+        id: row.get("id")?,
+        timestamp: row.get("timestamp")?,
+        trigger_type: row.get("trigger_type")?,
+        trigger_source: row.get("trigger_source")?,
+        task_id: row.get("task_id")?,
+        task_name: row.get("task_name")?,
+        account_email: row.get("account_email")?,
+        model_id: row.get("model_id")?,
+        prompt: row.get("prompt")?,
+        success: row.get("success")?,
+        message: row.get("message")?,
+        duration: row.get::<_, Option<i64>>("duration")?.map(|value| value.max(0) as u64),
+        prompt_tokens: row.get("prompt_tokens")?,
+        completion_tokens: row.get("completion_tokens")?,
+        total_tokens: row.get("total_tokens")?,
+    })
 }
 
-/// 加载唤醒历史记录
+/// 根据过滤条件构建 WHERE 子句
+fn build_where(filter: &WakeupHistoryFilter) -> (String, Vec<Value>) {
+    let mut clauses: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(email) = filter.account_email.as_ref().filter(|v| !v.is_empty()) {
+        clauses.push("account_email = ? COLLATE NOCASE");
+        values.push(Value::Text(email.clone()));
+    }
+    if let Some(model_id) = filter.model_id.as_ref().filter(|v| !v.is_empty()) {
+        clauses.push("model_id = ?");
+        values.push(Value::Text(model_id.clone()));
+    }
+    if let Some(task_id) = filter.task_id.as_ref().filter(|v| !v.is_empty()) {
+        clauses.push("task_id = ?");
+        values.push(Value::Text(task_id.clone()));
+    }
+    if let Some(task_name) = filter.task_name.as_ref().filter(|v| !v.is_empty()) {
+        clauses.push("task_name = ?");
+        values.push(Value::Text(task_name.clone()));
+    }
+    if let Some(source) = filter.trigger_source.as_ref().filter(|v| !v.is_empty()) {
+        clauses.push("trigger_source = ?");
+        values.push(Value::Text(source.clone()));
+    }
+    if let Some(success) = filter.success {
+        clauses.push("success = ?");
+        values.push(Value::Integer(success as i64));
+    }
+    if let Some(start) = filter.start_time {
+        clauses.push("timestamp >= ?");
+        values.push(Value::Integer(start));
+    }
+    if let Some(end) = filter.end_time {
+        clauses.push("timestamp <= ?");
+        values.push(Value::Integer(end));
+    }
+
+    if clauses.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!(" WHERE {}", clauses.join(" AND ")), values)
+    }
+}
+
+fn select_items(conn: &Connection, filter: &WakeupHistoryFilter, limit: Option<usize>) -> Result<Vec<WakeupHistoryItem>, String> {
+    let (where_sql, mut values) = build_where(filter);
+    let mut sql = format!("SELECT * FROM wakeup_history{} ORDER BY timestamp DESC, id DESC", where_sql);
+    if let Some(limit) = limit {
+        sql.push_str(" LIMIT ? OFFSET ?");
+        values.push(Value::Integer(limit as i64));
+        values.push(Value::Integer(filter.offset.unwrap_or(0) as i64));
+    }
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("查询唤醒历史失败: {}", e))?;
+    let rows = stmt
+        .query_map(params_from_iter(values), row_to_item)
+        .map_err(|e| format!("查询唤醒历史失败: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("解析唤醒历史失败: {}", e))
+}
+
+/// 加载唤醒历史记录（最新的 MAX_HISTORY_ITEMS 条）
 pub fn load_history() -> Result<Vec<WakeupHistoryItem>, String> {
-    let path = history_path()?;
-    
-    if !path.exists() {
-        return Ok(Vec::new());
-    }
-    
-    let content = fs::read_to_string(&path)
-        .map_err(|e| format!("读取唤醒历史失败: {}", e))?;
-    
-    if content.trim().is_empty() {
-        return Ok(Vec::new());
-    }
-    
-    let items: Vec<WakeupHistoryItem> = serde_json::from_str(&content)
-        .map_err(|e| format!("解析唤醒历史失败: {}", e))?;
-    
-    Ok(items)
-}
-
-/// 保存唤醒历史记录
-fn save_history(items: &[WakeupHistoryItem]) -> Result<(), String> {
-    let path = history_path()?;
-    let data_dir = modules::account::get_data_dir()?;
-    let temp_path = data_dir.join(format!("{}.tmp", HISTORY_FILE));
-    
-    let content = serde_json::to_string_pretty(items)
-        .map_err(|e| format!("序列化唤醒历史失败: {}", e))?;
-    
-    fs::write(&temp_path, content)
-        .map_err(|e| format!("写入临时历史文件失败: {}", e))?;
-    
-    fs::rename(temp_path, path)
-        .map_err(|e| format!("替换历史文件失败: {}", e))
-}
-
-/// 添加历史记录（自动去重、限制数量）
+    query_history(WakeupHistoryFilter {
+        limit: Some(MAX_HISTORY_ITEMS),
+        ..Default::default()
+    })
+}
+
+/// 按条件查询唤醒历史
+pub fn query_history(filter: WakeupHistoryFilter) -> Result<Vec<WakeupHistoryItem>, String> {
+    let _lock = HISTORY_LOCK.lock().map_err(|_| "获取历史锁失败")?;
+    let conn = open_db()?;
+    select_items(&conn, &filter, Some(filter.limit.unwrap_or(MAX_HISTORY_ITEMS)))
+}
+
+/// 添加历史记录（按 ID 去重，超出上限时删除最旧记录）
 pub fn add_history_items(new_items: Vec<WakeupHistoryItem>) -> Result<(), String> {
     if new_items.is_empty() {
         return Ok(());
     }
-    
+
     let _lock = HISTORY_LOCK.lock().map_err(|_| "获取历史锁失败")?;
-    
-    let mut existing = load_history().unwrap_or_default();
-    
-    // 去重：根据 ID 过滤已存在的记录
-    let existing_ids: std::collections::HashSet<String> = existing.iter().map(|item| item.id.clone()).collect();
-    let filtered_new: Vec<WakeupHistoryItem> = new_items
-        .into_iter()
-        .filter(|item| !existing_ids.contains(&item.id))
-        .collect();
-    
-    if filtered_new.is_empty() {
-        return Ok(());
+    let mut conn = open_db()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("开启唤醒历史事务失败: {}", e))?;
+    insert_items(&tx, &new_items)?;
+    prune_history(&tx, MAX_STORED_ITEMS)?;
+    tx.commit().map_err(|e| format!("提交唤醒历史失败: {}", e))
+}
+
+/// 只保留最新的 `max_items` 条记录（与截断点时间戳相同的记录一并保留）
+fn prune_history(conn: &Connection, max_items: i64) -> Result<(), String> {
+    let cutoff: Option<i64> = conn
+        .query_row(
+            "SELECT timestamp FROM wakeup_history ORDER BY timestamp DESC LIMIT 1 OFFSET ?1",
+            params![max_items - 1],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("清理唤醒历史失败: {}", e))?;
+    if let Some(cutoff) = cutoff {
+        conn.execute("DELETE FROM wakeup_history WHERE timestamp < ?1", params![cutoff])
+            .map_err(|e| format!("清理唤醒历史失败: {}", e))?;
     }
-    
-    // 新记录放前面
-    let mut merged = filtered_new;
-    merged.append(&mut existing);
-    
-    // 按时间排序（最新的在前）
-    merged.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-    
-    // 限制数量
-    merged.truncate(MAX_HISTORY_ITEMS);
-    
-    save_history(&merged)
+    Ok(())
 }
 
 /// 清空历史记录
 pub fn clear_history() -> Result<(), String> {
     let _lock = HISTORY_LOCK.lock().map_err(|_| "获取历史锁失败")?;
-    save_history(&[])
+    let conn = open_db()?;
+    conn.execute("DELETE FROM wakeup_history", [])
+        .map_err(|e| format!("清空唤醒历史失败: {}", e))?;
+    Ok(())
+}
+
+const HISTORY_COLUMNS: &str = "id, timestamp, trigger_type, trigger_source, task_id, task_name, \
+    account_email, model_id, prompt, success, message, duration, prompt_tokens, completion_tokens, total_tokens";
+
+/// 导出数据库的一致性快照（用于备份）
+pub fn export_snapshot(dest: &Path) -> Result<(), String> {
+    let _lock = HISTORY_LOCK.lock().map_err(|_| "获取历史锁失败")?;
+    let conn = open_db()?;
+    if dest.exists() {
+        fs::remove_file(dest).map_err(|e| format!("清理快照文件失败: {}", e))?;
+    }
+    conn.execute("VACUUM INTO ?1", params![dest.to_string_lossy()])
+        .map_err(|e| format!("导出唤醒历史快照失败: {}", e))?;
+    Ok(())
+}
+
+/// 合并另一个历史数据库中的记录（按 ID 去重），返回新增条数
+pub fn merge_from(source: &Path) -> Result<usize, String> {
+    let _lock = HISTORY_LOCK.lock().map_err(|_| "获取历史锁失败")?;
+    let conn = open_db()?;
+    conn.execute("ATTACH DATABASE ?1 AS incoming", params![source.to_string_lossy()])
+        .map_err(|e| format!("打开备份历史数据库失败: {}", e))?;
+    let result = conn.execute(
+        &format!(
+            "INSERT OR IGNORE INTO wakeup_history ({cols}) SELECT {cols} FROM incoming.wakeup_history",
+            cols = HISTORY_COLUMNS
+        ),
+        [],
+    );
+    let _ = conn.execute("DETACH DATABASE incoming", []);
+    result.map_err(|e| format!("合并唤醒历史失败: {}", e))
+}
+
+/// 用另一个数据库文件替换当前历史数据库
+pub fn replace_with(source: &Path) -> Result<(), String> {
+    let _lock = HISTORY_LOCK.lock().map_err(|_| "获取历史锁失败")?;
+    let path = history_db_path()?;
+    let temp_path = path.with_extension("db.tmp");
+    fs::copy(source, &temp_path).map_err(|e| format!("写入唤醒历史数据库失败: {}", e))?;
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let _ = fs::remove_file(format!("{}{}", path.to_string_lossy(), suffix));
+    }
+    fs::rename(&temp_path, &path).map_err(|e| format!("替换唤醒历史数据库失败: {}", e))
+}
+
+fn median(values: &mut [u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some((values[mid - 1] + values[mid]) / 2)
+    } else {
+        Some(values[mid])
+    }
+}
+
+fn build_bucket(key: String, label: String, items: &[&WakeupHistoryItem]) -> WakeupStatsBucket {
+    let total = items.len() as u64;
+    let success_count = items.iter().filter(|item| item.success).count() as u64;
+    let mut durations: Vec<u64> = items.iter().filter_map(|item| item.duration).collect();
+    let sum = |pick: fn(&WakeupHistoryItem) -> Option<u32>| -> u64 {
+        items.iter().filter_map(|item| pick(item)).map(u64::from).sum()
+    };
+
+    WakeupStatsBucket {
+        key,
+        label,
+        total,
+        success_count,
+        failure_count: total - success_count,
+        success_rate: if total == 0 { 0.0 } else { success_count as f64 / total as f64 },
+        median_duration: median(&mut durations),
+        prompt_tokens: sum(|item| item.prompt_tokens),
+        completion_tokens: sum(|item| item.completion_tokens),
+        total_tokens: sum(|item| item.total_tokens),
+    }
+}
+
+fn group_buckets<F>(items: &[WakeupHistoryItem], key_of: F) -> Vec<WakeupStatsBucket>
+where
+    F: Fn(&WakeupHistoryItem) -> (String, String),
+{
+    let mut groups: HashMap<String, (String, Vec<&WakeupHistoryItem>)> = HashMap::new();
+    for item in items {
+        let (key, label) = key_of(item);
+        groups.entry(key).or_insert_with(|| (label, Vec::new())).1.push(item);
+    }
+    let mut buckets: Vec<WakeupStatsBucket> = groups
+        .into_iter()
+        .map(|(key, (label, group))| build_bucket(key, label, &group))
+        .collect();
+    buckets.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.key.cmp(&b.key)));
+    buckets
+}
+
+/// 统计唤醒历史（按任务、按账号），过滤条件中的分页参数会被忽略
+pub fn history_stats(filter: WakeupHistoryFilter) -> Result<WakeupHistoryStats, String> {
+    let items = {
+        let _lock = HISTORY_LOCK.lock().map_err(|_| "获取历史锁失败")?;
+        let conn = open_db()?;
+        select_items(&conn, &filter, None)?
+    };
+    Ok(build_stats(&items, &filter))
+}
+
+fn build_stats(items: &[WakeupHistoryItem], filter: &WakeupHistoryFilter) -> WakeupHistoryStats {
+    let all: Vec<&WakeupHistoryItem> = items.iter().collect();
+    let by_task = group_buckets(items, |item| {
+        let label = item.task_name.clone().unwrap_or_default();
+        let key = item.task_id.clone().unwrap_or_else(|| label.clone());
+        (key, label)
+    });
+    let by_account = group_buckets(items, |item| {
+        let email = item.account_email.to_lowercase();
+        (email.clone(), email)
+    });
+
+    WakeupHistoryStats {
+        start_time: filter.start_time,
+        end_time: filter.end_time,
+        overall: build_bucket("all".to_string(), "all".to_string(), &all),
+        by_task,
+        by_account,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wakeup_history_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn item(id: &str, timestamp: i64, task: &str, email: &str, success: bool, duration: u64) -> WakeupHistoryItem {
+        WakeupHistoryItem {
+            id: id.to_string(),
+            timestamp,
+            trigger_type: "auto".to_string(),
+            trigger_source: "scheduled".to_string(),
+            task_id: Some(task.to_string()),
+            task_name: Some(format!("任务 {}", task)),
+            account_email: email.to_string(),
+            model_id: "gemini".to_string(),
+            prompt: None,
+            success,
+            message: None,
+            duration: Some(duration),
+            prompt_tokens: Some(10),
+            completion_tokens: Some(5),
+            total_tokens: Some(15),
+        }
+    }
+
+    #[test]
+    fn migrates_legacy_json_once() {
+        let dir = temp_dir();
+        let legacy = vec![item("a", 1, "t1", "a@x.com", true, 100), item("b", 2, "t1", "a@x.com", false, 200)];
+        fs::write(dir.join(LEGACY_HISTORY_FILE), serde_json::to_string(&legacy).unwrap()).unwrap();
+
+        let conn = open_db_in(&dir).unwrap();
+        assert_eq!(select_items(&conn, &WakeupHistoryFilter::default(), None).unwrap().len(), 2);
+        assert!(!dir.join(LEGACY_HISTORY_FILE).exists());
+        assert!(dir.join(format!("{}.migrated", LEGACY_HISTORY_FILE)).exists());
+        drop(conn);
+
+        // 再次打开不会重复导入
+        let conn = open_db_in(&dir).unwrap();
+        assert_eq!(select_items(&conn, &WakeupHistoryFilter::default(), None).unwrap().len(), 2);
+        drop(conn);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn query_filters_and_paginates() {
+        let dir = temp_dir();
+        let conn = open_db_in(&dir).unwrap();
+        insert_items(
+            &conn,
+            &[
+                item("1", 100, "t1", "A@x.com", true, 10),
+                item("2", 200, "t1", "b@x.com", false, 20),
+                item("3", 300, "t2", "a@x.com", true, 30),
+                item("4", 400, "t2", "a@x.com", false, 40),
+            ],
+        )
+        .unwrap();
+        let ids = |filter: WakeupHistoryFilter, limit: Option<usize>| -> Vec<String> {
+            select_items(&conn, &filter, limit).unwrap().into_iter().map(|item| item.id).collect()
+        };
+
+        let by_email = WakeupHistoryFilter { account_email: Some("a@X.com".to_string()), ..Default::default() };
+        assert_eq!(ids(by_email, None), ["4", "3", "1"]);
+        let by_task_and_result = WakeupHistoryFilter {
+            task_id: Some("t2".to_string()),
+            success: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(ids(by_task_and_result, None), ["3"]);
+        let by_time = WakeupHistoryFilter { start_time: Some(200), end_time: Some(300), ..Default::default() };
+        assert_eq!(ids(by_time, None), ["3", "2"]);
+        let page = WakeupHistoryFilter { offset: Some(1), ..Default::default() };
+        assert_eq!(ids(page, Some(2)), ["3", "2"]);
+
+        drop(conn);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn stats_report_median_tokens_and_groups() {
+        let items = vec![
+            item("1", 1, "t1", "A@x.com", true, 100),
+            item("2", 2, "t1", "a@x.com", false, 300),
+            item("3", 3, "t2", "b@x.com", true, 200),
+            item("4", 4, "t1", "b@x.com", true, 1000),
+        ];
+        let stats = build_stats(&items, &WakeupHistoryFilter::default());
+
+        assert_eq!(stats.overall.total, 4);
+        assert_eq!(stats.overall.success_count, 3);
+        assert_eq!(stats.overall.success_rate, 0.75);
+        assert_eq!(stats.overall.median_duration, Some(250));
+        assert_eq!(stats.overall.total_tokens, 60);
+        assert_eq!(stats.overall.prompt_tokens, 40);
+
+        assert_eq!(stats.by_task[0].key, "t1");
+        assert_eq!(stats.by_task[0].label, "任务 t1");
+        assert_eq!((stats.by_task[0].total, stats.by_task[0].failure_count), (3, 1));
+        assert_eq!(stats.by_task[0].median_duration, Some(300));
+        assert_eq!(stats.by_task[1].key, "t2");
+
+        // 账号按邮箱忽略大小写分组
+        assert_eq!(stats.by_account.len(), 2);
+        assert_eq!(stats.by_account[0].key, "a@x.com");
+        assert_eq!(stats.by_account[0].total, 2);
+        assert_eq!(stats.by_account[0].completion_tokens, 10);
+    }
+
+    #[test]
+    fn prune_keeps_newest_items() {
+        let dir = temp_dir();
+        let conn = open_db_in(&dir).unwrap();
+        let items: Vec<_> = (0..5).map(|i| item(&i.to_string(), i, "t1", "a@x.com", true, 1)).collect();
+        insert_items(&conn, &items).unwrap();
+
+        prune_history(&conn, 3).unwrap();
+        let ids: Vec<String> = select_items(&conn, &WakeupHistoryFilter::default(), None)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(ids, ["4", "3", "2"]);
+
+        // 未超过上限时不删除
+        prune_history(&conn, MAX_STORED_ITEMS).unwrap();
+        assert_eq!(select_items(&conn, &WakeupHistoryFilter::default(), None).unwrap().len(), 3);
+
+        drop(conn);
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
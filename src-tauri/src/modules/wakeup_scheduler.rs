@@ -1,10 +1,13 @@
 use std::collections::{HashMap, HashSet};
-use std::sync::{Mutex, OnceLock};
-use std::time::Duration;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
-use chrono::{DateTime, Datelike, Local, TimeZone, Timelike};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
 use tokio::time::sleep;
 
 use crate::modules;
@@ -12,8 +15,24 @@ use crate::modules;
 const DEFAULT_PROMPT: &str = "hi";
 const RESET_TRIGGER_COOLDOWN_MS: i64 = 10 * 60 * 1000;
 const RESET_SAFETY_MARGIN_MS: i64 = 2 * 60 * 1000;
+const TASKS_FILE: &str = "wakeup_tasks.json";
+/// 超过该时长仍未执行的计划视为错过（调度循环每 30 秒一次）
+const MISSED_RUN_GRACE_MS: i64 = 90 * 1000;
+/// catch-up=all 时单次最多补跑的次数
+const MAX_CATCH_UP_RUNS: usize = 24;
+/// 单次扫描到期计划的上限
+const MAX_DUE_SCAN: usize = 2000;
+const DEFAULT_PREVIEW_COUNT: usize = 10;
+const MAX_PREVIEW_COUNT: usize = 100;
+/// 单任务并发上限
+const MAX_TASK_CONCURRENCY: u32 = 16;
+const MAX_RETRY_COUNT: u32 = 5;
+const DEFAULT_RETRY_BACKOFF_MS: u64 = 2000;
+const MAX_RETRY_BACKOFF_MS: u64 = 60 * 1000;
 
-#[derive(Debug, Clone, Deserialize)]
+static PERSIST_LOCK: std::sync::LazyLock<Mutex<()>> = std::sync::LazyLock::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WakeupTaskInput {
     pub id: String,
@@ -24,7 +43,7 @@ pub struct WakeupTaskInput {
     pub schedule: ScheduleConfig,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ScheduleConfig {
     pub repeat_mode: String,
@@ -44,6 +63,18 @@ pub struct ScheduleConfig {
     pub time_window_start: Option<String>,
     pub time_window_end: Option<String>,
     pub fallback_times: Option<Vec<String>>,
+    /// 任务时区：local、UTC 或固定偏移（如 +08:00、UTC-5）
+    pub timezone: Option<String>,
+    /// 错过计划时的补跑策略：skip / once / all
+    pub catch_up_policy: Option<String>,
+    /// 单任务最大并发请求数（默认 1，即逐个执行）
+    pub max_concurrency: Option<u32>,
+    /// 429/5xx 时的额外重试次数
+    pub retry_count: Option<u32>,
+    /// 重试退避基准时长（毫秒），每次重试翻倍
+    pub retry_backoff_ms: Option<u64>,
+    /// 同一账号两次请求的最小间隔（毫秒）
+    pub account_spacing_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -51,10 +82,11 @@ struct WakeupTask {
     id: String,
     name: String,
     enabled: bool,
-    #[allow(dead_code)]
     created_at: i64,
     last_run_at: Option<i64>,
     schedule: ScheduleConfigNormalized,
+    /// 前端提交的原始调度配置，用于持久化
+    raw_schedule: ScheduleConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -76,9 +108,151 @@ struct ScheduleConfigNormalized {
     time_window_start: Option<String>,
     time_window_end: Option<String>,
     fallback_times: Vec<String>,
+    timezone: ScheduleTimezone,
+    catch_up_policy: CatchUpPolicy,
+    max_concurrency: usize,
+    retry_count: u32,
+    retry_backoff_ms: u64,
+    account_spacing_ms: u64,
+}
+
+/// 任务时区：本地时间、固定偏移或 IANA 时区名（如 Asia/Shanghai）
+#[derive(Debug, Clone, PartialEq)]
+enum ScheduleTimezone {
+    Local,
+    Fixed(FixedOffset),
+    Named(jiff::tz::TimeZone),
+}
+
+impl ScheduleTimezone {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        let raw = value.map(str::trim).unwrap_or_default();
+        if raw.is_empty() || raw.eq_ignore_ascii_case("local") {
+            return Ok(Self::Local);
+        }
+
+        let upper = raw.to_ascii_uppercase();
+        let offset_part = upper
+            .strip_prefix("UTC")
+            .or_else(|| upper.strip_prefix("GMT"))
+            .unwrap_or(&upper);
+        if offset_part.is_empty() || offset_part == "Z" {
+            return Ok(Self::Fixed(FixedOffset::east_opt(0).expect("utc offset")));
+        }
+
+        let invalid = || format!("不支持的时区: {}（请使用 local、IANA 时区名或 +08:00 形式的偏移）", raw);
+        let (sign, digits) = match offset_part.chars().next() {
+            Some('+') => (1, &offset_part[1..]),
+            Some('-') => (-1, &offset_part[1..]),
+            _ => {
+                return jiff::tz::TimeZone::get(raw)
+                    .map(Self::Named)
+                    .map_err(|_| invalid())
+            }
+        };
+        let (hours, minutes) = if let Some((h, m)) = digits.split_once(':') {
+            (h, m)
+        } else if digits.len() == 4 {
+            digits.split_at(2)
+        } else {
+            (digits, "0")
+        };
+        let hours: i32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+        if hours > 14 || minutes > 59 {
+            return Err(invalid());
+        }
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .map(Self::Fixed)
+            .ok_or_else(invalid)
+    }
+
+    /// 指定时刻在该时区的 UTC 偏移
+    fn offset_at(&self, dt: DateTime<Local>) -> FixedOffset {
+        match self {
+            Self::Local => *dt.offset(),
+            Self::Fixed(offset) => *offset,
+            Self::Named(tz) => {
+                let seconds = jiff::Timestamp::from_second(dt.timestamp())
+                    .map(|ts| tz.to_offset(ts).seconds())
+                    .unwrap_or(0);
+                FixedOffset::east_opt(seconds).unwrap_or_else(|| FixedOffset::east_opt(0).expect("utc offset"))
+            }
+        }
+    }
+
+    fn to_naive(&self, dt: DateTime<Local>) -> NaiveDateTime {
+        dt.with_timezone(&self.offset_at(dt)).naive_local()
+    }
+
+    /// 夏令时跳过的本地时间返回 None，重复的本地时间取较早者
+    fn to_local(&self, naive: NaiveDateTime) -> Option<DateTime<Local>> {
+        match self {
+            Self::Local => Local.from_local_datetime(&naive).earliest(),
+            Self::Fixed(offset) => offset
+                .from_local_datetime(&naive)
+                .single()
+                .map(|dt| dt.with_timezone(&Local)),
+            Self::Named(tz) => {
+                use jiff::tz::AmbiguousOffset;
+
+                let civil = jiff::civil::DateTime::new(
+                    i16::try_from(naive.year()).ok()?,
+                    naive.month() as i8,
+                    naive.day() as i8,
+                    naive.hour() as i8,
+                    naive.minute() as i8,
+                    naive.second() as i8,
+                    0,
+                )
+                .ok()?;
+                let offset = match tz.to_ambiguous_timestamp(civil).offset() {
+                    AmbiguousOffset::Unambiguous { offset } => offset,
+                    AmbiguousOffset::Fold { before, .. } => before,
+                    AmbiguousOffset::Gap { .. } => return None,
+                };
+                FixedOffset::east_opt(offset.seconds())?
+                    .from_local_datetime(&naive)
+                    .single()
+                    .map(|dt| dt.with_timezone(&Local))
+            }
+        }
+    }
+}
+
+/// 机器休眠等原因错过计划时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CatchUpPolicy {
+    /// 丢弃错过的计划，只执行准点的
+    Skip,
+    /// 错过的计划合并为一次执行
+    Once,
+    /// 每个错过的计划各执行一次（上限 MAX_CATCH_UP_RUNS）
+    All,
+}
+
+impl CatchUpPolicy {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("once") => Ok(Self::Once),
+            Some("skip") => Ok(Self::Skip),
+            Some("all") => Ok(Self::All),
+            Some(other) => Err(format!("不支持的补跑策略: {}", other)),
+        }
+    }
+}
+
+/// 下次执行时间预览
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeupRunPreview {
+    pub timestamp: i64,
+    /// 任务时区下的 RFC3339 时间
+    pub time: String,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 struct ResetState {
     last_reset_trigger_timestamps: HashMap<String, String>,
     last_reset_trigger_at: HashMap<String, i64>,
@@ -94,9 +268,68 @@ struct SchedulerState {
     last_fallback_run_at: HashMap<String, i64>,
     /// 记录每个任务的实际执行时间，不会被前端 sync_state 覆盖
     last_executed_at: HashMap<String, i64>,
+    /// 已处理（执行或按策略跳过）的最近一个计划时间
+    last_slot_at: HashMap<String, i64>,
+}
+
+/// 单个任务的运行状态（持久化）
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct TaskRunState {
+    reset_state: ResetState,
+    last_fallback_run_at: Option<i64>,
+    last_executed_at: Option<i64>,
+    last_slot_at: Option<i64>,
+}
+
+/// 持久化文件结构（wakeup_tasks.json）
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct PersistedSchedulerState {
+    enabled: bool,
+    tasks: Vec<WakeupTaskInput>,
+    run_states: HashMap<String, TaskRunState>,
+    updated_at: i64,
+}
+
+/// 唤醒任务状态快照（供前端读取）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeupStateSnapshot {
+    pub enabled: bool,
+    pub tasks: Vec<WakeupTaskInput>,
+}
+
+/// 演练结果中的单条计划
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeupPlannedRun {
+    pub task_id: String,
+    pub task_name: String,
+    pub account_id: Option<String>,
+    pub account_email: String,
+    pub model_id: String,
+    /// 预计执行时间（毫秒时间戳），无法确定时为空
+    pub planned_at: Option<i64>,
+    /// 本轮调度是否会真正发送请求
+    pub will_trigger: bool,
+    pub reason: String,
+}
+
+/// 唤醒演练报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeupDryRunReport {
+    pub scheduler_enabled: bool,
+    pub generated_at: i64,
+    pub runs: Vec<WakeupPlannedRun>,
 }
 
 static STATE: OnceLock<Mutex<SchedulerState>> = OnceLock::new();
+/// 跨任务的全局请求并发限制（上限变更时整体替换，已持有的许可不受影响）
+static GLOBAL_REQUEST_LIMIT: Mutex<Option<Arc<Semaphore>>> = Mutex::new(None);
+/// 每个账号下一次允许发起请求的时间
+static ACCOUNT_NEXT_SLOT: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
 static STARTED: OnceLock<Mutex<bool>> = OnceLock::new();
 
 fn state() -> &'static Mutex<SchedulerState> {
@@ -107,7 +340,7 @@ fn started_flag() -> &'static Mutex<bool> {
     STARTED.get_or_init(|| Mutex::new(false))
 }
 
-fn normalize_schedule(raw: ScheduleConfig) -> ScheduleConfigNormalized {
+fn normalize_schedule(raw: ScheduleConfig) -> Result<ScheduleConfigNormalized, String> {
     let daily_times = raw
         .daily_times
         .filter(|times| !times.is_empty())
@@ -128,8 +361,10 @@ fn normalize_schedule(raw: ScheduleConfig) -> ScheduleConfigNormalized {
         .fallback_times
         .filter(|times| !times.is_empty())
         .unwrap_or_else(|| vec!["07:00".to_string()]);
+    let timezone = ScheduleTimezone::parse(raw.timezone.as_deref())?;
+    let catch_up_policy = CatchUpPolicy::parse(raw.catch_up_policy.as_deref())?;
 
-    ScheduleConfigNormalized {
+    Ok(ScheduleConfigNormalized {
         repeat_mode: raw.repeat_mode,
         daily_times,
         weekly_days,
@@ -147,23 +382,268 @@ fn normalize_schedule(raw: ScheduleConfig) -> ScheduleConfigNormalized {
         time_window_start: raw.time_window_start,
         time_window_end: raw.time_window_end,
         fallback_times,
+        timezone,
+        catch_up_policy,
+        max_concurrency: raw.max_concurrency.unwrap_or(1).clamp(1, MAX_TASK_CONCURRENCY) as usize,
+        retry_count: raw.retry_count.unwrap_or(0).min(MAX_RETRY_COUNT),
+        retry_backoff_ms: raw
+            .retry_backoff_ms
+            .unwrap_or(DEFAULT_RETRY_BACKOFF_MS)
+            .min(MAX_RETRY_BACKOFF_MS),
+        account_spacing_ms: raw.account_spacing_ms.unwrap_or(0),
+    })
+}
+
+/// 校验调度配置中需要解析的字段
+fn validate_schedule(schedule: &ScheduleConfig) -> Result<(), String> {
+    ScheduleTimezone::parse(schedule.timezone.as_deref())?;
+    CatchUpPolicy::parse(schedule.catch_up_policy.as_deref())?;
+    if let Some(expr) = schedule.crontab.as_deref().filter(|expr| !expr.trim().is_empty()) {
+        modules::wakeup_cron::CronSchedule::parse(expr)?;
     }
+    Ok(())
+}
+
+fn build_task(input: WakeupTaskInput) -> Result<WakeupTask, String> {
+    let schedule = normalize_schedule(input.schedule.clone())
+        .map_err(|e| format!("任务 {} 配置无效: {}", input.name, e))?;
+    Ok(WakeupTask {
+        id: input.id,
+        name: input.name,
+        enabled: input.enabled,
+        created_at: input.created_at,
+        last_run_at: input.last_run_at,
+        schedule,
+        raw_schedule: input.schedule,
+    })
+}
+
+fn task_to_input(task: &WakeupTask, last_executed_at: Option<i64>) -> WakeupTaskInput {
+    WakeupTaskInput {
+        id: task.id.clone(),
+        name: task.name.clone(),
+        enabled: task.enabled,
+        created_at: task.created_at,
+        last_run_at: last_executed_at.max(task.last_run_at),
+        schedule: task.raw_schedule.clone(),
+    }
+}
+
+/// 移除已不存在任务的运行状态
+fn prune_run_states(state: &mut SchedulerState) {
+    let ids: HashSet<String> = state.tasks.iter().map(|task| task.id.clone()).collect();
+    state.reset_states.retain(|id, _| ids.contains(id));
+    state.last_fallback_run_at.retain(|id, _| ids.contains(id));
+    state.last_executed_at.retain(|id, _| ids.contains(id));
+    state.last_slot_at.retain(|id, _| ids.contains(id));
+}
+
+fn tasks_path() -> Result<PathBuf, String> {
+    let data_dir = modules::account::get_data_dir()?;
+    Ok(data_dir.join(TASKS_FILE))
+}
+
+fn snapshot_for_persist(state: &SchedulerState) -> PersistedSchedulerState {
+    let tasks = state
+        .tasks
+        .iter()
+        .map(|task| task_to_input(task, state.last_executed_at.get(&task.id).copied()))
+        .collect();
+    let run_states = state
+        .tasks
+        .iter()
+        .map(|task| {
+            let run_state = TaskRunState {
+                reset_state: state.reset_states.get(&task.id).cloned().unwrap_or_default(),
+                last_fallback_run_at: state.last_fallback_run_at.get(&task.id).copied(),
+                last_executed_at: state.last_executed_at.get(&task.id).copied(),
+                last_slot_at: state.last_slot_at.get(&task.id).copied(),
+            };
+            (task.id.clone(), run_state)
+        })
+        .collect();
+    PersistedSchedulerState {
+        enabled: state.enabled,
+        tasks,
+        run_states,
+        updated_at: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+/// 写入快照（调用方需持有 PERSIST_LOCK）
+fn save_persisted_state(persisted: &PersistedSchedulerState) -> Result<(), String> {
+    let path = tasks_path()?;
+    let data_dir = modules::account::get_data_dir()?;
+    let temp_path = data_dir.join(format!("{}.tmp", TASKS_FILE));
+
+    let content = serde_json::to_string_pretty(persisted)
+        .map_err(|e| format!("序列化唤醒任务失败: {}", e))?;
+
+    fs::write(&temp_path, content)
+        .map_err(|e| format!("写入临时唤醒任务文件失败: {}", e))?;
+
+    fs::rename(temp_path, path)
+        .map_err(|e| format!("替换唤醒任务文件失败: {}", e))
 }
 
-pub fn sync_state(enabled: bool, tasks: Vec<WakeupTaskInput>) {
+/// 将当前内存状态写入数据目录
+///
+/// 快照与写入都在 PERSIST_LOCK 内完成，避免并发保存时旧快照覆盖新快照。
+fn persist_state() {
+    let Ok(_lock) = PERSIST_LOCK.lock() else {
+        modules::logger::log_error("[Wakeup] 获取唤醒任务锁失败");
+        return;
+    };
+    let persisted = {
+        let guard = state().lock().expect("wakeup state lock");
+        snapshot_for_persist(&guard)
+    };
+    if let Err(e) = save_persisted_state(&persisted) {
+        modules::logger::log_error(&format!("[Wakeup] 保存唤醒任务失败: {}", e));
+    }
+}
+
+fn read_persisted_state() -> Result<Option<PersistedSchedulerState>, String> {
+    let path = tasks_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("读取唤醒任务失败: {}", e))?;
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+
+    serde_json::from_str(&content).map(Some).map_err(|e| {
+        crate::error::file_corrupted_error(TASKS_FILE, &path.to_string_lossy(), &e.to_string())
+    })
+}
+
+/// 启动时从数据目录恢复任务及运行状态
+pub fn load_persisted_state() {
+    let persisted = match read_persisted_state() {
+        Ok(Some(persisted)) => persisted,
+        Ok(None) => return,
+        Err(e) => {
+            modules::logger::log_error(&format!("[Wakeup] 加载唤醒任务失败: {}", e));
+            return;
+        }
+    };
+
     let mut guard = state().lock().expect("wakeup state lock");
-    guard.enabled = enabled;
-    guard.tasks = tasks
+    guard.enabled = persisted.enabled;
+    guard.tasks = persisted
+        .tasks
         .into_iter()
-        .map(|task| WakeupTask {
-            id: task.id,
-            name: task.name,
-            enabled: task.enabled,
-            created_at: task.created_at,
-            last_run_at: task.last_run_at,
-            schedule: normalize_schedule(task.schedule),
+        .filter_map(|input| match build_task(input) {
+            Ok(task) => Some(task),
+            Err(e) => {
+                modules::logger::log_error(&format!("[Wakeup] {}，已跳过", e));
+                None
+            }
         })
         .collect();
+    for (task_id, run_state) in persisted.run_states {
+        guard.reset_states.insert(task_id.clone(), run_state.reset_state);
+        if let Some(ts) = run_state.last_fallback_run_at {
+            guard.last_fallback_run_at.insert(task_id.clone(), ts);
+        }
+        if let Some(ts) = run_state.last_executed_at {
+            guard.last_executed_at.insert(task_id.clone(), ts);
+        }
+        if let Some(ts) = run_state.last_slot_at {
+            guard.last_slot_at.insert(task_id, ts);
+        }
+    }
+    prune_run_states(&mut guard);
+    modules::logger::log_info(&format!(
+        "[Wakeup] 已加载 {} 个唤醒任务 (enabled={})",
+        guard.tasks.len(),
+        guard.enabled
+    ));
+}
+
+pub fn sync_state(enabled: bool, tasks: Vec<WakeupTaskInput>) -> Result<(), String> {
+    let tasks = tasks.into_iter().map(build_task).collect::<Result<Vec<_>, _>>()?;
+    {
+        let mut guard = state().lock().expect("wakeup state lock");
+        guard.enabled = enabled;
+        guard.tasks = tasks;
+        prune_run_states(&mut guard);
+    }
+    persist_state();
+    Ok(())
+}
+
+/// 获取当前任务状态
+pub fn get_state_snapshot() -> WakeupStateSnapshot {
+    let guard = state().lock().expect("wakeup state lock");
+    WakeupStateSnapshot {
+        enabled: guard.enabled,
+        tasks: guard
+            .tasks
+            .iter()
+            .map(|task| task_to_input(task, guard.last_executed_at.get(&task.id).copied()))
+            .collect(),
+    }
+}
+
+/// 设置唤醒总开关
+pub fn set_enabled(enabled: bool) -> WakeupStateSnapshot {
+    {
+        let mut guard = state().lock().expect("wakeup state lock");
+        guard.enabled = enabled;
+    }
+    persist_state();
+    get_state_snapshot()
+}
+
+/// 新增或更新任务（id 为空时自动生成）
+pub fn upsert_task(mut input: WakeupTaskInput) -> Result<WakeupTaskInput, String> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err("任务名称不能为空".to_string());
+    }
+    validate_schedule(&input.schedule)?;
+    if input.id.trim().is_empty() {
+        input.id = uuid::Uuid::new_v4().to_string();
+    }
+    if input.created_at <= 0 {
+        input.created_at = chrono::Utc::now().timestamp_millis();
+    }
+
+    let task_id = input.id.clone();
+    let task = build_task(input)?;
+    {
+        let mut guard = state().lock().expect("wakeup state lock");
+        match guard.tasks.iter().position(|item| item.id == task.id) {
+            Some(pos) => guard.tasks[pos] = task,
+            None => guard.tasks.push(task),
+        }
+    }
+    persist_state();
+
+    get_state_snapshot()
+        .tasks
+        .into_iter()
+        .find(|task| task.id == task_id)
+        .ok_or_else(|| "任务保存后无法读取".to_string())
+}
+
+/// 删除任务及其运行状态
+pub fn delete_task(task_id: &str) -> Result<(), String> {
+    {
+        let mut guard = state().lock().expect("wakeup state lock");
+        let original_len = guard.tasks.len();
+        guard.tasks.retain(|task| task.id != task_id);
+        if guard.tasks.len() == original_len {
+            return Err(format!("任务不存在: {}", task_id));
+        }
+        prune_run_states(&mut guard);
+    }
+    persist_state();
+    Ok(())
 }
 
 pub fn ensure_started(app: AppHandle) {
@@ -194,7 +674,7 @@ fn parse_time_to_minutes(value: &str) -> Option<i32> {
     Some(h * 60 + m)
 }
 
-fn is_in_time_window(start: Option<&String>, end: Option<&String>, now: DateTime<Local>) -> bool {
+fn is_in_time_window(start: Option<&String>, end: Option<&String>, now: NaiveDateTime) -> bool {
     let Some(start) = start else { return true; };
     let Some(end) = end else { return true; };
     let Some(start_minutes) = parse_time_to_minutes(start) else { return true; };
@@ -209,18 +689,17 @@ fn is_in_time_window(start: Option<&String>, end: Option<&String>, now: DateTime
 }
 
 fn next_run_time(schedule: &ScheduleConfigNormalized, after: DateTime<Local>) -> Option<DateTime<Local>> {
-    let mut results: Vec<DateTime<Local>> = Vec::new();
+    let tz = &schedule.timezone;
+    let base_date = tz.to_naive(after).date();
     if schedule.repeat_mode == "daily" && !schedule.daily_times.is_empty() {
         let mut times = schedule.daily_times.clone();
         times.sort();
         for day_offset in 0..7 {
+            let date = base_date + chrono::Duration::days(day_offset);
             for time in &times {
-                if let Some(candidate) = build_datetime(after, day_offset, &time) {
+                if let Some(candidate) = build_datetime(tz, date, time) {
                     if candidate > after {
-                        results.push(candidate);
-                        if !results.is_empty() {
-                            return results.into_iter().min();
-                        }
+                        return Some(candidate);
                     }
                 }
             }
@@ -229,23 +708,20 @@ fn next_run_time(schedule: &ScheduleConfigNormalized, after: DateTime<Local>) ->
         let mut times = schedule.weekly_times.clone();
         times.sort();
         for day_offset in 0..14 {
-            let date = after + chrono::Duration::days(day_offset);
+            let date = base_date + chrono::Duration::days(day_offset);
             let weekday = date.weekday().num_days_from_sunday() as i32;
             if schedule.weekly_days.contains(&weekday) {
                 for time in &times {
-                    if let Some(candidate) = build_datetime_from_date(date, &time) {
+                    if let Some(candidate) = build_datetime(tz, date, time) {
                         if candidate > after {
-                            results.push(candidate);
-                            if !results.is_empty() {
-                                return results.into_iter().min();
-                            }
+                            return Some(candidate);
                         }
                     }
                 }
             }
         }
     } else if schedule.repeat_mode == "interval" {
-        let start_time = schedule.interval_start_time.clone();
+        let start_minutes = parse_time_to_minutes(&schedule.interval_start_time).unwrap_or(0);
         let end_hour: i32 = schedule
             .interval_end_time
             .split(':')
@@ -255,14 +731,12 @@ fn next_run_time(schedule: &ScheduleConfigNormalized, after: DateTime<Local>) ->
         let interval = schedule.interval_hours.max(1);
 
         for day_offset in 0..7 {
-            for h in (parse_time_to_minutes(&start_time).unwrap_or(0) / 60..=end_hour).step_by(interval as usize) {
-                let time = format!("{:02}:{:02}", h, parse_time_to_minutes(&start_time).unwrap_or(0) % 60);
-                if let Some(candidate) = build_datetime(after, day_offset, &time) {
+            let date = base_date + chrono::Duration::days(day_offset);
+            for h in (start_minutes / 60..=end_hour).step_by(interval as usize) {
+                let time = format!("{:02}:{:02}", h, start_minutes % 60);
+                if let Some(candidate) = build_datetime(tz, date, &time) {
                     if candidate > after {
-                        results.push(candidate);
-                        if !results.is_empty() {
-                            return results.into_iter().min();
-                        }
+                        return Some(candidate);
                     }
                 }
             }
@@ -271,88 +745,135 @@ fn next_run_time(schedule: &ScheduleConfigNormalized, after: DateTime<Local>) ->
     None
 }
 
-fn build_datetime(base: DateTime<Local>, day_offset: i64, time: &str) -> Option<DateTime<Local>> {
-    let date = base + chrono::Duration::days(day_offset);
-    build_datetime_from_date(date, time)
-}
-
-fn build_datetime_from_date(date: DateTime<Local>, time: &str) -> Option<DateTime<Local>> {
+fn build_datetime(tz: &ScheduleTimezone, date: NaiveDate, time: &str) -> Option<DateTime<Local>> {
     let parts: Vec<&str> = time.split(':').collect();
     if parts.len() != 2 {
         return None;
     }
     let h: u32 = parts[0].parse().ok()?;
     let m: u32 = parts[1].parse().ok()?;
-    let naive_date = date.date_naive();
-    let naive = naive_date.and_hms_opt(h, m, 0)?;
-    Local.from_local_datetime(&naive).single()
+    tz.to_local(date.and_hms_opt(h, m, 0)?)
 }
 
-fn next_crontab_time(expr: &str, after: DateTime<Local>) -> Option<DateTime<Local>> {
-    let parts: Vec<&str> = expr.trim().split_whitespace().collect();
-    if parts.len() < 5 {
-        return None;
-    }
-    let minutes = parse_cron_field(parts[0], 59)?;
-    let hours = parse_cron_field(parts[1], 23)?;
+fn next_crontab_time(expr: &str, tz: &ScheduleTimezone, after: DateTime<Local>) -> Option<DateTime<Local>> {
+    let cron = match modules::wakeup_cron::CronSchedule::parse(expr) {
+        Ok(cron) => cron,
+        Err(e) => {
+            modules::logger::log_warn(&format!("[Wakeup] {}", e));
+            return None;
+        }
+    };
 
-    for day_offset in 0..7 {
-        for h in &hours {
-            for m in &minutes {
-                let candidate = build_datetime(after, day_offset, &format!("{:02}:{:02}", h, m));
-                if let Some(candidate) = candidate {
-                    if candidate > after {
-                        return Some(candidate);
-                    }
-                }
+    let mut cursor = tz.to_naive(after);
+    // 跳过夏令时不存在的本地时间
+    for _ in 0..8 {
+        cursor = cron.next_after(cursor)?;
+        if let Some(candidate) = tz.to_local(cursor) {
+            if candidate > after {
+                return Some(candidate);
             }
         }
     }
     None
 }
 
-fn parse_cron_field(field: &str, max: i32) -> Option<Vec<i32>> {
-    if field == "*" {
-        return Some((0..=max).collect());
+/// 按任务配置计算下一个计划时间（crontab 优先）
+fn next_scheduled_time(schedule: &ScheduleConfigNormalized, after: DateTime<Local>) -> Option<DateTime<Local>> {
+    match schedule.crontab.as_deref().filter(|expr| !expr.trim().is_empty()) {
+        Some(expr) => next_crontab_time(expr, &schedule.timezone, after),
+        None => next_run_time(schedule, after),
     }
-    if field.contains(',') {
-        let mut result = Vec::new();
-        for part in field.split(',') {
-            result.push(part.parse().ok()?);
+}
+
+/// (after, now] 区间内到期的计划
+#[derive(Debug, Clone, Copy)]
+struct DueRuns {
+    /// 到期次数（最多统计 MAX_DUE_SCAN 次）
+    count: usize,
+    /// 最近一次到期的计划时间
+    latest: DateTime<Local>,
+}
+
+/// 从 after 开始最多扫描 MAX_DUE_SCAN 个计划，返回 (次数, 最后一个, 是否还有未扫描的)
+fn scan_due_runs(
+    schedule: &ScheduleConfigNormalized,
+    after: DateTime<Local>,
+    now: DateTime<Local>,
+) -> (usize, Option<DateTime<Local>>, bool) {
+    let mut count = 0;
+    let mut cursor = after;
+    let mut last = None;
+    while count < MAX_DUE_SCAN {
+        match next_scheduled_time(schedule, cursor) {
+            Some(next) if next <= now => {
+                count += 1;
+                last = Some(next);
+                cursor = next;
+            }
+            _ => return (count, last, false),
         }
-        return Some(result);
     }
-    if field.contains('-') {
-        let parts: Vec<&str> = field.split('-').collect();
-        if parts.len() != 2 {
-            return None;
-        }
-        let start: i32 = parts[0].parse().ok()?;
-        let end: i32 = parts[1].parse().ok()?;
-        if end < start {
-            return None;
+    let more = next_scheduled_time(schedule, cursor).is_some_and(|next| next <= now);
+    (count, last, more)
+}
+
+/// 统计 (after, now] 区间内的计划
+///
+/// 错过的计划超过扫描上限时，从 now 往前逐步扩大窗口找到真正的最近一次，
+/// 保证 last_slot_at 与准点判断都基于最新的计划时间。
+fn collect_due_runs(
+    schedule: &ScheduleConfigNormalized,
+    after: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Option<DueRuns> {
+    let (count, last, more) = scan_due_runs(schedule, after, now);
+    let mut latest = last?;
+    if more {
+        let mut span = chrono::Duration::hours(1);
+        loop {
+            let start = (now - span).max(latest);
+            let (_, found, more) = scan_due_runs(schedule, start, now);
+            if let Some(found) = found {
+                latest = found;
+                if !more {
+                    break;
+                }
+                // 窗口内仍超出上限，从找到的位置继续
+                span = now - found;
+                continue;
+            }
+            if start == latest {
+                break;
+            }
+            span = span * 4;
         }
-        return Some((start..=end).collect());
     }
-    if field.contains('/') {
-        let parts: Vec<&str> = field.split('/').collect();
-        if parts.len() != 2 {
-            return None;
-        }
-        let step: i32 = parts[1].parse().ok()?;
-        if step <= 0 {
-            return None;
-        }
-        let mut result = Vec::new();
-        let mut value = 0;
-        while value <= max {
-            result.push(value);
-            value += step;
-        }
-        return Some(result);
+    Some(DueRuns { count, latest })
+}
+
+/// 预览调度配置接下来的执行时间
+pub fn preview_schedule(schedule: ScheduleConfig, count: Option<usize>) -> Result<Vec<WakeupRunPreview>, String> {
+    validate_schedule(&schedule)?;
+    let normalized = normalize_schedule(schedule)?;
+    if normalized.wake_on_reset {
+        return Ok(Vec::new());
+    }
+
+    let count = count.unwrap_or(DEFAULT_PREVIEW_COUNT).clamp(1, MAX_PREVIEW_COUNT);
+    let mut previews = Vec::with_capacity(count);
+    let mut cursor = Local::now();
+    while previews.len() < count {
+        let Some(next) = next_scheduled_time(&normalized, cursor) else {
+            break;
+        };
+        let time = next.with_timezone(&normalized.timezone.offset_at(next)).to_rfc3339();
+        previews.push(WakeupRunPreview {
+            timestamp: next.timestamp_millis(),
+            time,
+        });
+        cursor = next;
     }
-    let value: i32 = field.parse().ok()?;
-    Some(vec![value])
+    Ok(previews)
 }
 
 fn normalize_max_tokens(value: i32) -> u32 {
@@ -363,10 +884,10 @@ fn normalize_max_tokens(value: i32) -> u32 {
     }
 }
 
-fn should_trigger_on_reset(state: &mut ResetState, model_key: &str, reset_at: &str, remaining_percent: i32) -> bool {
+/// 判断配额重置触发被拦截的原因，返回 None 表示可以触发
+fn reset_block_reason(state: &ResetState, model_key: &str, reset_at: &str, remaining_percent: i32) -> Option<&'static str> {
     if remaining_percent < 100 {
-        state.last_reset_remaining.insert(model_key.to_string(), remaining_percent);
-        return false;
+        return Some("quota_not_full");
     }
 
     let now = chrono::Utc::now().timestamp_millis();
@@ -376,26 +897,28 @@ fn should_trigger_on_reset(state: &mut ResetState, model_key: &str, reset_at: &s
         {
             let safe_time = last_reset_time + RESET_SAFETY_MARGIN_MS;
             if now < safe_time {
-                state.last_reset_remaining.insert(model_key.to_string(), remaining_percent);
-                return false;
+                return Some("reset_safety_margin");
             }
         }
     }
 
     if let Some(last_trigger_at) = state.last_reset_trigger_at.get(model_key) {
         if now - *last_trigger_at < RESET_TRIGGER_COOLDOWN_MS {
-            state.last_reset_remaining.insert(model_key.to_string(), remaining_percent);
-            return false;
+            return Some("reset_cooldown");
         }
     }
 
     if state.last_reset_trigger_timestamps.get(model_key) == Some(&reset_at.to_string()) {
-        state.last_reset_remaining.insert(model_key.to_string(), remaining_percent);
-        return false;
+        return Some("already_triggered");
     }
 
+    None
+}
+
+fn should_trigger_on_reset(state: &mut ResetState, model_key: &str, reset_at: &str, remaining_percent: i32) -> bool {
+    let blocked = reset_block_reason(state, model_key, reset_at, remaining_percent).is_some();
     state.last_reset_remaining.insert(model_key.to_string(), remaining_percent);
-    true
+    !blocked
 }
 
 fn mark_reset_triggered(state: &mut ResetState, model_key: &str, reset_at: &str) {
@@ -403,6 +926,32 @@ fn mark_reset_triggered(state: &mut ResetState, model_key: &str, reset_at: &str)
     state.last_reset_trigger_at.insert(model_key.to_string(), chrono::Utc::now().timestamp_millis());
 }
 
+/// 计划计算的起点：优先使用本地记录的执行时间，避免被前端同步覆盖导致重复执行
+fn schedule_cursor(snapshot: &SchedulerState, task: &WakeupTask, now: DateTime<Local>) -> DateTime<Local> {
+    let local_last_run = snapshot.last_executed_at.get(&task.id).copied();
+    let last_slot = snapshot.last_slot_at.get(&task.id).copied();
+    local_last_run
+        .or(task.last_run_at)
+        .max(last_slot)
+        .and_then(|ts| Local.timestamp_millis_opt(ts).single())
+        .unwrap_or_else(|| now - chrono::Duration::minutes(1))
+}
+
+/// 当前是否处于时间窗口外的兜底执行时间点
+fn is_fallback_due(task: &WakeupTask, zoned_now: NaiveDateTime) -> bool {
+    let current_minutes = (zoned_now.hour() as i32) * 60 + zoned_now.minute() as i32;
+    task.schedule
+        .fallback_times
+        .iter()
+        .filter_map(|time| parse_time_to_minutes(time))
+        .any(|minutes| (current_minutes - minutes).abs() <= 1)
+}
+
+fn is_outside_time_window(task: &WakeupTask, zoned_now: NaiveDateTime) -> bool {
+    task.schedule.time_window_enabled
+        && !is_in_time_window(task.schedule.time_window_start.as_ref(), task.schedule.time_window_end.as_ref(), zoned_now)
+}
+
 async fn run_scheduler_once(app: &AppHandle) {
     let snapshot = {
         let guard = state().lock().expect("wakeup state lock");
@@ -428,41 +977,48 @@ async fn run_scheduler_once(app: &AppHandle) {
             continue;
         }
 
-        // 优先使用本地记录的执行时间，避免被前端同步覆盖导致重复执行
-        let local_last_run = snapshot.last_executed_at.get(&task.id).copied();
-        let after = local_last_run
-            .or(task.last_run_at)
-            .and_then(|ts| Local.timestamp_millis_opt(ts).single())
-            .unwrap_or_else(|| now - chrono::Duration::minutes(1));
+        let after = schedule_cursor(&snapshot, task, now);
 
-        let next_run = if let Some(expr) = &task.schedule.crontab {
-            next_crontab_time(expr, after)
-        } else {
-            next_run_time(&task.schedule, after)
+        // 只有到达预定时间才触发（不再提前30秒）
+        let Some(due) = collect_due_runs(&task.schedule, after, now) else {
+            continue;
         };
+        let latest = due.latest;
 
-        // 只有到达预定时间才触发（不再提前30秒）
-        if let Some(next_run) = next_run {
-            if next_run <= now {
-                run_task(app, task, "scheduled").await;
-            }
+        let on_time = (now - latest).num_milliseconds() <= MISSED_RUN_GRACE_MS;
+        let runs = match task.schedule.catch_up_policy {
+            CatchUpPolicy::Skip => usize::from(on_time),
+            CatchUpPolicy::Once => 1,
+            CatchUpPolicy::All => due.count.min(MAX_CATCH_UP_RUNS),
+        };
+        if due.count > runs {
+            modules::logger::log_info(&format!(
+                "[Wakeup] 任务 {} 错过 {} 次计划，按 {:?} 策略执行 {} 次",
+                task.name,
+                due.count - usize::from(on_time),
+                task.schedule.catch_up_policy,
+                runs
+            ));
+        }
+
+        {
+            let mut guard = state().lock().expect("wakeup state lock");
+            guard.last_slot_at.insert(task.id.clone(), latest.timestamp_millis());
+        }
+        if runs == 0 {
+            persist_state();
+            continue;
+        }
+        for _ in 0..runs {
+            run_task(app, task, "scheduled").await;
         }
     }
 }
 
 async fn handle_quota_reset_task(app: &AppHandle, task: &WakeupTask, now: DateTime<Local>) {
-    let mut should_run_fallback = false;
-    if task.schedule.time_window_enabled && !is_in_time_window(task.schedule.time_window_start.as_ref(), task.schedule.time_window_end.as_ref(), now) {
-        let current_minutes = (now.hour() as i32) * 60 + now.minute() as i32;
-        for time in &task.schedule.fallback_times {
-            if let Some(minutes) = parse_time_to_minutes(time) {
-                if (current_minutes - minutes).abs() <= 1 {
-                    should_run_fallback = true;
-                    break;
-                }
-            }
-        }
-        if should_run_fallback {
+    let zoned_now = task.schedule.timezone.to_naive(now);
+    if is_outside_time_window(task, zoned_now) {
+        if is_fallback_due(task, zoned_now) {
             run_task(app, task, "scheduled").await;
         }
         return;
@@ -511,10 +1067,275 @@ async fn handle_quota_reset_task(app: &AppHandle, task: &WakeupTask, now: DateTi
     };
 
     if !models_to_trigger.is_empty() {
+        persist_state();
         run_task_with_models(app, task, "quota_reset", models_to_trigger.into_iter().collect()).await;
     }
 }
 
+/// 演练：按当前配额与调度状态评估所有启用的任务，不发送任何请求
+pub fn dry_run() -> Result<WakeupDryRunReport, String> {
+    let snapshot = {
+        let guard = state().lock().expect("wakeup state lock");
+        guard.clone()
+    };
+    let accounts = modules::list_accounts()?;
+    Ok(build_dry_run(&snapshot, &accounts, Local::now()))
+}
+
+fn build_dry_run(
+    snapshot: &SchedulerState,
+    accounts: &[crate::models::Account],
+    now: DateTime<Local>,
+) -> WakeupDryRunReport {
+    let mut runs = Vec::new();
+    for task in snapshot.tasks.iter().filter(|task| task.enabled) {
+        let plans = if task.schedule.wake_on_reset {
+            plan_quota_reset_task(snapshot, task, accounts, now)
+        } else {
+            plan_scheduled_task(snapshot, task, now)
+        };
+
+        for (model_id, planned_at, will_trigger, reason, account_filter) in plans {
+            for email in &task.schedule.selected_accounts {
+                if let Some(filter) = &account_filter {
+                    if !filter.eq_ignore_ascii_case(email) {
+                        continue;
+                    }
+                }
+                let account = accounts.iter().find(|acc| acc.email.eq_ignore_ascii_case(email));
+                let (will_trigger, reason) = match account {
+                    Some(_) => (will_trigger && !snapshot.running_tasks.contains(&task.id), reason.to_string()),
+                    None => (false, "account_not_found".to_string()),
+                };
+                runs.push(WakeupPlannedRun {
+                    task_id: task.id.clone(),
+                    task_name: task.name.clone(),
+                    account_id: account.map(|acc| acc.id.clone()),
+                    account_email: email.clone(),
+                    model_id: model_id.clone(),
+                    planned_at,
+                    will_trigger: snapshot.enabled && will_trigger,
+                    reason,
+                });
+            }
+        }
+    }
+
+    WakeupDryRunReport {
+        scheduler_enabled: snapshot.enabled,
+        generated_at: now.timestamp_millis(),
+        runs,
+    }
+}
+
+/// (模型, 计划时间, 是否触发, 原因, 限定账号)
+type PlanItem = (String, Option<i64>, bool, &'static str, Option<String>);
+
+fn plan_scheduled_task(snapshot: &SchedulerState, task: &WakeupTask, now: DateTime<Local>) -> Vec<PlanItem> {
+    let after = schedule_cursor(snapshot, task, now);
+    let due = collect_due_runs(&task.schedule, after, now);
+    let is_crontab = task
+        .schedule
+        .crontab
+        .as_deref()
+        .is_some_and(|expr| !expr.trim().is_empty());
+
+    let (planned_at, will_trigger, reason) = match due.map(|due| due.latest) {
+        Some(latest) => {
+            let on_time = (now - latest).num_milliseconds() <= MISSED_RUN_GRACE_MS;
+            match (on_time, task.schedule.catch_up_policy) {
+                (true, _) if is_crontab => (Some(latest.timestamp_millis()), true, "crontab"),
+                (true, _) => (Some(latest.timestamp_millis()), true, "scheduled"),
+                (false, CatchUpPolicy::Skip) => (
+                    next_scheduled_time(&task.schedule, now).map(|next| next.timestamp_millis()),
+                    false,
+                    "missed_skipped",
+                ),
+                (false, _) => (Some(now.timestamp_millis()), true, "catch_up"),
+            }
+        }
+        None => {
+            let next = next_scheduled_time(&task.schedule, now).map(|next| next.timestamp_millis());
+            let reason = if next.is_none() {
+                "no_upcoming_run"
+            } else if is_crontab {
+                "crontab"
+            } else {
+                "scheduled"
+            };
+            (next, false, reason)
+        }
+    };
+
+    task.schedule
+        .selected_models
+        .iter()
+        .map(|model| (model.clone(), planned_at, will_trigger, reason, None))
+        .collect()
+}
+
+fn plan_quota_reset_task(
+    snapshot: &SchedulerState,
+    task: &WakeupTask,
+    accounts: &[crate::models::Account],
+    now: DateTime<Local>,
+) -> Vec<PlanItem> {
+    let zoned_now = task.schedule.timezone.to_naive(now);
+    if is_outside_time_window(task, zoned_now) {
+        let due = is_fallback_due(task, zoned_now);
+        let planned_at = if due {
+            Some(now.timestamp_millis())
+        } else {
+            next_fallback_time(task, now).map(|next| next.timestamp_millis())
+        };
+        return task
+            .schedule
+            .selected_models
+            .iter()
+            .map(|model| (model.clone(), planned_at, due, "fallback_time", None))
+            .collect();
+    }
+
+    // 模拟 handle_quota_reset_task 的判定，作用于状态副本
+    let mut reset_state = snapshot.reset_states.get(&task.id).cloned().unwrap_or_default();
+    let mut triggered: HashSet<String> = HashSet::new();
+    let mut plans = Vec::new();
+    for model_id in &task.schedule.selected_models {
+        for email in &task.schedule.selected_accounts {
+            let Some(account) = accounts.iter().find(|acc| acc.email.eq_ignore_ascii_case(email)) else {
+                continue;
+            };
+            let quota = account
+                .quota
+                .as_ref()
+                .and_then(|q| q.models.iter().find(|item| item.name == *model_id));
+            let Some(quota) = quota else {
+                plans.push((model_id.clone(), None, false, "model_quota_missing", Some(email.clone())));
+                continue;
+            };
+            let reset_at = DateTime::parse_from_rfc3339(&quota.reset_time)
+                .ok()
+                .map(|dt| dt.timestamp_millis());
+            match reset_block_reason(&reset_state, model_id, &quota.reset_time, quota.percentage) {
+                None => {
+                    mark_reset_triggered(&mut reset_state, model_id, &quota.reset_time);
+                    triggered.insert(model_id.clone());
+                    plans.push((model_id.clone(), Some(now.timestamp_millis()), true, "quota_reset", Some(email.clone())));
+                }
+                Some(reason) => plans.push((model_id.clone(), reset_at, false, reason, Some(email.clone()))),
+            }
+        }
+    }
+
+    // 任一账号触发即对任务的全部账号执行该模型
+    let mut expanded = Vec::new();
+    for model_id in &triggered {
+        expanded.push((model_id.clone(), Some(now.timestamp_millis()), true, "quota_reset", None));
+    }
+    plans.retain(|(model_id, ..)| !triggered.contains(model_id));
+    expanded.extend(plans);
+    expanded
+}
+
+fn next_fallback_time(task: &WakeupTask, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let tz = &task.schedule.timezone;
+    let base_date = tz.to_naive(now).date();
+    (0..2)
+        .flat_map(|day_offset| {
+            let date = base_date + chrono::Duration::days(day_offset);
+            task.schedule
+                .fallback_times
+                .iter()
+                .filter_map(move |time| build_datetime(tz, date, time))
+        })
+        .filter(|candidate| *candidate > now)
+        .min()
+}
+
+fn global_request_limit() -> Arc<Semaphore> {
+    GLOBAL_REQUEST_LIMIT
+        .lock()
+        .expect("wakeup global limit lock")
+        .get_or_insert_with(|| {
+            let limit = modules::config::get_user_config().wakeup_max_concurrency.max(1);
+            Arc::new(Semaphore::new(limit as usize))
+        })
+        .clone()
+}
+
+/// 保存全局并发上限并立即替换信号量，返回生效的上限
+pub fn set_max_concurrency(limit: u32) -> Result<u32, String> {
+    let limit = limit.max(1);
+    let current = modules::config::get_user_config();
+    if current.wakeup_max_concurrency != limit {
+        modules::config::save_user_config(&modules::config::UserConfig {
+            wakeup_max_concurrency: limit,
+            ..current
+        })?;
+    }
+    *GLOBAL_REQUEST_LIMIT.lock().expect("wakeup global limit lock") = Some(Arc::new(Semaphore::new(limit as usize)));
+    modules::logger::log_info(&format!("[Wakeup] 全局并发上限已设置为 {}", limit));
+    Ok(limit)
+}
+
+/// 预约账号的下一个请求时间，返回需要等待的时长
+fn reserve_account_slot(account_id: &str, spacing_ms: u64) -> Duration {
+    if spacing_ms == 0 {
+        return Duration::ZERO;
+    }
+    let now = Instant::now();
+    let mut slots = ACCOUNT_NEXT_SLOT
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .expect("wakeup account slot lock");
+    let slot = slots.get(account_id).copied().filter(|slot| *slot > now).unwrap_or(now);
+    slots.insert(account_id.to_string(), slot + Duration::from_millis(spacing_ms));
+    slot - now
+}
+
+/// 第 `attempt` 次重试（从 0 开始）前的等待时长，按基准时长翻倍并封顶
+fn retry_delay_ms(backoff_ms: u64, attempt: u32) -> u64 {
+    backoff_ms
+        .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+        .min(MAX_RETRY_BACKOFF_MS)
+}
+
+/// 按任务的重试策略发送单个唤醒请求（受全局并发与账号间隔限制）
+async fn trigger_with_retry(
+    task: &WakeupTask,
+    account_id: &str,
+    model: &str,
+    prompt: &str,
+    max_tokens: u32,
+) -> Result<modules::wakeup::WakeupResponse, String> {
+    let mut attempt: u32 = 0;
+    loop {
+        let wait = reserve_account_slot(account_id, task.schedule.account_spacing_ms);
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+
+        let result = {
+            let limit = global_request_limit();
+            let _permit = limit.acquire().await.expect("wakeup global semaphore");
+            modules::wakeup::trigger_wakeup(account_id, model, prompt, max_tokens).await
+        };
+
+        match result {
+            Err(err) if attempt < task.schedule.retry_count && err.is_retryable() => {
+                let delay = retry_delay_ms(task.schedule.retry_backoff_ms, attempt);
+                attempt += 1;
+                modules::logger::log_warn(&format!(
+                    "[Wakeup] 任务 {} 请求失败，{}ms 后重试 ({}/{}): {}",
+                    task.name, delay, attempt, task.schedule.retry_count, err
+                ));
+                sleep(Duration::from_millis(delay)).await;
+            }
+            other => return other.map_err(|e| e.message),
+        }
+    }
+}
+
 async fn run_task(app: &AppHandle, task: &WakeupTask, trigger_source: &str) {
     run_task_with_models(app, task, trigger_source, task.schedule.selected_models.clone()).await;
 }
@@ -553,31 +1374,47 @@ async fn run_task_with_models(app: &AppHandle, task: &WakeupTask, trigger_source
         .unwrap_or_else(|| DEFAULT_PROMPT.to_string());
     let max_tokens = normalize_max_tokens(task.schedule.max_output_tokens);
 
-    let mut history: Vec<modules::wakeup_history::WakeupHistoryItem> = Vec::new();
-    for account in &selected_accounts {
-        for model in &models {
-            let started = chrono::Utc::now();
-            let result = modules::wakeup::trigger_wakeup(&account.id, model, &prompt, max_tokens).await;
-            let duration = chrono::Utc::now().signed_duration_since(started).num_milliseconds().max(0) as u64;
-            let (success, message) = match result {
-                Ok(resp) => (true, Some(resp.reply)),
-                Err(err) => (false, Some(err.to_string())),
-            };
-            history.push(modules::wakeup_history::WakeupHistoryItem {
-                id: format!("{}-{}", chrono::Utc::now().timestamp_millis(), history.len()),
-                timestamp: chrono::Utc::now().timestamp_millis(),
-                trigger_type: "auto".to_string(),
-                trigger_source: trigger_source.to_string(),
-                task_name: Some(task.name.clone()),
-                account_email: account.email.clone(),
-                model_id: model.clone(),
-                prompt: Some(prompt.clone()),
-                success,
-                message,
-                duration: Some(duration),
-            });
-        }
-    }
+    let task_limit = Arc::new(Semaphore::new(task.schedule.max_concurrency));
+    let jobs: Vec<_> = selected_accounts
+        .iter()
+        .flat_map(|account| models.iter().map(move |model| (*account, model)))
+        .map(|(account, model)| {
+            let task_limit = task_limit.clone();
+            let prompt = prompt.clone();
+            async move {
+                let _permit = task_limit.acquire().await.expect("wakeup task semaphore");
+                let started = chrono::Utc::now();
+                let result = trigger_with_retry(task, &account.id, model, &prompt, max_tokens).await;
+                let duration = chrono::Utc::now().signed_duration_since(started).num_milliseconds().max(0) as u64;
+                let (success, message, tokens) = match result {
+                    Ok(resp) => (
+                        true,
+                        Some(resp.reply),
+                        (resp.prompt_tokens, resp.completion_tokens, resp.total_tokens),
+                    ),
+                    Err(err) => (false, Some(err), (None, None, None)),
+                };
+                modules::wakeup_history::WakeupHistoryItem {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    timestamp: chrono::Utc::now().timestamp_millis(),
+                    trigger_type: "auto".to_string(),
+                    trigger_source: trigger_source.to_string(),
+                    task_id: Some(task.id.clone()),
+                    task_name: Some(task.name.clone()),
+                    account_email: account.email.clone(),
+                    model_id: model.clone(),
+                    prompt: Some(prompt),
+                    success,
+                    message,
+                    duration: Some(duration),
+                    prompt_tokens: tokens.0,
+                    completion_tokens: tokens.1,
+                    total_tokens: tokens.2,
+                }
+            }
+        })
+        .collect();
+    let history = futures::future::join_all(jobs).await;
 
     {
         let mut guard = state().lock().expect("wakeup state lock");
@@ -592,8 +1429,9 @@ async fn run_task_with_models(app: &AppHandle, task: &WakeupTask, trigger_source
         // 记录本地执行时间，防止被前端同步覆盖导致重复执行
         guard.last_executed_at.insert(task.id.clone(), executed_at);
     }
+    persist_state();
 
-    // 写入历史文件
+    // 写入历史数据库
     if let Err(e) = modules::wakeup_history::add_history_items(history.clone()) {
         modules::logger::log_error(&format!("写入唤醒历史失败: {}", e));
     }
@@ -615,3 +1453,180 @@ struct WakeupTaskResultPayload {
 }
 
 // (no local helpers)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(value: serde_json::Value) -> Result<ScheduleConfigNormalized, String> {
+        normalize_schedule(serde_json::from_value(value).unwrap())
+    }
+
+    fn task(id: &str, enabled: bool, schedule: serde_json::Value) -> WakeupTask {
+        build_task(WakeupTaskInput {
+            id: id.to_string(),
+            name: id.to_string(),
+            enabled,
+            created_at: 0,
+            last_run_at: None,
+            schedule: serde_json::from_value(schedule).unwrap(),
+        })
+        .unwrap()
+    }
+
+    fn account(email: &str, models: &[(&str, i32)]) -> crate::models::Account {
+        let token = crate::models::TokenData::new(String::new(), String::new(), 3600, None, None, None);
+        let mut account = crate::models::Account::new(format!("id-{}", email), email.to_string(), token);
+        let mut quota = crate::models::QuotaData::new();
+        for (name, percentage) in models {
+            quota.add_model(name.to_string(), *percentage, "2026-01-01T00:00:00Z".to_string());
+        }
+        account.update_quota(quota);
+        account
+    }
+
+    #[test]
+    fn dry_run_plans_scheduled_tasks_and_skips_disabled_ones() {
+        let now = Local::now();
+        let due = task("due", true, serde_json::json!({
+            "repeatMode": "daily",
+            "selectedModels": ["gemini"],
+            "selectedAccounts": ["a@x.com", "missing@x.com"],
+            "crontab": "* * * * *",
+        }));
+        let disabled = task("disabled", false, serde_json::json!({
+            "repeatMode": "daily",
+            "selectedModels": ["gemini"],
+            "selectedAccounts": ["a@x.com"],
+        }));
+        let mut snapshot = SchedulerState {
+            enabled: true,
+            tasks: vec![due, disabled],
+            ..Default::default()
+        };
+        snapshot.last_slot_at.insert("due".to_string(), (now - chrono::Duration::minutes(2)).timestamp_millis());
+        let accounts = vec![account("a@x.com", &[])];
+
+        let report = build_dry_run(&snapshot, &accounts, now);
+        assert!(report.runs.iter().all(|run| run.task_id == "due"));
+        assert_eq!(report.runs.len(), 2);
+        let found = &report.runs[0];
+        assert_eq!((found.account_id.as_deref(), found.will_trigger, found.reason.as_str()), (Some("id-a@x.com"), true, "crontab"));
+        let missing = &report.runs[1];
+        assert_eq!((missing.will_trigger, missing.reason.as_str()), (false, "account_not_found"));
+
+        // 调度器关闭或任务正在运行时不会触发
+        snapshot.running_tasks.insert("due".to_string());
+        assert!(!build_dry_run(&snapshot, &accounts, now).runs[0].will_trigger);
+        snapshot.running_tasks.clear();
+        snapshot.enabled = false;
+        assert!(build_dry_run(&snapshot, &accounts, now).runs.iter().all(|run| !run.will_trigger));
+    }
+
+    #[test]
+    fn dry_run_plans_quota_reset_tasks() {
+        let now = Local::now();
+        let reset = task("reset", true, serde_json::json!({
+            "repeatMode": "daily",
+            "selectedModels": ["full", "partial", "unknown"],
+            "selectedAccounts": ["a@x.com", "b@x.com"],
+            "wakeOnReset": true,
+        }));
+        let snapshot = SchedulerState {
+            enabled: true,
+            tasks: vec![reset],
+            ..Default::default()
+        };
+        let accounts = vec![
+            account("a@x.com", &[("full", 100), ("partial", 40)]),
+            account("b@x.com", &[("full", 20), ("partial", 60)]),
+        ];
+
+        let report = build_dry_run(&snapshot, &accounts, now);
+        let runs_for = |model: &str| report.runs.iter().filter(|run| run.model_id == model).collect::<Vec<_>>();
+
+        // 任一账号满额即对任务的全部账号触发
+        let full = runs_for("full");
+        assert_eq!(full.len(), 2);
+        assert!(full.iter().all(|run| run.will_trigger && run.reason == "quota_reset"));
+
+        let partial = runs_for("partial");
+        assert_eq!(partial.len(), 2);
+        assert!(partial.iter().all(|run| !run.will_trigger && run.reason == "quota_not_full"));
+
+        let unknown = runs_for("unknown");
+        assert_eq!(unknown.len(), 2);
+        assert!(unknown.iter().all(|run| run.reason == "model_quota_missing"));
+    }
+
+    #[test]
+    fn retry_backoff_doubles_and_caps() {
+        assert_eq!(retry_delay_ms(2000, 0), 2000);
+        assert_eq!(retry_delay_ms(2000, 1), 4000);
+        assert_eq!(retry_delay_ms(2000, 3), 16000);
+        assert_eq!(retry_delay_ms(2000, 10), MAX_RETRY_BACKOFF_MS);
+        assert_eq!(retry_delay_ms(2000, 80), MAX_RETRY_BACKOFF_MS);
+        assert_eq!(retry_delay_ms(0, 3), 0);
+
+        let error = |status: Option<u16>| modules::wakeup::WakeupError {
+            status: status.map(|code| reqwest::StatusCode::from_u16(code).unwrap()),
+            message: "唤醒请求失败".to_string(),
+        };
+        assert!(error(Some(429)).is_retryable());
+        assert!(error(Some(503)).is_retryable());
+        assert!(!error(Some(400)).is_retryable());
+        assert!(!error(Some(401)).is_retryable());
+        assert!(!error(None).is_retryable());
+    }
+
+    #[test]
+    fn account_spacing_queues_requests_per_account() {
+        let account = format!("spacing-{}", uuid::Uuid::new_v4());
+        let other = format!("spacing-{}", uuid::Uuid::new_v4());
+        assert_eq!(reserve_account_slot(&account, 0), Duration::ZERO);
+        assert_eq!(reserve_account_slot(&account, 1000), Duration::ZERO);
+
+        let second = reserve_account_slot(&account, 1000);
+        assert!(second > Duration::from_millis(900) && second <= Duration::from_millis(1000));
+        let third = reserve_account_slot(&account, 1000);
+        assert!(third > Duration::from_millis(1900) && third <= Duration::from_millis(2000));
+
+        // 其他账号互不影响
+        assert_eq!(reserve_account_slot(&other, 1000), Duration::ZERO);
+    }
+
+    #[test]
+    fn capped_catch_up_uses_latest_missed_slot() {
+        let schedule = schedule(serde_json::json!({
+            "repeatMode": "daily",
+            "selectedModels": [],
+            "crontab": "* * * * *",
+        }))
+        .unwrap();
+        let now = Local::now();
+        let due = collect_due_runs(&schedule, now - chrono::Duration::days(3), now).unwrap();
+        assert_eq!(due.count, MAX_DUE_SCAN);
+        assert!((now - due.latest).num_seconds() < 60);
+        assert!(collect_due_runs(&schedule, due.latest, now).is_none());
+    }
+
+    #[test]
+    fn timezone_accepts_iana_names_and_rejects_invalid() {
+        let base = serde_json::json!({ "repeatMode": "daily", "selectedModels": [] });
+        let with_tz = |tz: &str| {
+            let mut value = base.clone();
+            value["timezone"] = serde_json::Value::String(tz.to_string());
+            schedule(value)
+        };
+        assert!(matches!(with_tz("Asia/Shanghai").unwrap().timezone, ScheduleTimezone::Named(_)));
+        assert!(matches!(with_tz("+05:30").unwrap().timezone, ScheduleTimezone::Fixed(_)));
+        assert!(with_tz("Mars/Olympus").is_err());
+
+        let tz = ScheduleTimezone::parse(Some("America/New_York")).unwrap();
+        // 夏令时开始时 02:30 不存在
+        let gap = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap().and_hms_opt(2, 30, 0).unwrap();
+        assert!(tz.to_local(gap).is_none());
+        let noon = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(tz.to_naive(tz.to_local(noon).unwrap()), noon);
+    }
+}
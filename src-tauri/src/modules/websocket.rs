@@ -2,24 +2,51 @@
 //! 提供本地 WebSocket 服务供 VS Code 扩展实时通信
 
 use futures_util::{SinkExt, StreamExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
 use tokio_tungstenite::tungstenite::Message;
 
-use super::config::{PORT_RANGE, get_preferred_port, init_server_status};
+use crate::models::codex::CodexQuota;
+use crate::models::copilot::CopilotQuota;
+use super::config::{PORT_RANGE, get_preferred_port, get_ws_auth_tokens, init_server_status};
+
+/// 协议版本（不兼容变更时递增）
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// 服务端支持的能力，随 `event.ready` 下发
+const CAPABILITIES: [&str; 6] = [
+    "auth",
+    "scopes",
+    "providers",
+    "subscribe",
+    "quota_events",
+    "schema",
+];
 
 /// 消息类型
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "payload")]
 pub enum WsMessage {
     // ============ 事件通知（Tools -> 扩展） ============
     /// 服务就绪
     #[serde(rename = "event.ready")]
-    Ready { version: String },
+    Ready {
+        version: String,
+        #[serde(default)]
+        protocol_version: u32,
+        #[serde(default)]
+        capabilities: Vec<String>,
+        /// 当前连接获得的权限
+        #[serde(default)]
+        scopes: Vec<String>,
+    },
     
     /// 数据已变更，请刷新
     #[serde(rename = "event.data_changed")]
@@ -31,16 +58,34 @@ pub enum WsMessage {
     
     /// 账号切换完成
     #[serde(rename = "event.account_switched")]
-    AccountSwitched { account_id: String, email: String },
+    AccountSwitched {
+        account_id: String,
+        email: String,
+        #[serde(default)]
+        provider: AccountProvider,
+    },
     
     /// 切换账号错误
     #[serde(rename = "event.switch_error")]
-    SwitchError { message: String },
+    SwitchError {
+        message: String,
+        #[serde(default)]
+        provider: AccountProvider,
+    },
 
     /// 唤醒功能互斥开关
     #[serde(rename = "event.wakeup_override")]
     WakeupOverride { enabled: bool },
 
+    /// 单个账号配额已刷新
+    #[serde(rename = "event.quota_updated")]
+    QuotaUpdated {
+        provider: AccountProvider,
+        account_id: String,
+        email: String,
+        quota: ProviderQuota,
+    },
+
     // ============ 请求（扩展 -> Tools） ============
     /// 请求获取账号列表
     #[serde(rename = "request.get_accounts")]
@@ -79,6 +124,42 @@ pub enum WsMessage {
         email: String,
     },
     
+    /// 请求获取指定平台的账号列表（含配额）
+    #[serde(rename = "request.get_provider_accounts")]
+    GetProviderAccounts {
+        request_id: String,
+        provider: AccountProvider,
+    },
+
+    /// 请求切换指定平台的账号
+    #[serde(rename = "request.switch_provider_account")]
+    SwitchProviderAccount {
+        request_id: String,
+        provider: AccountProvider,
+        account_id: String,
+    },
+
+    /// 请求获取指定平台账号的配额（refresh=true 时先从上游刷新）
+    #[serde(rename = "request.get_provider_quota")]
+    GetProviderQuota {
+        request_id: String,
+        provider: AccountProvider,
+        account_id: String,
+        #[serde(default)]
+        refresh: bool,
+    },
+
+    /// 订阅事件类型（替换当前订阅；支持 `*` 与 `event.*` 这类前缀通配）
+    #[serde(rename = "request.subscribe")]
+    Subscribe {
+        request_id: String,
+        events: Vec<String>,
+    },
+
+    /// 请求获取协议 JSON Schema
+    #[serde(rename = "request.get_schema")]
+    GetSchema { request_id: String },
+
     /// 通知数据已变更
     #[serde(rename = "request.data_changed")]
     NotifyDataChanged { source: String },
@@ -108,6 +189,38 @@ pub enum WsMessage {
         current_account_id: Option<String>,
     },
     
+    /// 指定平台账号列表响应
+    #[serde(rename = "response.provider_accounts")]
+    ProviderAccountsResponse {
+        request_id: String,
+        provider: AccountProvider,
+        accounts: Vec<ProviderAccountInfo>,
+        current_account_id: Option<String>,
+    },
+
+    /// 指定平台账号配额响应
+    #[serde(rename = "response.provider_quota")]
+    ProviderQuotaResponse {
+        request_id: String,
+        provider: AccountProvider,
+        account_id: String,
+        quota: ProviderQuota,
+    },
+
+    /// 协议 JSON Schema 响应
+    #[serde(rename = "response.schema")]
+    SchemaResponse {
+        request_id: String,
+        schema: serde_json::Value,
+    },
+
+    /// 订阅结果
+    #[serde(rename = "response.subscribed")]
+    SubscribedResponse {
+        request_id: String,
+        events: Vec<String>,
+    },
+
     /// 当前账号响应
     #[serde(rename = "response.current_account")]
     CurrentAccountResponse {
@@ -131,7 +244,7 @@ pub enum WsMessage {
 }
 
 /// 账号信息（用于 WebSocket 传输）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AccountInfo {
     pub id: String,
     pub email: String,
@@ -144,8 +257,45 @@ pub struct AccountInfo {
     pub subscription_tier: Option<String>,
 }
 
+/// 账号所属平台
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountProvider {
+    #[default]
+    Antigravity,
+    Codex,
+    Copilot,
+}
+
+/// 各平台的配额数据
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProviderQuota {
+    Antigravity(crate::models::QuotaData),
+    Codex(CodexQuota),
+    Copilot(CopilotQuota),
+}
+
+/// 多平台账号信息（用于 WebSocket 传输）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProviderAccountInfo {
+    pub id: String,
+    /// 邮箱（Copilot 无邮箱时为 GitHub 用户名）
+    pub email: String,
+    pub name: Option<String>,
+    pub is_current: bool,
+    pub disabled: bool,
+    pub last_used: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<ProviderQuota>,
+}
+
 /// 账号信息（包含 Token，用于同步）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AccountTokenInfo {
     pub id: String,
     pub email: String,
@@ -162,16 +312,80 @@ pub struct AccountTokenInfo {
     pub project_id: Option<String>,
 }
 
+/// 客户端权限范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WsScope {
+    /// 读取账号列表等非敏感信息
+    Read,
+    /// 读取账号 Token
+    Tokens,
+    /// 切换/添加/删除账号、修改设置
+    Write,
+}
+
+impl WsScope {
+    const ALL: [WsScope; 3] = [WsScope::Read, WsScope::Tokens, WsScope::Write];
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "read" => Some(WsScope::Read),
+            "tokens" => Some(WsScope::Tokens),
+            "write" => Some(WsScope::Write),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            WsScope::Read => "read",
+            WsScope::Tokens => "tokens",
+            WsScope::Write => "write",
+        }
+    }
+}
+
+/// 允许的 Origin 前缀（VS Code 扩展宿主不带 Origin，Webview 带以下 scheme）
+const ALLOWED_ORIGIN_PREFIXES: [&str; 2] = ["vscode-webview://", "vscode-file://"];
+
 /// 已连接的客户端信息
 #[derive(Debug)]
 struct Client {
     _addr: SocketAddr,
+    _scopes: HashSet<WsScope>,
+}
+
+/// 广播消息（附带事件类型，供各连接按订阅过滤）
+#[derive(Debug, Clone)]
+struct BroadcastItem {
+    event: String,
+    json: String,
+}
+
+/// 单个连接的会话状态
+struct ClientSession {
+    scopes: HashSet<WsScope>,
+    /// 订阅的事件类型，None 表示全部
+    subscriptions: Option<Vec<String>>,
+    /// 后台任务完成后发回本连接的响应（已序列化）
+    replies: mpsc::UnboundedSender<String>,
+}
+
+impl ClientSession {
+    fn wants(&self, event: &str) -> bool {
+        let Some(subscriptions) = &self.subscriptions else {
+            return true;
+        };
+        subscriptions.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => event.starts_with(prefix),
+            None => pattern == event,
+        })
+    }
 }
 
 /// WebSocket 服务状态
 pub struct WsServer {
     /// 广播发送器
-    tx: broadcast::Sender<String>,
+    tx: broadcast::Sender<BroadcastItem>,
     /// 已连接的客户端
     clients: Arc<RwLock<HashMap<SocketAddr, Client>>>,
 }
@@ -186,11 +400,20 @@ impl WsServer {
         }
     }
     
-    /// 广播消息给所有客户端
+    /// 广播消息给所有订阅了该事件的客户端
     pub fn broadcast(&self, message: WsMessage) {
-        if let Ok(json) = serde_json::to_string(&message) {
-            let _ = self.tx.send(json);
-        }
+        let Ok(value) = serde_json::to_value(&message) else {
+            return;
+        };
+        let event = value
+            .get("type")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let _ = self.tx.send(BroadcastItem {
+            event,
+            json: value.to_string(),
+        });
     }
 }
 
@@ -234,12 +457,34 @@ pub fn broadcast_language_changed(language: &str, source: &str) {
 
 /// 广播账号切换完成
 pub fn broadcast_account_switched(account_id: &str, email: &str) {
+    broadcast_provider_account_switched(AccountProvider::Antigravity, account_id, email);
+}
+
+/// 广播指定平台的账号切换完成
+pub fn broadcast_provider_account_switched(provider: AccountProvider, account_id: &str, email: &str) {
     let server = get_server();
     server.broadcast(WsMessage::AccountSwitched {
         account_id: account_id.to_string(),
         email: email.to_string(),
+        provider,
+    });
+    crate::modules::logger::log_info(&format!("[WS] 广播账号切换: {:?}", provider));
+}
+
+/// 广播单个账号的配额更新
+pub fn broadcast_quota_updated(
+    provider: AccountProvider,
+    account_id: &str,
+    email: &str,
+    quota: ProviderQuota,
+) {
+    let server = get_server();
+    server.broadcast(WsMessage::QuotaUpdated {
+        provider,
+        account_id: account_id.to_string(),
+        email: email.to_string(),
+        quota,
     });
-    crate::modules::logger::log_info("[WS] 广播账号切换");
 }
 
 /// 广播唤醒互斥开关
@@ -297,6 +542,10 @@ pub async fn start_server() {
     }
     
     crate::modules::logger::log_info(&format!("[WS] WebSocket 服务已启动: ws://127.0.0.1:{}", port));
+
+    // 本地 HTTP API 使用紧随 WebSocket 端口范围之后的独立范围
+    let http_start = preferred_port.saturating_add(PORT_RANGE);
+    super::http_api::start_server(http_start, http_start.saturating_add(PORT_RANGE));
     
     let server = get_server();
     
@@ -306,9 +555,185 @@ pub async fn start_server() {
     }
 }
 
+/// 常量时间比较，避免通过响应时间猜测令牌
+fn token_matches(expected: &str, provided: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), provided.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 从查询字符串中取出参数值（已 URL 解码）
+pub(crate) fn query_value(query: Option<&str>, name: &str) -> Option<String> {
+    query?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        if key != name {
+            return None;
+        }
+        urlencoding::decode(value).ok().map(|v| v.into_owned())
+    })
+}
+
+fn query_param(request: &Request, name: &str) -> Option<String> {
+    query_value(request.uri().query(), name)
+}
+
+fn header_value<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+fn reject_response(status: StatusCode, reason: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(reason.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+/// 校验客户端凭据，返回客户端获得的权限范围（WebSocket 与 HTTP API 共用）
+///
+/// - Origin：缺省（扩展宿主/本地进程）或 VS Code Webview 才允许，拒绝浏览器网页
+/// - 令牌：`?token=` 查询参数或 `Authorization: Bearer` 头，来自 server.json
+/// - 权限：完整令牌拥有全部权限，只读令牌仅 `read`；可用 `?scopes=read,write` 进一步收窄
+pub(crate) fn authorize_client(
+    origin: Option<&str>,
+    token: Option<String>,
+    authorization: Option<&str>,
+    requested_scopes: Option<String>,
+) -> Result<HashSet<WsScope>, (u16, &'static str)> {
+    if let Some(origin) = origin {
+        if !ALLOWED_ORIGIN_PREFIXES.iter().any(|prefix| origin.starts_with(prefix)) {
+            return Err((403, "origin not allowed"));
+        }
+    }
+
+    let provided = token.or_else(|| {
+        authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|value| value.trim().to_string())
+    });
+    let Some(provided) = provided else {
+        return Err((401, "missing token"));
+    };
+
+    let tokens = get_ws_auth_tokens();
+    let granted: HashSet<WsScope> = if token_matches(&tokens.full, &provided) {
+        WsScope::ALL.into_iter().collect()
+    } else if token_matches(&tokens.read, &provided) {
+        [WsScope::Read].into_iter().collect()
+    } else {
+        return Err((401, "invalid token"));
+    };
+
+    match requested_scopes {
+        Some(requested) => {
+            let requested: HashSet<WsScope> =
+                requested.split(',').filter_map(WsScope::parse).collect();
+            Ok(granted.intersection(&requested).copied().collect())
+        }
+        None => Ok(granted),
+    }
+}
+
+fn authorize_handshake(request: &Request) -> Result<HashSet<WsScope>, (StatusCode, &'static str)> {
+    authorize_client(
+        header_value(request, "Origin"),
+        query_param(request, "token"),
+        header_value(request, "Authorization"),
+        query_param(request, "scopes"),
+    )
+    .map_err(|(status, reason)| {
+        (
+            StatusCode::from_u16(status).unwrap_or(StatusCode::UNAUTHORIZED),
+            reason,
+        )
+    })
+}
+
+/// 消息所需的权限（None 表示无需权限）
+fn required_scope(msg: &WsMessage) -> Option<WsScope> {
+    match msg {
+        WsMessage::Ping | WsMessage::Subscribe { .. } | WsMessage::GetSchema { .. } => None,
+        WsMessage::GetAccounts { .. }
+        | WsMessage::GetCurrentAccount { .. }
+        | WsMessage::GetProviderAccounts { .. }
+        | WsMessage::GetProviderQuota { .. } => Some(WsScope::Read),
+        WsMessage::GetAccountsWithTokens { .. } => Some(WsScope::Tokens),
+        _ => Some(WsScope::Write),
+    }
+}
+
+fn request_id_of(msg: &WsMessage) -> String {
+    match msg {
+        WsMessage::GetAccounts { request_id }
+        | WsMessage::GetAccountsWithTokens { request_id }
+        | WsMessage::GetCurrentAccount { request_id }
+        | WsMessage::SetLanguage { request_id, .. }
+        | WsMessage::AddAccount { request_id, .. }
+        | WsMessage::DeleteAccountByEmail { request_id, .. }
+        | WsMessage::GetProviderAccounts { request_id, .. }
+        | WsMessage::SwitchProviderAccount { request_id, .. }
+        | WsMessage::GetProviderQuota { request_id, .. }
+        | WsMessage::Subscribe { request_id, .. }
+        | WsMessage::GetSchema { request_id } => request_id.clone(),
+        _ => String::new(),
+    }
+}
+
+/// 生成协议的 JSON Schema（由 `WsMessage` 派生）
+pub fn protocol_schema() -> serde_json::Value {
+    let schema = schemars::schema_for!(WsMessage);
+    let mut value = serde_json::to_value(schema).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.insert("x-protocol-version".to_string(), PROTOCOL_VERSION.into());
+    }
+    value
+}
+
+/// 解析客户端消息，失败时返回 (request_id, 错误信息)，request_id 尽量从原始消息中提取
+fn parse_client_message(text: &str) -> Result<WsMessage, (String, String)> {
+    let value: serde_json::Value = serde_json::from_str(text)
+        .map_err(|e| (String::new(), format!("解析消息失败: {}", e)))?;
+
+    let request_id = value
+        .get("payload")
+        .and_then(|payload| payload.get("request_id"))
+        .and_then(|id| id.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let message_type = value
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    serde_json::from_value(value).map_err(|e| {
+        let error = if e.to_string().contains("unknown variant") || message_type.is_empty() {
+            format!("不支持的消息类型: {}", message_type)
+        } else {
+            format!("消息格式错误 ({}): {}", message_type, e)
+        };
+        (request_id, error)
+    })
+}
+
 /// 处理单个客户端连接
 async fn handle_connection(server: Arc<WsServer>, stream: TcpStream, addr: SocketAddr) {
-    let ws_stream = match tokio_tungstenite::accept_async(stream).await {
+    let mut scopes = HashSet::new();
+    // 回调签名由 tungstenite 决定
+    #[allow(clippy::result_large_err)]
+    let callback = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        match authorize_handshake(request) {
+            Ok(granted) => {
+                scopes = granted;
+                Ok(response)
+            }
+            Err((status, reason)) => {
+                crate::modules::logger::log_warn(&format!("[WS] 拒绝连接 {}: {}", addr, reason));
+                Err(reject_response(status, reason))
+            }
+        }
+    };
+    let ws_stream = match tokio_tungstenite::accept_hdr_async(stream, callback).await {
         Ok(ws) => ws,
         Err(e) => {
             crate::modules::logger::log_error(&format!("[WS] 握手失败 {}: {}", addr, e));
@@ -316,12 +741,18 @@ async fn handle_connection(server: Arc<WsServer>, stream: TcpStream, addr: Socke
         }
     };
     
-    crate::modules::logger::log_info(&format!("[WS] 新连接: {}", addr));
+    let mut scope_names: Vec<&str> = scopes.iter().map(WsScope::as_str).collect();
+    scope_names.sort_unstable();
+    crate::modules::logger::log_info(&format!(
+        "[WS] 新连接: {} (scopes={})",
+        addr,
+        scope_names.join(",")
+    ));
     
     // 添加客户端
     {
         let mut clients = server.clients.write().await;
-        clients.insert(addr, Client { _addr: addr });
+        clients.insert(addr, Client { _addr: addr, _scopes: scopes.clone() });
     }
     
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
@@ -329,6 +760,9 @@ async fn handle_connection(server: Arc<WsServer>, stream: TcpStream, addr: Socke
     // 发送 Ready 消息
     let ready_msg = WsMessage::Ready {
         version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol_version: PROTOCOL_VERSION,
+        capabilities: CAPABILITIES.iter().map(|c| c.to_string()).collect(),
+        scopes: scope_names.iter().map(|s| s.to_string()).collect(),
     };
     if let Ok(json) = serde_json::to_string(&ready_msg) {
         let _ = ws_sender.send(Message::Text(json.into())).await;
@@ -336,6 +770,12 @@ async fn handle_connection(server: Arc<WsServer>, stream: TcpStream, addr: Socke
     
     // 订阅广播
     let mut broadcast_rx = server.tx.subscribe();
+    let (reply_tx, mut reply_rx) = mpsc::unbounded_channel::<String>();
+    let mut session = ClientSession {
+        scopes,
+        subscriptions: None,
+        replies: reply_tx,
+    };
     
     loop {
         tokio::select! {
@@ -343,7 +783,7 @@ async fn handle_connection(server: Arc<WsServer>, stream: TcpStream, addr: Socke
             msg = ws_receiver.next() => {
                 match msg {
                     Some(Ok(Message::Text(text))) => {
-                        if let Err(e) = handle_client_message(&server, &mut ws_sender, &text).await {
+                        if let Err(e) = handle_client_message(&server, &mut ws_sender, &mut session, &text).await {
                             crate::modules::logger::log_error(&format!("[WS] 处理消息失败: {}", e));
                         }
                     }
@@ -359,10 +799,19 @@ async fn handle_connection(server: Arc<WsServer>, stream: TcpStream, addr: Socke
                     _ => {}
                 }
             }
+            // 发送后台任务的响应
+            Some(json) = reply_rx.recv() => {
+                if ws_sender.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
             // 发送广播消息
             msg = broadcast_rx.recv() => {
-                if let Ok(json) = msg {
-                    if ws_sender.send(Message::Text(json.into())).await.is_err() {
+                if let Ok(item) = msg {
+                    if !session.wants(&item.event) {
+                        continue;
+                    }
+                    if ws_sender.send(Message::Text(item.json.into())).await.is_err() {
                         break;
                     }
                 }
@@ -386,10 +835,33 @@ async fn handle_client_message(
         tokio_tungstenite::WebSocketStream<TcpStream>,
         Message,
     >,
+    session: &mut ClientSession,
     text: &str,
 ) -> Result<(), String> {
-    let msg: WsMessage = serde_json::from_str(text)
-        .map_err(|e| format!("解析消息失败: {}", e))?;
+    let msg = match parse_client_message(text) {
+        Ok(msg) => msg,
+        Err((request_id, error)) => {
+            let response = WsMessage::ErrorResponse { request_id, error };
+            crate::modules::logger::log_warn(&format!("[WS] 无法处理的消息: {}", text.chars().take(200).collect::<String>()));
+            let json = serde_json::to_string(&response).unwrap();
+            sender.send(Message::Text(json.into())).await
+                .map_err(|e| format!("发送响应失败: {}", e))?;
+            return Ok(());
+        }
+    };
+
+    if let Some(scope) = required_scope(&msg) {
+        if !session.scopes.contains(&scope) {
+            let response = WsMessage::ErrorResponse {
+                request_id: request_id_of(&msg),
+                error: format!("权限不足: 需要 {} 权限", scope.as_str()),
+            };
+            let json = serde_json::to_string(&response).unwrap();
+            sender.send(Message::Text(json.into())).await
+                .map_err(|e| format!("发送响应失败: {}", e))?;
+            return Ok(());
+        }
+    }
     
     match msg {
         WsMessage::Ping => {
@@ -476,23 +948,16 @@ async fn handle_client_message(
             crate::modules::logger::log_info("[WS] 收到切换请求");
             
             // 异步执行切换
-            let server_clone = server.tx.clone();
             tokio::spawn(async move {
                 match crate::modules::account::switch_account_internal(&account_id).await {
                     Ok(account) => {
-                        let msg = WsMessage::AccountSwitched {
-                            account_id: account.id,
-                            email: account.email,
-                        };
-                        if let Ok(json) = serde_json::to_string(&msg) {
-                            let _ = server_clone.send(json);
-                        }
+                        broadcast_account_switched(&account.id, &account.email);
                     }
                     Err(e) => {
-                        let msg = WsMessage::SwitchError { message: e };
-                        if let Ok(json) = serde_json::to_string(&msg) {
-                            let _ = server_clone.send(json);
-                        }
+                        get_server().broadcast(WsMessage::SwitchError {
+                            message: e,
+                            provider: AccountProvider::Antigravity,
+                        });
                     }
                 }
             });
@@ -570,6 +1035,93 @@ async fn handle_client_message(
             }
         }
         
+        WsMessage::GetProviderAccounts { request_id, provider } => {
+            let response = match get_provider_accounts_info(provider) {
+                Ok((accounts, current_account_id)) => WsMessage::ProviderAccountsResponse {
+                    request_id,
+                    provider,
+                    accounts,
+                    current_account_id,
+                },
+                Err(e) => WsMessage::ErrorResponse { request_id, error: e },
+            };
+
+            if let Ok(json) = serde_json::to_string(&response) {
+                sender.send(Message::Text(json.into())).await
+                    .map_err(|e| format!("发送响应失败: {}", e))?;
+            }
+        }
+
+        WsMessage::GetProviderQuota { request_id, provider, account_id, refresh } => {
+            let response = match get_provider_quota(provider, &account_id, refresh).await {
+                Ok(quota) => WsMessage::ProviderQuotaResponse {
+                    request_id,
+                    provider,
+                    account_id,
+                    quota,
+                },
+                Err(e) => WsMessage::ErrorResponse { request_id, error: e },
+            };
+
+            if let Ok(json) = serde_json::to_string(&response) {
+                sender.send(Message::Text(json.into())).await
+                    .map_err(|e| format!("发送响应失败: {}", e))?;
+            }
+        }
+
+        WsMessage::SwitchProviderAccount { request_id, provider, account_id } => {
+            crate::modules::logger::log_info(&format!("[WS] 收到 {:?} 切换请求", provider));
+
+            // 切换可能需要关闭/重启客户端，在后台执行，避免阻塞本连接的消息循环；
+            // 切换完成后由各平台的切换流程广播 event.account_switched
+            let replies = session.replies.clone();
+            tokio::spawn(async move {
+                let response = match switch_provider_account(provider, &account_id).await {
+                    Ok(email) => WsMessage::SuccessResponse {
+                        request_id,
+                        message: format!("已切换到 {}", email),
+                    },
+                    Err(e) => {
+                        get_server().broadcast(WsMessage::SwitchError {
+                            message: e.clone(),
+                            provider,
+                        });
+                        WsMessage::ErrorResponse { request_id, error: e }
+                    }
+                };
+                if let Ok(json) = serde_json::to_string(&response) {
+                    let _ = replies.send(json);
+                }
+            });
+        }
+
+        WsMessage::GetSchema { request_id } => {
+            let response = WsMessage::SchemaResponse {
+                request_id,
+                schema: protocol_schema(),
+            };
+            if let Ok(json) = serde_json::to_string(&response) {
+                sender.send(Message::Text(json.into())).await
+                    .map_err(|e| format!("发送响应失败: {}", e))?;
+            }
+        }
+
+        WsMessage::Subscribe { request_id, events } => {
+            let events: Vec<String> = events
+                .into_iter()
+                .map(|event| event.trim().to_string())
+                .filter(|event| !event.is_empty())
+                .collect();
+            crate::modules::logger::log_info(&format!("[WS] 更新订阅: {}", events.join(",")));
+            session.subscriptions = Some(events.clone());
+
+            let response = WsMessage::SubscribedResponse { request_id, events };
+            if let Ok(json) = serde_json::to_string(&response) {
+                sender.send(Message::Text(json.into())).await
+                    .map_err(|e| format!("发送响应失败: {}", e))?;
+            }
+        }
+
         WsMessage::NotifyDataChanged { source } => {
             crate::modules::logger::log_info(&format!("[WS] 收到数据变更通知: {}", source));
             // 广播给其他客户端
@@ -583,7 +1135,7 @@ async fn handle_client_message(
 }
 
 /// 获取账号列表信息
-fn get_accounts_info() -> Result<(Vec<AccountInfo>, Option<String>), String> {
+pub(crate) fn get_accounts_info() -> Result<(Vec<AccountInfo>, Option<String>), String> {
     use crate::modules::account;
     
     let accounts = account::list_accounts()?;
@@ -606,8 +1158,144 @@ fn get_accounts_info() -> Result<(Vec<AccountInfo>, Option<String>), String> {
     Ok((account_infos, current_id))
 }
 
+/// 获取指定平台的账号列表信息
+pub(crate) fn get_provider_accounts_info(
+    provider: AccountProvider,
+) -> Result<(Vec<ProviderAccountInfo>, Option<String>), String> {
+    match provider {
+        AccountProvider::Antigravity => {
+            let accounts = crate::modules::account::list_accounts()?;
+            let current_id = crate::modules::account::get_current_account_id()?;
+            let infos = accounts
+                .into_iter()
+                .map(|acc| ProviderAccountInfo {
+                    is_current: current_id.as_ref() == Some(&acc.id),
+                    plan: acc.quota.as_ref().and_then(|q| q.subscription_tier.clone()),
+                    id: acc.id,
+                    email: acc.email,
+                    name: acc.name,
+                    disabled: acc.disabled,
+                    last_used: acc.last_used,
+                    tags: Vec::new(),
+                    quota: acc.quota.map(ProviderQuota::Antigravity),
+                })
+                .collect();
+            Ok((infos, current_id))
+        }
+        AccountProvider::Codex => {
+            use crate::modules::codex_account;
+            let current_id = codex_account::get_current_account().map(|acc| acc.id);
+            let infos = codex_account::list_accounts()
+                .into_iter()
+                .map(|acc| ProviderAccountInfo {
+                    is_current: current_id.as_ref() == Some(&acc.id),
+                    id: acc.id,
+                    email: acc.email,
+                    name: None,
+                    disabled: acc.disabled,
+                    last_used: acc.last_used,
+                    plan: acc.plan_type,
+                    tags: acc.tags.unwrap_or_default(),
+                    quota: acc.quota.map(ProviderQuota::Codex),
+                })
+                .collect();
+            Ok((infos, current_id))
+        }
+        AccountProvider::Copilot => {
+            use crate::modules::copilot_account;
+            let current_id = copilot_account::get_current_account().map(|acc| acc.id);
+            let infos = copilot_account::list_accounts()
+                .into_iter()
+                .map(|acc| ProviderAccountInfo {
+                    is_current: current_id.as_ref() == Some(&acc.id),
+                    id: acc.id,
+                    email: acc.email.unwrap_or_else(|| acc.username.clone()),
+                    name: Some(acc.username),
+                    disabled: acc.disabled,
+                    last_used: acc.last_used,
+                    plan: acc.plan,
+                    tags: acc.tags.unwrap_or_default(),
+                    quota: acc.quota.map(ProviderQuota::Copilot),
+                })
+                .collect();
+            Ok((infos, current_id))
+        }
+    }
+}
+
+/// 获取指定平台账号的配额
+pub(crate) async fn get_provider_quota(
+    provider: AccountProvider,
+    account_id: &str,
+    refresh: bool,
+) -> Result<ProviderQuota, String> {
+    match provider {
+        AccountProvider::Antigravity => {
+            use crate::modules::account;
+            let mut account = account::load_account(account_id)?;
+            if refresh {
+                let quota = account::fetch_quota_with_retry(&mut account, true)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                account::update_account_quota(account_id, quota.clone())?;
+                return Ok(ProviderQuota::Antigravity(quota));
+            }
+            account
+                .quota
+                .map(ProviderQuota::Antigravity)
+                .ok_or_else(|| "暂无配额数据".to_string())
+        }
+        AccountProvider::Codex => {
+            if refresh {
+                return crate::modules::codex_quota::refresh_account_quota(account_id)
+                    .await
+                    .map(ProviderQuota::Codex);
+            }
+            crate::modules::codex_account::load_account(account_id)
+                .ok_or_else(|| format!("账号不存在: {}", account_id))?
+                .quota
+                .map(ProviderQuota::Codex)
+                .ok_or_else(|| "暂无配额数据".to_string())
+        }
+        AccountProvider::Copilot => {
+            if refresh {
+                return crate::modules::copilot_quota::refresh_account_quota(account_id)
+                    .await
+                    .map(ProviderQuota::Copilot);
+            }
+            crate::modules::copilot_account::load_account(account_id)
+                .ok_or_else(|| format!("账号不存在: {}", account_id))?
+                .quota
+                .map(ProviderQuota::Copilot)
+                .ok_or_else(|| "暂无配额数据".to_string())
+        }
+    }
+}
+
+/// 切换指定平台的账号，返回切换后的邮箱
+pub(crate) async fn switch_provider_account(provider: AccountProvider, account_id: &str) -> Result<String, String> {
+    match provider {
+        AccountProvider::Antigravity => {
+            let account = crate::modules::account::switch_account_internal(account_id).await?;
+            broadcast_account_switched(&account.id, &account.email);
+            Ok(account.email)
+        }
+        AccountProvider::Codex => {
+            let app = crate::get_app_handle().ok_or("应用未初始化")?;
+            crate::commands::codex::switch_codex_account(app.clone(), account_id.to_string())
+                .await
+                .map(|account| account.email)
+        }
+        AccountProvider::Copilot => {
+            crate::commands::copilot::switch_copilot_account(account_id.to_string())
+                .await
+                .map(|account| account.username)
+        }
+    }
+}
+
 /// 获取账号列表信息（包含 Token）
-fn get_accounts_with_tokens_info() -> Result<(Vec<AccountTokenInfo>, Option<String>), String> {
+pub(crate) fn get_accounts_with_tokens_info() -> Result<(Vec<AccountTokenInfo>, Option<String>), String> {
     use crate::modules::account;
 
     let accounts = account::list_accounts()?;
@@ -635,7 +1323,7 @@ fn get_accounts_with_tokens_info() -> Result<(Vec<AccountTokenInfo>, Option<Stri
 }
 
 /// 获取当前账号信息
-fn get_current_account_info() -> Result<Option<AccountInfo>, String> {
+pub(crate) fn get_current_account_info() -> Result<Option<AccountInfo>, String> {
     use crate::modules::account;
     
     let current = account::get_current_account()?;
@@ -739,6 +1427,16 @@ fn handle_set_language(language: &str, source: Option<&str>) -> Result<String, S
         antigravity_app_path: current.antigravity_app_path,
         codex_app_path: current.codex_app_path,
         opencode_sync_on_switch: current.opencode_sync_on_switch,
+        wakeup_max_concurrency: current.wakeup_max_concurrency,
+        endpoints: current.endpoints,
+        account_sync_folder: current.account_sync_folder,
+        account_sync_passphrase: current.account_sync_passphrase,
+        background_token_refresh: current.background_token_refresh,
+        health_check_interval_minutes: current.health_check_interval_minutes,
+        vscode_app_path: current.vscode_app_path,
+        copilot_config_dir: current.copilot_config_dir,
+        tray_quota_icon: current.tray_quota_icon,
+        tray_quota_group: current.tray_quota_group,
     };
 
     config::save_user_config(&new_config)?;
@@ -747,3 +1445,105 @@ fn handle_set_language(language: &str, source: Option<&str>) -> Result<String, S
 
     Ok(format!("语言已更新为 {}", normalized))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_message_keeps_request_id() {
+        let (request_id, error) = parse_client_message(
+            r#"{"type":"request.does_not_exist","payload":{"request_id":"r1"}}"#,
+        )
+        .unwrap_err();
+        assert_eq!(request_id, "r1");
+        assert!(error.contains("request.does_not_exist"));
+    }
+
+    #[test]
+    fn malformed_payload_reports_type() {
+        let (_, error) = parse_client_message(r#"{"type":"request.get_accounts","payload":{}}"#).unwrap_err();
+        assert!(error.contains("request.get_accounts"));
+    }
+
+    fn set_language() -> WsMessage {
+        WsMessage::SetLanguage {
+            request_id: "r1".to_string(),
+            language: "en".to_string(),
+            source: None,
+        }
+    }
+
+    #[test]
+    fn read_token_cannot_send_write_messages() {
+        let tokens = get_ws_auth_tokens();
+        let scopes = authorize_client(None, Some(tokens.read.clone()), None, None).unwrap();
+        assert_eq!(scopes, HashSet::from([WsScope::Read]));
+        assert_eq!(required_scope(&set_language()), Some(WsScope::Write));
+        assert!(!scopes.contains(&WsScope::Write));
+        assert_eq!(
+            required_scope(&WsMessage::GetAccounts { request_id: "r1".to_string() }),
+            Some(WsScope::Read)
+        );
+        assert_eq!(
+            required_scope(&WsMessage::GetAccountsWithTokens { request_id: "r1".to_string() }),
+            Some(WsScope::Tokens)
+        );
+        assert_eq!(required_scope(&WsMessage::Ping), None);
+
+        // 只读令牌无法通过 scopes 参数提升权限
+        let scopes = authorize_client(None, Some(tokens.read), None, Some("read,write".to_string())).unwrap();
+        assert_eq!(scopes, HashSet::from([WsScope::Read]));
+    }
+
+    #[test]
+    fn full_token_grants_all_scopes_and_can_be_narrowed() {
+        let tokens = get_ws_auth_tokens();
+        let bearer = format!("Bearer {}", tokens.full);
+        let scopes = authorize_client(None, None, Some(&bearer), None).unwrap();
+        assert_eq!(scopes, HashSet::from(WsScope::ALL));
+
+        let scopes = authorize_client(None, Some(tokens.full), None, Some("read".to_string())).unwrap();
+        assert_eq!(scopes, HashSet::from([WsScope::Read]));
+    }
+
+    #[test]
+    fn rejects_missing_or_invalid_tokens() {
+        assert_eq!(authorize_client(None, None, None, None).unwrap_err(), (401, "missing token"));
+        assert_eq!(
+            authorize_client(None, Some("not-a-token".to_string()), None, None).unwrap_err(),
+            (401, "invalid token")
+        );
+        assert_eq!(
+            authorize_client(None, None, Some("Basic abc"), None).unwrap_err(),
+            (401, "missing token")
+        );
+    }
+
+    #[test]
+    fn only_vscode_origins_are_allowed() {
+        let tokens = get_ws_auth_tokens();
+        let authorize = |origin: Option<&str>| authorize_client(origin, Some(tokens.full.clone()), None, None);
+
+        assert!(authorize(Some("vscode-webview://abc123")).is_ok());
+        // 扩展宿主与本地进程不带 Origin
+        assert!(authorize(None).is_ok());
+        assert_eq!(authorize(Some("https://evil.example.com")).unwrap_err(), (403, "origin not allowed"));
+        assert_eq!(authorize(Some("null")).unwrap_err(), (403, "origin not allowed"));
+    }
+
+    #[test]
+    fn schema_lists_message_types() {
+        let schema = protocol_schema().to_string();
+        for name in [
+            "event.ready",
+            "event.quota_updated",
+            "request.get_provider_accounts",
+            "request.subscribe",
+            "response.schema",
+            "ping",
+        ] {
+            assert!(schema.contains(name), "schema missing {}", name);
+        }
+    }
+}
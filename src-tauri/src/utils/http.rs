@@ -1,4 +1,6 @@
-use reqwest::Client;
+use std::fmt;
+
+use reqwest::{Client, StatusCode};
 
 /// 创建统一配置的 HTTP 客户端
 pub fn create_client(timeout_secs: u64) -> Client {
@@ -7,3 +9,66 @@ pub fn create_client(timeout_secs: u64) -> Client {
         .build()
         .unwrap_or_else(|_| Client::new())
 }
+
+/// HTTP 请求失败，保留状态码与接口错误码供调用方区分 Token 失效、权限不足与网络错误
+#[derive(Debug, Clone, Default)]
+pub struct HttpError {
+    /// HTTP 状态码（请求未完成时为空）
+    pub status: Option<StatusCode>,
+    /// 响应体中的错误码（OAuth 的 `error` 或 `error.code`）
+    pub code: Option<String>,
+    /// 请求未能完成（连接失败、DNS 错误、超时等）
+    pub network: bool,
+    pub message: String,
+}
+
+impl HttpError {
+    /// 请求发送失败
+    pub fn network(message: String) -> Self {
+        Self { network: true, message, ..Self::default() }
+    }
+
+    /// 接口返回非成功状态码
+    pub fn response(status: StatusCode, body: &str, message: String) -> Self {
+        Self {
+            status: Some(status),
+            code: error_code(body),
+            network: false,
+            message,
+        }
+    }
+}
+
+/// 解析 `{"error": "invalid_grant"}` 或 `{"error": {"code": "..."}}` 形式的错误码
+fn error_code(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let error = value.get("error")?;
+    error
+        .as_str()
+        .or_else(|| error.get("code").and_then(|code| code.as_str()))
+        .map(str::to_string)
+}
+
+impl From<String> for HttpError {
+    fn from(message: String) -> Self {
+        Self { message, ..Self::default() }
+    }
+}
+
+impl From<&str> for HttpError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<HttpError> for String {
+    fn from(error: HttpError) -> Self {
+        error.message
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
@@ -19,6 +19,9 @@ import { UpdateNotification } from './components/UpdateNotification';
 import { CloseConfirmDialog } from './components/CloseConfirmDialog';
 import { Page } from './types/navigation';
 import { useAutoRefresh } from './hooks/useAutoRefresh';
+import { useAccountStore } from './stores/useAccountStore';
+import { useCodexAccountStore } from './stores/useCodexAccountStore';
+import { useCopilotAccountStore } from './stores/useCopilotAccountStore';
 import { changeLanguage, getCurrentLanguage, normalizeLanguage } from './i18n';
 
 import { DashboardPage } from './pages/DashboardPage';
@@ -38,6 +41,28 @@ type AppPathMissingDetail = {
   retry?: { kind: 'default' | 'instance'; instanceId?: string };
 };
 
+type DisabledAccountNotice = {
+  provider: 'antigravity' | 'codex' | 'copilot';
+  account_id: string;
+  email: string;
+  reason: string;
+};
+
+const PROVIDER_LABELS: Record<DisabledAccountNotice['provider'], string> = {
+  antigravity: 'Antigravity',
+  codex: 'Codex',
+  copilot: 'Copilot',
+};
+
+type CodexQuotaAlert = {
+  account_id: string;
+  email: string;
+  level: 'low' | 'exhausted';
+  hourly_percentage: number;
+  weekly_percentage: number;
+  code_review_percentage?: number | null;
+};
+
 const WAKEUP_ENABLED_KEY = 'agtools.wakeup.enabled';
 const TASKS_STORAGE_KEY = 'agtools.wakeup.tasks';
 
@@ -70,6 +95,8 @@ function App() {
   const [appPathMissing, setAppPathMissing] = useState<AppPathMissingDetail | null>(null);
   const [appPathSetting, setAppPathSetting] = useState(false);
   const [appPathDraft, setAppPathDraft] = useState('');
+  const [disabledAccounts, setDisabledAccounts] = useState<DisabledAccountNotice[]>([]);
+  const [quotaAlerts, setQuotaAlerts] = useState<CodexQuotaAlert[]>([]);
   
   // 启用自动刷新 hook
   useAutoRefresh();
@@ -373,6 +400,47 @@ function App() {
     }
   };
 
+  // 后台刷新发现刷新令牌失效时提示用户重新登录
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined;
+
+    listen<DisabledAccountNotice[]>('token:accounts_disabled', (event) => {
+      const items = Array.isArray(event.payload) ? event.payload : [];
+      if (items.length === 0) return;
+      setDisabledAccounts((prev) => [
+        ...prev.filter((prevItem) => !items.some((item) => item.account_id === prevItem.account_id)),
+        ...items,
+      ]);
+      useAccountStore.getState().fetchAccounts().catch(() => {});
+      useCodexAccountStore.getState().fetchAccounts().catch(() => {});
+      useCopilotAccountStore.getState().fetchAccounts().catch(() => {});
+    }).then((fn) => { unlisten = fn; });
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  // Codex 配额不足或耗尽时提醒用户
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined;
+
+    listen<CodexQuotaAlert>('codex:quota_alert', (event) => {
+      const alert = event.payload;
+      if (!alert || typeof alert.account_id !== 'string') return;
+      setQuotaAlerts((prev) => [...prev.filter((item) => item.account_id !== alert.account_id), alert]);
+      useCodexAccountStore.getState().fetchAccounts().catch(() => {});
+    }).then((fn) => { unlisten = fn; });
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
   // 监听窗口关闭请求事件
   useEffect(() => {
     let unlisten: UnlistenFn | undefined;
@@ -396,6 +464,7 @@ function App() {
           switch (target) {
             case 'overview':
             case 'codex':
+            case 'copilot':
             case 'settings':
               setPage(target as Page);
               break;
@@ -428,6 +497,77 @@ function App() {
         <CloseConfirmDialog onClose={() => setShowCloseDialog(false)} />
       )}
 
+      {disabledAccounts.length > 0 && (
+        <div className="modal-overlay">
+          <div className="modal" onClick={(e) => e.stopPropagation()} style={{ maxWidth: 520 }}>
+            <div className="modal-header">
+              <h2>{t('tokenRefresh.disabled.title', '账号已被禁用')}</h2>
+              <button
+                className="modal-close"
+                onClick={() => setDisabledAccounts([])}
+                aria-label={t('common.close', '关闭')}
+              >
+                <X />
+              </button>
+            </div>
+            <div className="modal-body">
+              <p style={{ margin: 0, color: 'var(--text-primary)' }}>
+                {t('tokenRefresh.disabled.desc', '以下账号的登录凭据已失效，请重新登录后再使用：')}
+              </p>
+              <ul style={{ marginTop: 12, marginBottom: 0, paddingLeft: 20 }}>
+                {disabledAccounts.map((item) => (
+                  <li key={`${item.provider}:${item.account_id}`} title={item.reason}>
+                    {PROVIDER_LABELS[item.provider] ?? item.provider} · {item.email}
+                  </li>
+                ))}
+              </ul>
+            </div>
+            <div className="modal-footer">
+              <button className="btn btn-primary" onClick={() => setDisabledAccounts([])}>
+                {t('common.confirm', '确认')}
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+
+      {quotaAlerts.length > 0 && (
+        <div className="modal-overlay">
+          <div className="modal" onClick={(e) => e.stopPropagation()} style={{ maxWidth: 520 }}>
+            <div className="modal-header">
+              <h2>{t('codex.quotaAlert.title', 'Codex 配额提醒')}</h2>
+              <button
+                className="modal-close"
+                onClick={() => setQuotaAlerts([])}
+                aria-label={t('common.close', '关闭')}
+              >
+                <X />
+              </button>
+            </div>
+            <div className="modal-body">
+              <ul style={{ margin: 0, paddingLeft: 20 }}>
+                {quotaAlerts.map((item) => (
+                  <li key={item.account_id}>
+                    {item.email} ·{' '}
+                    {item.level === 'exhausted'
+                      ? t('codex.quotaAlert.exhausted', '配额已耗尽')
+                      : t('codex.quotaAlert.low', '配额即将耗尽')}
+                    {` (5h ${item.hourly_percentage}% / ${t('codex.quotaAlert.weekly', '周')} ${item.weekly_percentage}%`}
+                    {item.code_review_percentage != null ? ` / Code Review ${item.code_review_percentage}%` : ''}
+                    {')'}
+                  </li>
+                ))}
+              </ul>
+            </div>
+            <div className="modal-footer">
+              <button className="btn btn-primary" onClick={() => setQuotaAlerts([])}>
+                {t('common.confirm', '确认')}
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+
       {appPathMissing && (
         <div className="modal-overlay">
           <div className="modal" onClick={(e) => e.stopPropagation()} style={{ maxWidth: 520 }}>
@@ -0,0 +1,37 @@
+/** OpenAI 兼容接口的 API Key 账号 */
+export interface ApiKeyAccount {
+  id: string;
+  label: string;
+  /** 接口基础地址（如 https://api.openai.com/v1） */
+  base_url: string;
+  api_key: string;
+  /** 自定义用量查询地址 */
+  usage_url?: string;
+  usage?: ApiKeyUsage | null;
+  tags?: string[];
+  created_at: number;
+  last_used: number;
+}
+
+/** API Key 用量（单位：美元） */
+export interface ApiKeyUsage {
+  total_granted?: number | null;
+  total_used?: number | null;
+  remaining?: number | null;
+  fetched_at: number;
+}
+
+/** 凭据写入目标结果 */
+export interface ApiKeySwitchTarget {
+  id: string;
+  name: string;
+  path: string;
+  updated: boolean;
+  error?: string;
+}
+
+/** 切换结果 */
+export interface ApiKeySwitchResult {
+  account: ApiKeyAccount;
+  targets: ApiKeySwitchTarget[];
+}
@@ -29,10 +29,31 @@ export interface CodexQuota {
   weekly_percentage: number;
   /** 周配额重置时间 (Unix timestamp) */
   weekly_reset_time?: number;
+  /** 5小时窗口长度（秒） */
+  hourly_window_seconds?: number;
+  /** 周窗口长度（秒） */
+  weekly_window_seconds?: number;
+  /** 是否允许继续请求 */
+  allowed?: boolean;
+  /** 是否已触发限额 */
+  limit_reached?: boolean;
+  /** 订阅类型 */
+  plan_type?: string;
+  /** Code Review 限额 */
+  code_review?: CodexRateLimit;
   /** 原始响应数据 */
   raw_data?: unknown;
 }
 
+/** Codex 单个速率限制 */
+export interface CodexRateLimit {
+  percentage: number;
+  reset_time?: number;
+  window_seconds?: number;
+  allowed: boolean;
+  limit_reached: boolean;
+}
+
 /** 获取订阅类型显示名称 */
 export function getCodexPlanDisplayName(planType?: string): string {
   if (!planType) return 'FREE';
@@ -5,9 +5,20 @@ export interface CopilotQuota {
   usage_items_count: number;
   copilot_plan?: string;
   quota_reset_date?: string;
+  snapshots?: CopilotQuotaSnapshot[];
   raw_data?: unknown;
 }
 
+export interface CopilotQuotaSnapshot {
+  quota_id: string;
+  entitlement?: number;
+  remaining?: number;
+  percent_remaining?: number;
+  unlimited: boolean;
+  overage_permitted: boolean;
+  overage_count: number;
+}
+
 export interface CopilotAccount {
   id: string;
   username: string;